@@ -53,7 +53,7 @@ fn handle_run(args: RunArgs) -> anyhow::Result<()> {
       if args.dump {
         eprintln!("{:#?}", hebi);
       }
-      crate::hebi::report_errors(&source, e);
+      crate::hebi::report_errors(e);
       anyhow::bail!("Failed to run {}", args.input.name());
     }
   }
@@ -67,7 +67,7 @@ fn handle_disassemble(input: InputArgs) -> anyhow::Result<()> {
   let chunk = match hebi.compile(&source) {
     Ok(chunk) => chunk,
     Err(e) => {
-      crate::hebi::report_errors(&source, e);
+      crate::hebi::report_errors(e);
       anyhow::bail!("Failed to disassemble {}", input.name());
     }
   };
@@ -7,11 +7,11 @@ pub fn build_hebi() -> Hebi {
   hebi
 }
 
-pub fn report_errors(source: &str, e: hebi::Error) {
+pub fn report_errors(e: hebi::Error) {
   let color = supports_color::on(supports_color::Stream::Stderr)
     .map(|c| c.has_basic)
     .unwrap_or(false);
-  eprintln!("{}", e.report(source, color));
+  eprintln!("{}", e.report(color));
 }
 
 mod parsing {
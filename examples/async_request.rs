@@ -36,7 +36,7 @@ get("https://jsonplaceholder.typicode.com/todos/1")
     Ok(result) => match result {
       Ok(value) => println!("Result is:\n{value}"),
       Err(e) => {
-        eprintln!("{}", e.report(source, true))
+        eprintln!("{}", e.report(true))
       }
     },
     Err(panic) => {
@@ -6,7 +6,10 @@ use indexmap::IndexMap;
 use super::opcode::symbolic::*;
 use super::opcode::{self as op, Instruction, Opcode};
 use super::operands::{Operand, Width};
-use crate::internal::object::{Any, ClassDescriptor, FunctionDescriptor, Ptr, Str};
+use super::spans::SpanTable;
+use crate::internal::object::{
+  Any, ClassDescriptor, EnumDescriptor, FunctionDescriptor, Int, Ptr, Str,
+};
 use crate::internal::value::constant::{Constant, NonNaNFloat};
 use crate::span::Span;
 
@@ -18,12 +21,7 @@ pub struct BytecodeBuilder {
 
   // TODO: encode spans into a flat buffer
   // use delta-encoding to make the bytes smaller
-  // NOTE: it's fine to store the data in a flat buffer, because
-  // we iterate over the bytecode array and each time we step
-  // through bytecode,  we can also step the span buffer iterator.
-  // for random access, positions in the buffer may be saved and
-  // restored at a later point.
-  spans: Vec<Span>,
+  spans: SpanTable,
 }
 
 pub struct BasicLabel {
@@ -106,13 +104,13 @@ impl BytecodeBuilder {
       constant_pool_builder: ConstantPoolBuilder::new(),
       unbound_jumps: 0,
 
-      spans: Vec::new(),
+      spans: SpanTable::new(),
     }
   }
 
   fn write(&mut self, instruction: impl Instruction, span: Span) {
+    self.spans.push(self.bytecode.len(), span);
     instruction.encode(&mut self.bytecode);
-    self.spans.push(span);
   }
 
   /// Emit an instruction.
@@ -187,6 +185,28 @@ impl BytecodeBuilder {
     )
   }
 
+  /// Emit a `PushHandler` instruction. Any error raised after this
+  /// instruction and before the matching `PopHandler` will resume execution
+  /// at `label`, with the caught error in the accumulator.
+  pub fn emit_push_handler(&mut self, label: &impl Label, span: impl Into<Span>) {
+    assert!(
+      !label.is_used(),
+      "more than one instruction refers to label {}",
+      label.name(),
+    );
+
+    // handler offsets are encoded the same way as forward jumps
+    self.unbound_jumps += 1;
+    label.set_referrer(self.bytecode.len());
+    let offset = self.constant_pool_builder().reserve();
+    self.write(
+      PushHandler {
+        offset: op::Offset(offset.0),
+      },
+      span.into(),
+    )
+  }
+
   /// Marks the current offset as a loop header and returns it for use as a
   /// target in `emit_jump_loop`.
   pub fn loop_header(&self) -> LoopHeader {
@@ -215,8 +235,12 @@ impl BytecodeBuilder {
     &mut self.constant_pool_builder
   }
 
-  pub fn finish(self) -> (Vec<u8>, Vec<Constant>) {
-    (self.bytecode, self.constant_pool_builder.constants)
+  pub fn finish(self) -> (Vec<u8>, Vec<Constant>, SpanTable) {
+    (
+      self.bytecode,
+      self.constant_pool_builder.constants,
+      self.spans,
+    )
   }
 
   fn patch_jump(&mut self, referrer_offset: usize, relative_offset: op::Offset) {
@@ -231,7 +255,7 @@ impl BytecodeBuilder {
         encoded_width = Width::Wide32;
         op = Opcode::new(self.bytecode[referrer_offset + 1]);
       }
-      v @ (Opcode::Jump | Opcode::JumpIfFalse) => {
+      v @ (Opcode::Jump | Opcode::JumpIfFalse | Opcode::PushHandler) => {
         encoded_width = Width::Normal;
         op = v;
       }
@@ -254,6 +278,7 @@ impl BytecodeBuilder {
       let new_op = match op {
         Opcode::Jump => Opcode::JumpConst as u8,
         Opcode::JumpIfFalse => Opcode::JumpIfFalseConst as u8,
+        Opcode::PushHandler => Opcode::PushHandlerConst as u8,
         _ => unreachable!(),
       };
       self.bytecode[opcode_offset] = new_op;
@@ -361,6 +386,8 @@ macro_rules! insert_constant_object {
 insert_constant_object!(Str, String);
 insert_constant_object!(FunctionDescriptor, Function);
 insert_constant_object!(ClassDescriptor, Class);
+insert_constant_object!(EnumDescriptor, Enum);
+insert_constant_object!(Int, Int);
 
 impl private::Sealed for NonNaNFloat {}
 impl InsertConstant for NonNaNFloat {
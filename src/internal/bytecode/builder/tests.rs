@@ -16,7 +16,7 @@ fn basic_emit() {
   }, 0..0);
   builder.emit(Print, 0..0);
 
-  let (bytecode, constants) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert_eq!(
     bytecode,
@@ -44,7 +44,7 @@ fn emit_constant() {
   builder.emit(LoadConst { idx: b }, 0..0);
   builder.emit(LoadConst { idx: c }, 0..0);
 
-  let (bytecode, constants) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert_eq!(
     bytecode,
@@ -79,7 +79,7 @@ fn emit_forward_jump_8bit() {
   builder.bind_label(test);
   builder.emit(Return, 0..0);
 
-  let (bytecode, constants ) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert_eq!(
     bytecode,
@@ -111,7 +111,7 @@ fn emit_forward_jump_8bit_overflow() {
   builder.bind_label(test);
   builder.emit(Return, 0..0);
 
-  let (bytecode, constants) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert_eq!(bytecode[..2], [Opcode::JumpConst as u8, /* index */ 0],);
   assert!(bytecode[2..256].iter().all(|v| *v == Opcode::Nop as u8));
@@ -140,7 +140,7 @@ fn emit_forward_jump_16bit() {
   builder.bind_label(test);
   builder.emit(Return, 0..0);
 
-  let (bytecode, _) = builder.finish();
+  let (bytecode, _, _) = builder.finish();
 
   assert_eq!(
     bytecode[..jump_len],
@@ -179,7 +179,7 @@ fn emit_forward_jump_16bit_overflow() {
   builder.bind_label(test);
   builder.emit(Return, 0..0);
 
-  let (bytecode, constants) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert_eq!(
     bytecode[..jump_len],
@@ -222,7 +222,7 @@ fn emit_forward_jump_32bit() {
   builder.bind_label(test);
   builder.emit(Return, 0..0);
 
-  let (bytecode, _) = builder.finish();
+  let (bytecode, _, _) = builder.finish();
 
   assert_eq!(
     bytecode[..jump_len],
@@ -255,7 +255,7 @@ fn emit_jump_loop() {
   builder.emit_jump_loop(&start, 0..0);
   builder.emit(Return, 0..0);
 
-  let (bytecode, constants) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert!(constants.is_empty());
   assert_eq!(
@@ -288,7 +288,7 @@ fn emit_multi_label() {
   builder.bind_label(labels);
   builder.emit(Return, 0..0);
 
-  let (bytecode, constants) = builder.finish();
+  let (bytecode, constants, _) = builder.finish();
 
   assert_eq!(
     bytecode,
@@ -308,3 +308,41 @@ fn emit_multi_label() {
 
   assert_snapshot!(Disassembly::new(&bytecode, &constants, 0, true).to_string());
 }
+
+#[rustfmt::skip]
+#[test]
+fn emit_spans() {
+  let mut builder = BytecodeBuilder::new();
+
+  builder.emit(LoadSmi { value: op::Smi(10) }, 0..2);
+  builder.emit(Store { reg: op::Register(0) }, 0..2);
+  builder.emit(LoadSmi { value: op::Smi(1000) }, 5..9);
+  builder.emit(Add { lhs: op::Register(0) }, 5..9);
+  builder.emit(Print, 10..15);
+  builder.emit(Return, 0..0);
+
+  let (bytecode, _, spans) = builder.finish();
+
+  assert_eq!(
+    bytecode,
+    [
+      Opcode::LoadSmi as u8, 10,
+      Opcode::Store as u8, 0,
+      Opcode::Wide16 as u8, Opcode::LoadSmi as u8, 232, 3,
+      Opcode::Add as u8, 0,
+      Opcode::Print as u8,
+      Opcode::Return as u8,
+    ]
+  );
+
+  assert_eq!(spans.len(), 4);
+  assert_eq!(spans.get(0), Some((0..2).into()));
+  assert_eq!(spans.get(3), Some((0..2).into()));
+  // the prefix and the instruction share a span
+  assert_eq!(spans.get(4), Some((5..9).into()));
+  assert_eq!(spans.get(5), Some((5..9).into()));
+  assert_eq!(spans.get(9), Some((5..9).into()));
+  assert_eq!(spans.get(10), Some((10..15).into()));
+  // instructions without a span
+  assert_eq!(spans.get(11), None);
+}
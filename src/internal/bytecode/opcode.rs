@@ -41,24 +41,45 @@ instructions! {
   MakeClassDerived(desc: Constant),
   MakeDataClass(desc: Constant, parts: Register),
   MakeDataClassDerived(desc: Constant, parts: Register),
+  MakeEnum(desc: Constant),
   // TODO: MakeListConst / MakeTableConst for statically known values
   MakeList(start: Register, count: Count),
   MakeListEmpty,
+  MakeTuple(start: Register, count: Count),
+  MakeTupleEmpty,
   MakeTable(start: Register, count: Count),
   MakeTableEmpty,
+  Concat(start: Register, count: Count),
+  CheckLen(len: Count),
+  Append(list: Register),
+  Extend(list: Register),
+  MatchSeq(len: Count),
+  MatchTable,
+  MatchKey(obj: Register),
+  MatchPayload(len: Count),
   Jump(offset: Offset),
   JumpConst(offset: Constant),
   JumpLoop(offset: Offset),
   JumpIfFalse(offset: Offset),
   JumpIfFalseConst(offset: Constant),
+  PushHandler(offset: Offset),
+  PushHandlerConst(offset: Constant),
+  PopHandler,
   Add(lhs: Register),
   Sub(lhs: Register),
   Mul(lhs: Register),
   Div(lhs: Register),
   Rem(lhs: Register),
   Pow(lhs: Register),
+  FloorDiv(lhs: Register),
+  BitAnd(lhs: Register),
+  BitOr(lhs: Register),
+  BitXor(lhs: Register),
+  Shl(lhs: Register),
+  Shr(lhs: Register),
   Inv,
   Not,
+  BitNot,
   CmpEq(lhs: Register),
   CmpNe(lhs: Register),
   CmpGt(lhs: Register),
@@ -72,10 +93,13 @@ instructions! {
   PrintN(start: Register, count: Count),
   Call(callee: Register, args: Count),
   Call0,
+  CallSpread(callee: Register),
+  CallNamed(callee: Register, named: Register),
   Import(path: Constant),
   FinalizeModule,
   Return,
   Yield,
+  Throw,
 }
 
 operand_type!(Register, u32, "r{v}");
@@ -184,6 +208,8 @@ pub trait Instruction: disasm::Disassemble + private::Sealed {
         | Opcode::JumpLoop
         | Opcode::JumpIfFalse
         | Opcode::JumpIfFalseConst
+        | Opcode::PushHandler
+        | Opcode::PushHandlerConst
     )
   }
 }
@@ -38,7 +38,7 @@ fn register_patching() {
 
   let map = vec![127usize; 65537];
 
-  let (mut bytecode, _) = builder.finish();
+  let (mut bytecode, _, _) = builder.finish();
 
   assert_eq!(
     bytecode,
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::span::{Source, Span};
+
+/// Maps bytecode offsets to the source spans of the instructions at those
+/// offsets.
+///
+/// Runs of instructions which share a span are stored as a single entry,
+/// keyed by the offset of the first instruction in the run.
+#[derive(Clone, Debug, Default)]
+pub struct SpanTable {
+  source: Option<Arc<Source>>,
+  entries: Vec<(usize, Span)>,
+}
+
+impl SpanTable {
+  pub fn new() -> Self {
+    Self {
+      source: None,
+      entries: Vec::new(),
+    }
+  }
+
+  /// The source which the spans in this table point into.
+  pub fn source(&self) -> Option<&Arc<Source>> {
+    self.source.as_ref()
+  }
+
+  pub fn set_source(&mut self, source: Arc<Source>) {
+    self.source = Some(source);
+  }
+
+  /// Record `span` for the instruction which starts at `offset`.
+  ///
+  /// Offsets must be pushed in increasing order.
+  pub fn push(&mut self, offset: usize, span: Span) {
+    if let Some((last_offset, last_span)) = self.entries.last() {
+      debug_assert!(*last_offset <= offset, "span table offsets out of order");
+      if *last_span == span {
+        return;
+      }
+    }
+    self.entries.push((offset, span));
+  }
+
+  /// Find the span of the instruction which contains `offset`.
+  ///
+  /// Returns `None` if there is no such instruction, or if it was emitted
+  /// without a span.
+  pub fn get(&self, offset: usize) -> Option<Span> {
+    let index = self.entries.partition_point(|(start, _)| *start <= offset);
+    let (_, span) = self.entries.get(index.checked_sub(1)?)?;
+    (!span.is_empty()).then_some(*span)
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+}
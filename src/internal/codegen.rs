@@ -5,6 +5,8 @@ mod expr;
 mod regalloc;
 mod stmt;
 
+use std::sync::Arc;
+
 use indexmap::{IndexMap, IndexSet};
 
 use self::regalloc::{RegAlloc, Register, Slice};
@@ -16,18 +18,19 @@ use super::object::function;
 use super::object::ptr::Ptr;
 use super::syntax::ast;
 use super::vm::global::Global;
-use crate::span::Span;
+use crate::span::{Source, Span};
 use crate::Cow;
 
 pub fn emit<'src>(
   global: Global,
   ast: &'src ast::Module<'src>,
   name: impl Into<Cow<'src, str>>,
+  source: Arc<Source>,
   is_root: bool,
 ) -> Ptr<object::ModuleDescriptor> {
   let name = name.into();
 
-  let mut module = State::new(global.clone(), ast, name.clone(), is_root).emit_module();
+  let mut module = State::new(global.clone(), ast, name.clone(), source, is_root).emit_module();
 
   let name = global.alloc(object::Str::owned(name));
   // NOTE: no need to handle `.upvalues` here,
@@ -53,6 +56,7 @@ impl<'src> State<'src> {
     global: Global,
     ast: &'src ast::Module<'src>,
     name: impl Into<Cow<'src, str>>,
+    source: Arc<Source>,
     is_root: bool,
   ) -> Self {
     Self {
@@ -60,11 +64,14 @@ impl<'src> State<'src> {
       ast,
       module: Module {
         is_root,
+        source: source.clone(),
         vars: IndexSet::new(),
         functions: vec![Function::new(
           global,
           name,
+          source,
           function::Params::default(),
+          Vec::new(),
           false,
           false,
         )],
@@ -242,7 +249,9 @@ impl<'src> State<'src> {
     self.module.functions.push(Function::new(
       self.global.clone(),
       func.name.lexeme(),
+      self.module.source.clone(),
       function::Params::from_ast_func(func),
+      func.params.pos.iter().map(|v| v.name.lexeme()).collect(),
       func.has_yield,
       is_init,
     ));
@@ -250,7 +259,8 @@ impl<'src> State<'src> {
     self.current_function().enter_scope();
 
     // allocate registers
-    let param_slice = self.alloc_register_slice(1 + func.params.pos.len());
+    let param_slice =
+      self.alloc_register_slice(1 + func.params.pos.len() + func.params.rest.is_some() as usize);
     let (callee, receiver, positional) = match func.params.has_self {
       true => (None, Some(param_slice.get(0)), param_slice.offset(1)),
       false => (Some(param_slice.get(0)), None, param_slice.offset(1)),
@@ -289,6 +299,9 @@ impl<'src> State<'src> {
     for (i, param) in func.params.pos.iter().enumerate() {
       self.declare_local(param.name.lexeme(), positional.get(i));
     }
+    if let Some(rest) = &func.params.rest {
+      self.declare_local(rest.lexeme(), positional.get(func.params.pos.len()));
+    }
 
     // emit body
     for stmt in func.body.iter() {
@@ -353,12 +366,14 @@ impl function::Params {
       has_self: func.params.has_self,
       min,
       max,
+      has_rest: func.params.rest.is_some(),
     }
   }
 }
 
 struct Module<'src> {
   is_root: bool,
+  source: Arc<Source>,
   vars: IndexSet<Ptr<object::Str>>,
   functions: Vec<Function<'src>>,
 }
@@ -376,16 +391,19 @@ struct Function<'src> {
   is_init: bool,
 
   name: Cow<'src, str>,
+  source: Arc<Source>,
   builder: BytecodeBuilder,
   regalloc: RegAlloc,
 
   params: function::Params,
+  param_names: Vec<Cow<'src, str>>,
   locals: IndexMap<(Scope, Cow<'src, str>), Register>,
   upvalues: IndexMap<Cow<'src, str>, Upvalue>,
   scope: Scope,
 
   is_in_opt_expr: bool,
   current_loop: Option<Loop>,
+  try_blocks: Vec<TryBlock<'src>>,
 
   inner_functions: Vec<Ptr<object::FunctionDescriptor>>,
 }
@@ -394,7 +412,9 @@ impl<'src> Function<'src> {
   fn new(
     global: Global,
     name: impl Into<Cow<'src, str>>,
+    source: Arc<Source>,
     params: function::Params,
+    param_names: Vec<Cow<'src, str>>,
     is_generator: bool,
     is_init: bool,
   ) -> Self {
@@ -405,10 +425,12 @@ impl<'src> Function<'src> {
       is_init,
 
       name: name.into(),
+      source,
       builder: BytecodeBuilder::new(),
       regalloc: RegAlloc::new(),
 
       params,
+      param_names,
       locals: IndexMap::new(),
       upvalues: IndexMap::new(),
 
@@ -416,6 +438,7 @@ impl<'src> Function<'src> {
 
       is_in_opt_expr: false,
       current_loop: None,
+      try_blocks: Vec::new(),
 
       inner_functions: Vec::new(),
     }
@@ -438,7 +461,12 @@ impl<'src> Function<'src> {
   }
 
   fn enter_loop_body(&mut self, start: LoopHeader, end: MultiLabel) -> Option<Loop> {
-    self.current_loop.replace(Loop { start, end })
+    let try_depth = self.try_blocks.len();
+    self.current_loop.replace(Loop {
+      start,
+      end,
+      try_depth,
+    })
   }
 
   fn leave_loop_body(&mut self, previous: Option<Loop>) -> Loop {
@@ -460,7 +488,8 @@ impl<'src> Function<'src> {
 
   fn finish(self) -> EmittedFunction<'src> {
     let (frame_size, register_map) = self.regalloc.finish();
-    let (mut bytecode, constants) = self.builder.finish();
+    let (mut bytecode, constants, mut spans) = self.builder.finish();
+    spans.set_source(self.source.clone());
 
     // patch registers in bytecode
     op::patch_registers(&mut bytecode, &register_map);
@@ -481,6 +510,11 @@ impl<'src> Function<'src> {
       self.global.intern(self.name.to_string()),
       self.is_generator,
       self.params,
+      self
+        .param_names
+        .iter()
+        .map(|name| self.global.intern(name.to_string()))
+        .collect(),
       self
         .upvalues
         .values()
@@ -492,6 +526,7 @@ impl<'src> Function<'src> {
       frame_size,
       bytecode,
       constants,
+      spans,
     ));
     let upvalues = Upvalues(self.upvalues);
 
@@ -531,6 +566,14 @@ enum Get {
 struct Loop {
   start: LoopHeader,
   end: MultiLabel,
+  /// Number of `try` blocks which were active when the loop was entered.
+  try_depth: usize,
+}
+
+/// A `try` block with an active handler.
+#[derive(Clone, Copy)]
+struct TryBlock<'src> {
+  finally: Option<&'src [ast::Stmt<'src>]>,
 }
 
 #[repr(transparent)]
@@ -14,6 +14,11 @@ impl<'src> State<'src> {
       ast::ExprKind::GetIndex(v) => self.emit_get_index_expr(v, expr.span),
       ast::ExprKind::SetIndex(v) => self.emit_set_index_expr(v, expr.span),
       ast::ExprKind::Call(v) => self.emit_call_expr(v, expr.span),
+      ast::ExprKind::FString(v) => self.emit_fstring_expr(v, expr.span),
+      ast::ExprKind::Comprehension(v) => self.emit_comprehension_expr(v, expr.span),
+      ast::ExprKind::Func(v) => self.emit_func_expr(v, expr.span),
+      // the parser only produces spreads as call arguments
+      ast::ExprKind::Spread(_) => unreachable!("spread outside of call arguments"),
       ast::ExprKind::GetSelf => self.emit_get_self_expr(expr.span),
       ast::ExprKind::GetSuper => self.emit_get_super_expr(expr.span),
     }
@@ -22,7 +27,15 @@ impl<'src> State<'src> {
   fn emit_literal_expr(&mut self, expr: &'src ast::Literal<'src>, span: Span) {
     match expr {
       ast::Literal::None => self.builder().emit(LoadNone, span),
-      ast::Literal::Int(v) => self.builder().emit(LoadSmi { value: op::Smi(*v) }, span),
+      ast::Literal::Int(v) => match i32::try_from(*v) {
+        Ok(v) => self.builder().emit(LoadSmi { value: op::Smi(v) }, span),
+        Err(_) => {
+          // ints outside of `i32` range are boxed
+          let int = self.global.alloc(object::Int::I64(*v));
+          let int = self.constant_value(int);
+          self.builder().emit(LoadConst { idx: int }, span);
+        }
+      },
       ast::Literal::Float(v) => {
         // float is 4 bits so cannot be stored inline,
         // but it is interned
@@ -61,6 +74,26 @@ impl<'src> State<'src> {
           register.access();
         } */
       }
+      ast::Literal::Tuple(tuple) => {
+        if tuple.is_empty() {
+          self.builder().emit(MakeTupleEmpty, span);
+          return;
+        }
+
+        let items = self.alloc_register_slice(tuple.len());
+
+        for (i, value) in tuple.iter().enumerate() {
+          self.emit_expr(value);
+          self.emit_store(items.get(i), value.span);
+        }
+        self.builder().emit(
+          MakeTuple {
+            start: items.access(0),
+            count: op::Count(tuple.len() as u32),
+          },
+          span,
+        );
+      }
       ast::Literal::Table(table) => {
         if table.is_empty() {
           self.builder().emit(MakeTableEmpty, span);
@@ -91,6 +124,89 @@ impl<'src> State<'src> {
     }
   }
 
+  fn emit_comprehension_expr(&mut self, expr: &'src ast::Comprehension<'src>, span: Span) {
+    let result = self.alloc_register();
+    match &expr.kind {
+      ast::ComprehensionKind::List(_) => self.builder().emit(MakeListEmpty, span),
+      ast::ComprehensionKind::Table(..) => self.builder().emit(MakeTableEmpty, span),
+    }
+    self.emit_store(result.clone(), span);
+
+    let body = |this: &mut Self, cond, end| {
+      let skip = expr.cond.as_ref().map(|filter| {
+        let skip = this.builder().label("skip");
+        this.emit_expr(filter);
+        this.builder().emit_jump_if_false(&skip, filter.span);
+        skip
+      });
+
+      match &expr.kind {
+        ast::ComprehensionKind::List(value) => {
+          this.emit_expr(value);
+          this.builder().emit(
+            Append {
+              list: result.access(),
+            },
+            value.span,
+          );
+        }
+        ast::ComprehensionKind::Table(key, value) => {
+          let key_register = this.alloc_register();
+          this.emit_expr(key);
+          this.emit_store(key_register.clone(), key.span);
+          this.emit_expr(value);
+          this.builder().emit(
+            StoreIndex {
+              obj: result.access(),
+              key: key_register.access(),
+            },
+            value.span,
+          );
+        }
+      }
+
+      if let Some(skip) = skip {
+        this.builder().bind_label(skip);
+      }
+      (cond, end)
+    };
+    match &expr.iter {
+      ast::ForIter::Range(range) => self.emit_range_loop(&expr.item, range, body),
+      ast::ForIter::Expr(iter) => self.emit_iter_loop(&expr.item, iter, body),
+    }
+
+    self.emit_load(result, span);
+  }
+
+  fn emit_func_expr(&mut self, expr: &'src ast::Func<'src>, span: Span) {
+    let function = self.emit_function(expr, false);
+    let desc = self.constant_value(function.ptr);
+    self.builder().emit(MakeFn { desc }, span);
+    function.upvalues.finish();
+  }
+
+  fn emit_fstring_expr(&mut self, expr: &'src ast::FString<'src>, span: Span) {
+    if expr.parts.is_empty() {
+      let str = self.constant_name("");
+      self.builder().emit(LoadConst { idx: str }, span);
+      return;
+    }
+
+    let parts = self.alloc_register_slice(expr.parts.len());
+
+    for (i, part) in expr.parts.iter().enumerate() {
+      self.emit_expr(part);
+      self.emit_store(parts.get(i), part.span);
+    }
+    self.builder().emit(
+      Concat {
+        start: parts.access(0),
+        count: op::Count(expr.parts.len() as u32),
+      },
+      span,
+    );
+  }
+
   fn emit_binary_expr(&mut self, expr: &'src ast::Binary<'src>, span: Span) {
     // binary expressions store lhs in a register,
     // and rhs in the accumulator
@@ -115,6 +231,12 @@ impl<'src> State<'src> {
       ast::BinaryOp::Mul => self.builder().emit(Mul { lhs }, span),
       ast::BinaryOp::Rem => self.builder().emit(Rem { lhs }, span),
       ast::BinaryOp::Pow => self.builder().emit(Pow { lhs }, span),
+      ast::BinaryOp::FloorDiv => self.builder().emit(FloorDiv { lhs }, span),
+      ast::BinaryOp::BitAnd => self.builder().emit(BitAnd { lhs }, span),
+      ast::BinaryOp::BitOr => self.builder().emit(BitOr { lhs }, span),
+      ast::BinaryOp::BitXor => self.builder().emit(BitXor { lhs }, span),
+      ast::BinaryOp::Shl => self.builder().emit(Shl { lhs }, span),
+      ast::BinaryOp::Shr => self.builder().emit(Shr { lhs }, span),
       ast::BinaryOp::Eq => self.builder().emit(CmpEq { lhs }, span),
       ast::BinaryOp::Neq => self.builder().emit(CmpNe { lhs }, span),
       ast::BinaryOp::More => self.builder().emit(CmpGt { lhs }, span),
@@ -207,6 +329,7 @@ impl<'src> State<'src> {
       ast::UnaryOp::Plus => {}
       ast::UnaryOp::Minus => self.builder().emit(Inv, span),
       ast::UnaryOp::Not => self.builder().emit(Not, span),
+      ast::UnaryOp::BitNot => self.builder().emit(BitNot, span),
       ast::UnaryOp::Opt => unreachable!(),
     }
   }
@@ -227,12 +350,17 @@ impl<'src> State<'src> {
 
   fn emit_set_var_expr(&mut self, expr: &'src ast::SetVar<'src>, span: Span) {
     self.emit_expr(&expr.value);
-    match self.resolve_var(expr.target.name.lexeme()) {
+    self.emit_set_var(&expr.target.name, span);
+  }
+
+  /// Store the accumulator in the existing variable `name`.
+  pub(super) fn emit_set_var(&mut self, name: &ast::Ident<'src>, span: Span) {
+    match self.resolve_var(name.lexeme()) {
       Get::Local(reg) => self.builder().emit(Store { reg: reg.access() }, span),
       Get::Upvalue(idx) => self.builder().emit(StoreUpvalue { idx }, span),
       Get::ModuleVar(idx) => self.builder().emit(StoreModuleVar { idx }, span),
       Get::Global => {
-        let name = self.constant_name(&expr.target.name);
+        let name = self.constant_name(name);
         self.builder().emit(StoreGlobal { name }, span);
       }
     }
@@ -298,7 +426,14 @@ impl<'src> State<'src> {
 
   fn emit_call_expr(&mut self, expr: &'src ast::Call<'src>, span: Span) {
     self.emit_expr(&expr.target);
-    if expr.args.is_empty() {
+    if !expr.named.is_empty()
+      || expr
+        .args
+        .iter()
+        .any(|arg| matches!(&**arg, ast::ExprKind::Spread(_)))
+    {
+      self.emit_spread_call(expr, span);
+    } else if expr.args.is_empty() {
       self.builder().emit(Call0, span);
     } else {
       let args = self.alloc_register_slice(1 + expr.args.len());
@@ -319,6 +454,81 @@ impl<'src> State<'src> {
     }
   }
 
+  /// The number of arguments is only known at runtime, so they are
+  /// collected into a list, which is then spread onto the stack by the call.
+  /// Named arguments are collected into a table, and matched against the
+  /// callee's parameters by the call.
+  fn emit_spread_call(&mut self, expr: &'src ast::Call<'src>, span: Span) {
+    let callee = self.alloc_register();
+    let args = self.alloc_register();
+    self.emit_store(callee.clone(), expr.target.span);
+    self.builder().emit(MakeListEmpty, span);
+    self.emit_store(args.clone(), span);
+
+    for arg in expr.args.iter() {
+      match &**arg {
+        ast::ExprKind::Spread(value) => {
+          self.emit_expr(value);
+          self.builder().emit(
+            Extend {
+              list: args.access(),
+            },
+            arg.span,
+          );
+        }
+        _ => {
+          self.emit_expr(arg);
+          self.builder().emit(
+            Append {
+              list: args.access(),
+            },
+            arg.span,
+          );
+        }
+      }
+    }
+
+    if expr.named.is_empty() {
+      self.emit_load(args, span);
+      self.builder().emit(
+        CallSpread {
+          callee: callee.access(),
+        },
+        span,
+      );
+      return;
+    }
+
+    let named = self.alloc_register();
+    let key = self.alloc_register();
+    self.builder().emit(MakeTableEmpty, span);
+    self.emit_store(named.clone(), span);
+    for (name, value) in expr.named.iter() {
+      let name_const = self.constant_name(name);
+      self
+        .builder()
+        .emit(LoadConst { idx: name_const }, name.span);
+      self.emit_store(key.clone(), name.span);
+      self.emit_expr(value);
+      self.builder().emit(
+        StoreIndex {
+          obj: named.access(),
+          key: key.access(),
+        },
+        value.span,
+      );
+    }
+
+    self.emit_load(args, span);
+    self.builder().emit(
+      CallNamed {
+        callee: callee.access(),
+        named: named.access(),
+      },
+      span,
+    );
+  }
+
   fn emit_get_self_expr(&mut self, span: Span) {
     self.builder().emit(LoadSelf, span);
   }
@@ -14,10 +14,14 @@ impl<'src> State<'src> {
       ast::StmtKind::Ctrl(v) => self.emit_ctrl_stmt(v, stmt.span),
       ast::StmtKind::Func(v) => self.emit_func_stmt(v),
       ast::StmtKind::Class(v) => self.emit_class_stmt(v),
+      ast::StmtKind::Enum(v) => self.emit_enum_stmt(v),
       ast::StmtKind::Expr(v) => self.emit_expr_stmt(v),
       ast::StmtKind::Pass => self.emit_pass_stmt(),
       ast::StmtKind::Print(v) => self.emit_print_stmt(v, stmt.span),
       ast::StmtKind::Import(v) => self.emit_import_stmt(v, stmt.span),
+      ast::StmtKind::Try(v) => self.emit_try_stmt(v, stmt.span),
+      ast::StmtKind::Match(v) => self.emit_match_stmt(v, stmt.span),
+      ast::StmtKind::Destructure(v) => self.emit_destructure_stmt(v, stmt.span),
     }
   }
 
@@ -32,6 +36,73 @@ impl<'src> State<'src> {
     self.emit_var(stmt.name.lexeme(), span)
   }
 
+  fn emit_destructure_stmt(&mut self, stmt: &'src ast::Destructure<'src>, span: Span) {
+    self.emit_expr(&stmt.value);
+    let value = self.alloc_register();
+    self.emit_store(value.clone(), stmt.value.span);
+    self.emit_destructure(&stmt.pattern, value, span, |this, name| {
+      if stmt.is_decl {
+        this.emit_var(name.lexeme(), name.span);
+      } else {
+        this.emit_set_var(name, name.span);
+      }
+    });
+  }
+
+  /// Load each part of the value in `value` which is matched by `pattern`,
+  /// and call `bind` with it in the accumulator.
+  fn emit_destructure(
+    &mut self,
+    pattern: &'src ast::Pattern<'src>,
+    value: Register,
+    span: Span,
+    mut bind: impl FnMut(&mut Self, &'src ast::Ident<'src>),
+  ) {
+    match pattern {
+      ast::Pattern::Ident(name) => {
+        self.emit_load(value, name.span);
+        bind(self, name);
+      }
+      ast::Pattern::Seq(names) => {
+        self.emit_load(value.clone(), span);
+        self.builder().emit(
+          CheckLen {
+            len: op::Count(names.len() as u32),
+          },
+          span,
+        );
+        for (i, name) in names.iter().enumerate() {
+          self.builder().emit(
+            LoadSmi {
+              value: op::Smi(i as i32),
+            },
+            name.span,
+          );
+          self.builder().emit(
+            LoadIndex {
+              obj: value.access(),
+            },
+            name.span,
+          );
+          bind(self, name);
+        }
+      }
+      ast::Pattern::Table(names) => {
+        for name in names.iter() {
+          let key = self.constant_name(name);
+          self.builder().emit(LoadConst { idx: key }, name.span);
+          self.builder().emit(
+            LoadIndex {
+              obj: value.access(),
+            },
+            name.span,
+          );
+          bind(self, name);
+        }
+      }
+    }
+  }
+
   fn emit_if_stmt(&mut self, stmt: &'src ast::If<'src>, span: Span) {
     // exit label for all branches
     let end = self.builder().multi_label("end");
@@ -60,6 +131,296 @@ impl<'src> State<'src> {
     self.builder().bind_label(end);
   }
 
+  fn emit_match_stmt(&mut self, stmt: &'src ast::Match<'src>, span: Span) {
+    let value = self.alloc_register();
+    self.emit_expr(&stmt.value);
+    self.emit_store(value.clone(), stmt.value.span);
+
+    // exit label for all arms
+    let end = self.builder().multi_label("end");
+
+    // each arm jumps to the next one as soon as any part of it fails to match
+    for arm in stmt.arms.iter() {
+      let next = self.builder().multi_label("next");
+      self.current_function().enter_scope();
+      self.emit_match_pattern(&arm.pattern, value.clone(), &next, span);
+      if let Some(guard) = arm.guard.as_ref() {
+        self.emit_expr(guard);
+        self.builder().emit_jump_if_false(&next, guard.span);
+      }
+      for stmt in arm.body.iter() {
+        self.emit_stmt(stmt);
+      }
+      self.builder().emit_jump(&end, span);
+      self.current_function().leave_scope();
+      self.builder().bind_label(next);
+    }
+
+    let _ = value.access();
+    self.builder().bind_label(end);
+  }
+
+  /// Emit checks which jump to `next` if `value` does not match `pattern`,
+  /// and declare the variables bound by the pattern.
+  fn emit_match_pattern(
+    &mut self,
+    pattern: &'src ast::MatchPattern<'src>,
+    value: Register,
+    next: &MultiLabel,
+    span: Span,
+  ) {
+    match pattern {
+      ast::MatchPattern::Wildcard => {}
+      ast::MatchPattern::Bind(name) => {
+        let register = self.alloc_register();
+        self.emit_load(value, name.span);
+        self.emit_store(register.clone(), name.span);
+        self.declare_local(name.lexeme(), register);
+      }
+      ast::MatchPattern::Literal(literal) => {
+        self.emit_expr(literal);
+        self.builder().emit(
+          CmpEq {
+            lhs: value.access(),
+          },
+          literal.span,
+        );
+        self.builder().emit_jump_if_false(next, literal.span);
+      }
+      ast::MatchPattern::Range(range) => {
+        // comparing values of different types is an error,
+        // so anything that isn't the same type as the bounds doesn't match
+        self.emit_expr(&range.start);
+        self.builder().emit(
+          CmpType {
+            lhs: value.access(),
+          },
+          range.start.span,
+        );
+        self.builder().emit_jump_if_false(next, range.start.span);
+        self.emit_expr(&range.start);
+        self.builder().emit(
+          CmpGe {
+            lhs: value.access(),
+          },
+          range.start.span,
+        );
+        self.builder().emit_jump_if_false(next, range.start.span);
+        self.emit_expr(&range.end);
+        if range.inclusive {
+          self.builder().emit(
+            CmpLe {
+              lhs: value.access(),
+            },
+            range.end.span,
+          );
+        } else {
+          self.builder().emit(
+            CmpLt {
+              lhs: value.access(),
+            },
+            range.end.span,
+          );
+        }
+        self.builder().emit_jump_if_false(next, range.end.span);
+      }
+      ast::MatchPattern::Type(ty) => {
+        self.emit_expr(ty);
+        self.builder().emit(
+          CmpType {
+            lhs: value.access(),
+          },
+          ty.span,
+        );
+        self.builder().emit_jump_if_false(next, ty.span);
+      }
+      ast::MatchPattern::Variant(variant) => {
+        self.emit_expr(&variant.path);
+        self.builder().emit(
+          CmpType {
+            lhs: value.access(),
+          },
+          variant.path.span,
+        );
+        self.builder().emit_jump_if_false(next, variant.path.span);
+        if let Some(fields) = variant.fields.as_ref() {
+          self.emit_load(value.clone(), span);
+          self.builder().emit(
+            MatchPayload {
+              len: op::Count(fields.len() as u32),
+            },
+            span,
+          );
+          self.builder().emit_jump_if_false(next, span);
+          self.emit_match_items(fields, value, next, span);
+        }
+      }
+      ast::MatchPattern::Seq(items) => {
+        self.emit_load(value.clone(), span);
+        self.builder().emit(
+          MatchSeq {
+            len: op::Count(items.len() as u32),
+          },
+          span,
+        );
+        self.builder().emit_jump_if_false(next, span);
+        self.emit_match_items(items, value, next, span);
+      }
+      ast::MatchPattern::Table(entries) => {
+        self.emit_load(value.clone(), span);
+        self.builder().emit(MatchTable, span);
+        self.builder().emit_jump_if_false(next, span);
+        for (key, item) in entries.iter() {
+          let name = self.constant_name(key);
+          self.builder().emit(LoadConst { idx: name }, key.span);
+          self.builder().emit(
+            MatchKey {
+              obj: value.access(),
+            },
+            key.span,
+          );
+          self.builder().emit_jump_if_false(next, key.span);
+          if matches!(item, ast::MatchPattern::Wildcard) {
+            continue;
+          }
+          let item_register = self.alloc_register();
+          self.builder().emit(LoadConst { idx: name }, key.span);
+          self.builder().emit(
+            LoadIndex {
+              obj: value.access(),
+            },
+            key.span,
+          );
+          self.emit_store(item_register.clone(), key.span);
+          self.emit_match_pattern(item, item_register, next, span);
+        }
+      }
+    }
+  }
+
+  /// Match each item of a list, tuple, or enum payload in `value` against
+  /// the pattern in the same position.
+  fn emit_match_items(
+    &mut self,
+    items: &'src [ast::MatchPattern<'src>],
+    value: Register,
+    next: &MultiLabel,
+    span: Span,
+  ) {
+    for (i, item) in items.iter().enumerate() {
+      if matches!(item, ast::MatchPattern::Wildcard) {
+        continue;
+      }
+      let item_register = self.alloc_register();
+      self.builder().emit(
+        LoadSmi {
+          value: op::Smi(i as i32),
+        },
+        span,
+      );
+      self.builder().emit(
+        LoadIndex {
+          obj: value.access(),
+        },
+        span,
+      );
+      self.emit_store(item_register.clone(), span);
+      self.emit_match_pattern(item, item_register, next, span);
+    }
+  }
+
+  fn emit_try_stmt(&mut self, stmt: &'src ast::Try<'src>, span: Span) {
+    let catch = self.builder().label("catch");
+    let end = self.builder().multi_label("end");
+    let finally = stmt.finally.as_deref();
+
+    // body
+    self.builder().emit_push_handler(&catch, span);
+    self
+      .current_function()
+      .try_blocks
+      .push(TryBlock { finally });
+    self.current_function().enter_scope();
+    self.emit_stmt_list(&stmt.body);
+    self.current_function().leave_scope();
+    self.current_function().try_blocks.pop();
+    self.builder().emit(PopHandler, span);
+    self.emit_finally(finally);
+    self.builder().emit_jump(&end, span);
+
+    // the caught error is in the accumulator
+    self.builder().bind_label(catch);
+    let Some(catch) = stmt.catch.as_ref() else {
+      // no `catch`, run `finally` and re-throw
+      self.emit_finally_and_rethrow(finally, span);
+      self.builder().bind_label(end);
+      return;
+    };
+
+    // an error thrown from inside `catch` must still run `finally`
+    let rethrow = finally.map(|_| self.builder().label("rethrow"));
+    if let Some(rethrow) = &rethrow {
+      self.builder().emit_push_handler(rethrow, span);
+      self
+        .current_function()
+        .try_blocks
+        .push(TryBlock { finally });
+    }
+
+    self.current_function().enter_scope();
+    if let Some(name) = &catch.name {
+      let register = self.alloc_register();
+      self.emit_store(register.clone(), name.span);
+      self.declare_local(name.lexeme(), register);
+    }
+    self.emit_stmt_list(&catch.body);
+    self.current_function().leave_scope();
+
+    if let Some(rethrow) = rethrow {
+      self.current_function().try_blocks.pop();
+      self.builder().emit(PopHandler, span);
+      self.emit_finally(finally);
+      self.builder().emit_jump(&end, span);
+
+      self.builder().bind_label(rethrow);
+      self.emit_finally_and_rethrow(finally, span);
+    }
+
+    self.builder().bind_label(end);
+  }
+
+  fn emit_finally(&mut self, finally: Option<&'src [ast::Stmt<'src>]>) {
+    if let Some(finally) = finally {
+      self.current_function().enter_scope();
+      self.emit_stmt_list(finally);
+      self.current_function().leave_scope();
+    }
+  }
+
+  /// Expects the error to be in the accumulator.
+  fn emit_finally_and_rethrow(&mut self, finally: Option<&'src [ast::Stmt<'src>]>, span: Span) {
+    let error = self.alloc_register();
+    self.emit_store(error.clone(), span);
+    self.emit_finally(finally);
+    self.emit_load(error, span);
+    self.builder().emit(Throw, span);
+  }
+
+  /// Pop the handlers of every `try` block above `depth`, running their
+  /// `finally` blocks from innermost to outermost.
+  ///
+  /// Used when `break`, `continue`, or `return` jump out of a `try` block.
+  fn emit_unwind_try_blocks(&mut self, depth: usize, span: Span) {
+    let try_blocks = self.current_function().try_blocks.clone();
+    for (i, block) in try_blocks.iter().enumerate().skip(depth).rev() {
+      // a `finally` block is not covered by its own handler
+      self.current_function().try_blocks.truncate(i);
+      self.builder().emit(PopHandler, span);
+      self.emit_finally(block.finally);
+    }
+    self.current_function().try_blocks = try_blocks;
+  }
+
   fn emit_loop_stmt(&mut self, stmt: &'src ast::Loop<'src>, span: Span) {
     match stmt {
       ast::Loop::For(v) => match &v.iter {
@@ -72,19 +433,38 @@ impl<'src> State<'src> {
   }
 
   fn emit_for_range_loop(&mut self, stmt: &'src ast::For<'src>, range: &'src ast::IterRange<'src>) {
+    self.emit_range_loop(&stmt.item, range, |this, latch, end| {
+      this.emit_loop_body((latch, end), &stmt.body)
+    });
+  }
+
+  /// Emit a loop which binds each int in `range` to `item`, and calls `body`
+  /// to emit the rest of the loop body.
+  ///
+  /// `item` must be an identifier, which is checked by the parser.
+  pub(super) fn emit_range_loop(
+    &mut self,
+    item: &'src ast::Pattern<'src>,
+    range: &'src ast::IterRange<'src>,
+    body: impl FnOnce(&mut Self, LoopHeader, MultiLabel) -> (LoopHeader, MultiLabel),
+  ) {
     let cond = self.builder().loop_header();
     let latch = self.builder().loop_header();
-    let body = self.builder().label("body");
+    let body_label = self.builder().label("body");
     let end = self.builder().multi_label("end");
 
     self.current_function().enter_scope();
 
+    let ast::Pattern::Ident(item) = item else {
+      unreachable!("range loop item must be an identifier");
+    };
+
     let item_register = self.alloc_register();
     let end_register = self.alloc_register();
 
-    self.declare_local(stmt.item.lexeme(), item_register.clone());
+    self.declare_local(item.lexeme(), item_register.clone());
     self.emit_expr(&range.start);
-    self.emit_store(item_register.clone(), stmt.item.span);
+    self.emit_store(item_register.clone(), item.span);
 
     self.emit_expr(&range.end);
     self.emit_store(end_register.clone(), range.span());
@@ -107,7 +487,7 @@ impl<'src> State<'src> {
       );
     }
     self.builder().emit_jump_if_false(&end, range.span());
-    self.builder().emit_jump(&body, range.span());
+    self.builder().emit_jump(&body_label, range.span());
 
     self.builder().bind_loop_header(&latch);
     self
@@ -122,8 +502,8 @@ impl<'src> State<'src> {
     self.emit_store(item_register.clone(), range.span());
     self.builder().emit_jump_loop(&cond, range.span());
 
-    self.builder().bind_label(body);
-    let (latch, end) = self.emit_loop_body((latch, end), &stmt.body);
+    self.builder().bind_label(body_label);
+    let (latch, end) = body(self, latch, end);
     self.builder().emit_jump_loop(&latch, range.span());
 
     let _ = end_register.access();
@@ -134,6 +514,19 @@ impl<'src> State<'src> {
   }
 
   fn emit_for_iter_loop(&mut self, stmt: &'src ast::For<'src>, iter: &'src ast::Expr<'src>) {
+    self.emit_iter_loop(&stmt.item, iter, |this, cond, end| {
+      this.emit_loop_body((cond, end), &stmt.body)
+    });
+  }
+
+  /// Emit a loop which binds each value produced by `iter` to `item`,
+  /// and calls `body` to emit the rest of the loop body.
+  pub(super) fn emit_iter_loop(
+    &mut self,
+    item: &'src ast::Pattern<'src>,
+    iter: &'src ast::Expr<'src>,
+    body: impl FnOnce(&mut Self, LoopHeader, MultiLabel) -> (LoopHeader, MultiLabel),
+  ) {
     let iter_register = self.alloc_register();
     let item_register = self.alloc_register();
 
@@ -157,7 +550,24 @@ impl<'src> State<'src> {
     // `item = none`
     self.builder().emit(LoadNone, iter.span);
     self.emit_store(item_register.clone(), iter.span);
-    self.declare_local(stmt.item.lexeme(), item_register.clone());
+    // every name bound by the pattern is declared up front, and
+    // re-assigned on each iteration
+    let bindings = match item {
+      ast::Pattern::Ident(name) => {
+        self.declare_local(name.lexeme(), item_register.clone());
+        vec![]
+      }
+      ast::Pattern::Seq(names) | ast::Pattern::Table(names) => names
+        .iter()
+        .map(|name| {
+          let register = self.alloc_register();
+          self.builder().emit(LoadNone, name.span);
+          self.emit_store(register.clone(), name.span);
+          self.declare_local(name.lexeme(), register.clone());
+          register
+        })
+        .collect::<Vec<_>>(),
+    };
 
     // condition
     self.builder().bind_loop_header(&cond);
@@ -178,10 +588,20 @@ impl<'src> State<'src> {
       .emit(LoadField { name: next_const }, iter.span);
     self.builder().emit(Call0, iter.span);
     self.emit_store(item_register.clone(), iter.span);
+    if !matches!(item, ast::Pattern::Ident(_)) {
+      let mut registers = bindings.iter();
+      self.emit_destructure(item, item_register.clone(), iter.span, |this, name| {
+        let register = registers.next().unwrap().clone();
+        this.emit_store(register, name.span);
+      });
+    }
 
-    let (cond, end) = self.emit_loop_body((cond, end), &stmt.body);
+    let (cond, end) = body(self, cond, end);
     self.builder().emit_jump_loop(&cond, iter.span);
 
+    for register in bindings {
+      let _ = register.access();
+    }
     let _ = item_register.access();
     let _ = iter_register.access();
 
@@ -241,6 +661,16 @@ impl<'src> State<'src> {
         } else {
           self.builder().emit(LoadNone, span);
         }
+        let try_blocks = &self.current_function().try_blocks;
+        if try_blocks.iter().any(|block| block.finally.is_some()) {
+          // `finally` may clobber the accumulator
+          let value = self.alloc_register();
+          self.emit_store(value.clone(), span);
+          self.emit_unwind_try_blocks(0, span);
+          self.emit_load(value, span);
+        } else {
+          self.emit_unwind_try_blocks(0, span);
+        }
         self.builder().emit(Return, span);
       }
       ast::Ctrl::Yield(stmt) => {
@@ -251,15 +681,30 @@ impl<'src> State<'src> {
         }
         self.builder().emit(Yield, span);
       }
+      ast::Ctrl::Throw(stmt) => {
+        self.emit_expr(&stmt.value);
+        self.builder().emit(Throw, span);
+      }
       ast::Ctrl::Continue => {
-        let function = self.current_function();
-        let loop_ = function
+        let try_depth = self
+          .current_function()
           .current_loop
           .as_ref()
-          .expect("attempted to emit continue outside of loop");
+          .expect("attempted to emit continue outside of loop")
+          .try_depth;
+        self.emit_unwind_try_blocks(try_depth, span);
+        let function = self.current_function();
+        let loop_ = function.current_loop.as_ref().unwrap();
         function.builder.emit_jump_loop(&loop_.start, span);
       }
       ast::Ctrl::Break => {
+        let try_depth = self
+          .current_function()
+          .current_loop
+          .as_ref()
+          .expect("attempted to emit break outside of loop")
+          .try_depth;
+        self.emit_unwind_try_blocks(try_depth, span);
         let function = self.current_function();
         let loop_ = function
           .current_loop
@@ -360,6 +805,27 @@ impl<'src> State<'src> {
     self.emit_var(stmt.name.lexeme(), stmt.name.span);
   }
 
+  fn emit_enum_stmt(&mut self, stmt: &'src ast::Enum<'src>) {
+    let mut variants = IndexMap::with_capacity(stmt.variants.len());
+    for variant in stmt.variants.iter() {
+      let fields = variant
+        .fields
+        .iter()
+        .map(|field| self.global.intern(field.to_string()))
+        .collect();
+      variants.insert(self.global.intern(variant.name.to_string()), fields);
+    }
+
+    let desc = self.global.alloc(object::EnumDescriptor {
+      name: self.global.intern(stmt.name.to_string()),
+      variants,
+    });
+    let desc = self.constant_value(desc);
+    self.builder().emit(MakeEnum { desc }, stmt.name.span);
+
+    self.emit_var(stmt.name.lexeme(), stmt.name.span);
+  }
+
   fn emit_expr_stmt(&mut self, expr: &'src ast::Expr<'src>) {
     self.emit_expr(expr)
   }
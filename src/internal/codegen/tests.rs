@@ -595,3 +595,133 @@ check! {
         print b
   "#
 }
+
+check! {
+  try_catch_finally,
+  r#"
+    try:
+      print "try"
+    catch e:
+      print e
+    finally:
+      print "finally"
+  "#
+}
+
+check! {
+  try_finally_break,
+  r#"
+    fn test():
+      loop:
+        try:
+          break
+        finally:
+          print "finally"
+  "#
+}
+
+check! {
+  throw_stmt,
+  r#"
+    fn test(v):
+      if v:
+        throw Error("failed")
+      return v
+  "#
+}
+
+check! {
+  fstring,
+  r#"
+    name := "world"
+    print f"hello {name}, you have {1 + 2} items"
+  "#
+}
+
+check! {
+  tuple,
+  r#"
+    a := ()
+    b := (1, "two", a)
+    print b[1]
+  "#
+}
+
+check! {
+  destructure,
+  r#"
+    fn test(pair, person):
+      a, b := pair
+      {name} := person
+      a, b = (b, a)
+      for k, v in [pair]:
+        print k, v
+  "#
+}
+
+check! {
+  lambda,
+  r#"
+    fn test(n):
+      add := fn(x): x + n
+      print add(1)
+  "#
+}
+
+check! {
+  comprehension,
+  r#"
+    fn test(xs, pairs):
+      a := [x * 2 for x in xs if x > 0]
+      b := {k: v for k, v in pairs}
+  "#
+}
+
+check! {
+  range_comprehension,
+  r#"
+    fn test(n):
+      return [i * 2 for i in 0..n if i > 1]
+  "#
+}
+
+check! {
+  match_stmt,
+  r#"
+    fn test(v):
+      match v:
+        case 0..10: print "small"
+        case [a, _] if a: print a
+        case {name}: print name
+        case _: print "other"
+  "#
+}
+
+check! {
+  variadic_params_and_spread,
+  r#"
+    fn test(a, *rest):
+      print test(a, *rest, 1)
+  "#
+}
+
+check! {
+  named_args,
+  r#"
+    fn test(a, b = 1):
+      print test(a, b=2)
+  "#
+}
+
+check! {
+  enum_stmt,
+  r#"
+    enum Shape:
+      Circle(radius)
+      Empty
+    fn test(v):
+      match v:
+        case Shape.Circle(r): print r
+        case Shape.Empty: print "empty"
+  "#
+}
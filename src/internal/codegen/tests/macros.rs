@@ -16,7 +16,8 @@ macro_rules! check {
           panic!("Failed to parse source, see errors above.")
         }
       };
-      let module = emit(global, &module, "main", !as_module);
+      let source = global.add_source("main", input);
+      let module = emit(global, &module, "main", source, !as_module);
       let snapshot = format!(
         "# Input:\n{input}\n\n# Func:\n{}\n\n",
         module.root.disassemble(),
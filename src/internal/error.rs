@@ -1,8 +1,11 @@
 use std::error::Error as StdError;
 use std::fmt::Display;
+use std::sync::Arc;
 
+use super::object::class::ClassInstance;
 use super::syntax::SyntaxError;
-use crate::span::SpannedError;
+use crate::public::{Bind, Global, Value};
+use crate::span::{Source, Span, SpannedError};
 
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
@@ -11,6 +14,39 @@ pub enum Error {
   Vm(SpannedError),
   Syntax(SyntaxError),
   User(Box<dyn StdError + Send + Sync + 'static>),
+  Thrown(Thrown),
+  /// An error raised while executing a script, along with the state of the
+  /// call stack at the point where it was raised.
+  Traced(Box<Error>, Backtrace),
+  /// Execution was stopped from outside of the script.
+  ///
+  /// This error can not be caught by a `try` block.
+  Aborted(Abort),
+}
+
+/// The reason why execution was aborted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Abort {
+  /// An interrupt was requested through an
+  /// [`InterruptHandle`][crate::InterruptHandle].
+  Interrupted,
+  /// The timeout configured through
+  /// [`HebiBuilder::timeout`][crate::HebiBuilder::timeout] has elapsed.
+  TimedOut,
+  /// The fuel budget configured through
+  /// [`HebiBuilder::fuel`][crate::HebiBuilder::fuel] or
+  /// [`Hebi::set_fuel`][crate::Hebi::set_fuel] was exhausted.
+  OutOfFuel,
+}
+
+impl Display for Abort {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Abort::Interrupted => write!(f, "execution was interrupted"),
+      Abort::TimedOut => write!(f, "execution timed out"),
+      Abort::OutOfFuel => write!(f, "out of fuel"),
+    }
+  }
 }
 
 impl Error {
@@ -18,14 +54,25 @@ impl Error {
     Self::User(Box::new(e))
   }
 
-  pub fn report(&self, src: &str, use_color: bool) -> String {
+  /// Render the error for display to a user.
+  ///
+  /// Errors which point into some source code are rendered along with a
+  /// snippet of that code, which is retrieved from the VM's source map.
+  pub fn report(&self, use_color: bool) -> String {
     match self {
-      Error::Vm(e) => format!("runtime error: {}", e.report(src, use_color)),
+      Error::Vm(e) => format!("runtime error: {}", e.message),
       Error::Syntax(e) => {
         use std::fmt::Write;
-        let mut s = "syntax error:\n".to_string();
-        for e in e.errors() {
-          writeln!(&mut s, "{}", e.report(src, use_color)).unwrap();
+        let mut s = match e.source() {
+          Some(source) => format!("syntax error in `{}`:\n", source.name()),
+          None => "syntax error:\n".to_string(),
+        };
+        for error in e.errors() {
+          match e.source() {
+            Some(source) => writeln!(&mut s, "{}", error.report(source.text(), use_color)),
+            None => writeln!(&mut s, "{}", error.message),
+          }
+          .unwrap();
         }
         s
       }
@@ -33,8 +80,194 @@ impl Error {
         // TODO: spans in user errors
         format!("runtime error: {e}")
       }
+      Error::Thrown(e) => format!("uncaught error: {e}"),
+      Error::Aborted(e) => format!("aborted: {e}"),
+      // the span is already highlighted by the backtrace
+      Error::Traced(e, backtrace) => {
+        format!("{}{}", backtrace.report(use_color), e.report(use_color))
+      }
+    }
+  }
+
+  /// The error without its backtrace.
+  pub fn inner(&self) -> &Error {
+    match self {
+      Error::Traced(e, _) => e.inner(),
+      e => e,
+    }
+  }
+
+  /// The state of the call stack at the point where this error was raised,
+  /// if it was raised while executing a script.
+  pub fn backtrace(&self) -> Option<&Backtrace> {
+    match self {
+      Error::Traced(_, backtrace) => Some(backtrace),
+      _ => None,
+    }
+  }
+}
+
+/// A snapshot of the call stack, ordered from the outermost call to the
+/// innermost one.
+#[derive(Clone, Debug, Default)]
+pub struct Backtrace {
+  frames: Vec<BacktraceFrame>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BacktraceFrame {
+  function: String,
+  module: String,
+  source: Option<Arc<Source>>,
+  span: Option<Span>,
+}
+
+impl Backtrace {
+  pub(crate) fn new(frames: Vec<BacktraceFrame>) -> Self {
+    Self { frames }
+  }
+
+  pub fn frames(&self) -> &[BacktraceFrame] {
+    &self.frames
+  }
+
+  /// Render the backtrace in the style of a Python traceback.
+  pub fn report(&self, use_color: bool) -> String {
+    use std::fmt::Write;
+
+    let (r, c) = if use_color {
+      ("\x1b[0m", "\x1b[31m")
+    } else {
+      ("", "")
+    };
+
+    let mut out = "traceback (most recent call last):\n".to_string();
+    for frame in self.frames.iter() {
+      let f = &mut out;
+      let (Some(source), Some(span)) = (frame.source(), frame.span) else {
+        writeln!(f, "  module `{}`, in `{}`", frame.module, frame.function).unwrap();
+        continue;
+      };
+      let src = source.text();
+      if span.end > src.len() {
+        writeln!(f, "  file `{}`, in `{}`", source.name(), frame.function).unwrap();
+        continue;
+      }
+
+      writeln!(
+        f,
+        "  file `{}`, line {}, in `{}`",
+        source.name(),
+        source.line_of(span.start),
+        frame.function
+      )
+      .unwrap();
+
+      // the snippet is the first line of the span, and the markers
+      // stop at the end of that line
+      let line_start = src[..span.start].rfind('\n').map(|v| v + 1).unwrap_or(0);
+      let line_end = src[span.start..]
+        .find('\n')
+        .map(|v| v + span.start)
+        .unwrap_or(src.len());
+      let snippet = src[line_start..line_end].trim_end();
+      let indent = snippet.len() - snippet.trim_start().len();
+      let offset = src[line_start + indent..span.start].chars().count();
+      let width = src[span.start..span.end.min(line_end)]
+        .trim_end()
+        .chars()
+        .count()
+        .max(1);
+      writeln!(f, "    {}", snippet.trim_start()).unwrap();
+      writeln!(f, "    {}{c}{}{r}", " ".repeat(offset), "^".repeat(width)).unwrap();
+    }
+    out
+  }
+}
+
+impl BacktraceFrame {
+  pub(crate) fn new(
+    function: String,
+    module: String,
+    source: Option<Arc<Source>>,
+    span: Option<Span>,
+  ) -> Self {
+    Self {
+      function,
+      module,
+      source,
+      span,
     }
   }
+
+  /// Name of the function executing in this frame.
+  pub fn function(&self) -> &str {
+    &self.function
+  }
+
+  /// Name of the module which the function belongs to.
+  pub fn module(&self) -> &str {
+    &self.module
+  }
+
+  /// The source code of the function, if it is known.
+  pub fn source(&self) -> Option<&Source> {
+    self.source.as_deref()
+  }
+
+  /// Span of the instruction which was executing in this frame, if known.
+  ///
+  /// The span points into [`BacktraceFrame::source`].
+  pub fn span(&self) -> Option<Span> {
+    self.span
+  }
+}
+
+/// A value thrown by a script which was not caught by any `catch` block.
+///
+/// The value itself stays owned by the VM, and may be retrieved using
+/// [`Thrown::value`] until the VM is entered again.
+#[derive(Debug)]
+pub struct Thrown {
+  id: u64,
+  message: String,
+}
+
+impl Thrown {
+  pub(crate) fn new(id: u64, message: String) -> Self {
+    Self { id, message }
+  }
+
+  pub(crate) fn id(&self) -> u64 {
+    self.id
+  }
+
+  /// The `message` field of the thrown value, or the thrown value itself
+  /// converted to a string if it has no such field.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+
+  pub fn value<'cx>(&self, global: &Global<'cx>) -> Option<Value<'cx>> {
+    global
+      .inner
+      .get_thrown(self.id)
+      .map(|value| unsafe { value.bind_raw::<'cx>() })
+  }
+
+  /// Retrieve a field of the thrown value, if it is a class instance.
+  pub fn field<'cx>(&self, global: &Global<'cx>, name: &str) -> Option<Value<'cx>> {
+    let value = global.inner.get_thrown(self.id)?;
+    let instance = value.to_object::<ClassInstance>()?;
+    let field = instance.fields.get(name)?;
+    Some(unsafe { field.bind_raw::<'cx>() })
+  }
+}
+
+impl Display for Thrown {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.message)
+  }
 }
 
 impl From<SpannedError> for Error {
@@ -61,6 +294,15 @@ impl Display for Error {
       Error::User(e) => {
         write!(f, "{e}")
       }
+      Error::Thrown(e) => {
+        write!(f, "{e}")
+      }
+      Error::Traced(e, _) => {
+        write!(f, "{e}")
+      }
+      Error::Aborted(e) => {
+        write!(f, "{e}")
+      }
     }
   }
 }
@@ -36,6 +36,10 @@ macro_rules! declare_object_trait {
       pub(crate) display_fmt: fn(*const T, &mut std::fmt::Formatter<'_>) -> std::fmt::Result,
       pub(crate) debug_fmt: fn(*const T, &mut std::fmt::Formatter<'_>) -> std::fmt::Result,
 
+      pub(crate) traceable: bool,
+      pub(crate) trace: fn(*const T, &mut Tracer<'_>),
+      pub(crate) clear: fn(*const T),
+
       pub(crate) type_name: fn(Ptr<T>) -> &'static str,
       pub(crate) instance_of: fn(Ptr<T>, Value) -> Result<bool>,
       $(
@@ -48,8 +52,26 @@ macro_rules! declare_object_trait {
     }
 
     pub trait $Object: Debug + Display + Sized + 'static {
+      /// Whether this type holds references to other objects which may form
+      /// a cycle. Types which set this should also implement `trace` and
+      /// `clear`.
+      const TRACEABLE: bool = false;
+
       fn type_name(this: Ptr<Self>) -> &'static str;
       fn instance_of(this: Ptr<Self>, ty: Value) -> Result<bool>;
+
+      /// Visit every reference to another object held by `self`.
+      ///
+      /// Each reference must be visited at most once.
+      fn trace(&self, tracer: &mut Tracer<'_>) {
+        let _ = tracer;
+      }
+
+      /// Drop every reference which could be part of a cycle.
+      ///
+      /// Called by the cycle collector on objects which are only kept alive
+      /// by cycles.
+      fn clear(&self) {}
       $(
         fn $name(
           $scope: Scope<'_>,
@@ -132,6 +154,10 @@ macro_rules! declare_object_trait {
               display_fmt: |ptr, f| <$T as ::std::fmt::Display>::fmt(unsafe { &*ptr }, f),
               debug_fmt: |ptr, f| <$T as ::std::fmt::Debug>::fmt(unsafe { &*ptr }, f),
 
+              traceable: <$T as $crate::internal::object::Object>::TRACEABLE,
+              trace: |ptr, tracer| <$T as $crate::internal::object::Object>::trace(unsafe { &*ptr }, tracer),
+              clear: |ptr| <$T as $crate::internal::object::Object>::clear(unsafe { &*ptr }),
+
               type_name: <$T as $crate::internal::object::Object>::type_name,
 
               instance_of: <$T as $crate::internal::object::Object>::instance_of,
@@ -254,6 +280,48 @@ declare_object_trait! {
       fail!("`{this}` does not support `**`")
     }
 
+    fn floor_divide(scope, this, other: Self) -> Result<Value> {
+      let _ = scope;
+      let _ = other;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `//`")
+    }
+
+    fn bit_and(scope, this, other: Self) -> Result<Value> {
+      let _ = scope;
+      let _ = other;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `&`")
+    }
+
+    fn bit_or(scope, this, other: Self) -> Result<Value> {
+      let _ = scope;
+      let _ = other;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `|`")
+    }
+
+    fn bit_xor(scope, this, other: Self) -> Result<Value> {
+      let _ = scope;
+      let _ = other;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `^`")
+    }
+
+    fn shift_left(scope, this, other: Self) -> Result<Value> {
+      let _ = scope;
+      let _ = other;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `<<`")
+    }
+
+    fn shift_right(scope, this, other: Self) -> Result<Value> {
+      let _ = scope;
+      let _ = other;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `>>`")
+    }
+
     fn invert(scope, this) -> Result<Value> {
       let _ = scope;
       let this = Self::type_name(this);
@@ -266,6 +334,12 @@ declare_object_trait! {
       fail!("`{this}` does not support `!`")
     }
 
+    fn bit_not(scope, this) -> Result<Value> {
+      let _ = scope;
+      let this = Self::type_name(this);
+      fail!("`{this}` does not support `~`")
+    }
+
     fn cmp(scope, this, other: Self) -> Result<Ordering> {
       let _ = scope;
       let _ = other;
@@ -306,29 +380,43 @@ pub type ReturnAddr = Option<usize>;
 
 #[macro_use]
 pub mod builtin;
+pub mod bytes;
 
 pub mod class;
+pub mod enum_;
+pub mod error;
 pub mod function;
+pub mod gc;
+pub mod int;
 pub mod list;
 pub mod module;
 pub mod native;
+pub mod set;
 pub mod string;
 pub mod table;
+pub mod tuple;
 
 pub(crate) mod ptr;
 
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 
+pub use bytes::Bytes;
 pub use class::{ClassDescriptor, ClassType};
+pub use enum_::{EnumDescriptor, EnumType, EnumValue};
+pub use error::RuntimeError;
 pub use function::{BoundFunction, Function, FunctionDescriptor};
+pub use int::Int;
 pub use list::List;
 pub use module::{Module, ModuleDescriptor};
 pub use ptr::{Any, Ptr};
+pub use set::Set;
 pub use string::Str;
 pub use table::Table;
+pub use tuple::Tuple;
 
 use self::class::{ClassInstance, ClassProxy};
+use self::gc::Tracer;
 use self::native::{NativeAsyncFunction, NativeClassInstance, NativeFunction};
 use super::error::Result;
 use super::value::Value;
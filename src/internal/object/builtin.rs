@@ -3,15 +3,18 @@
 use std::fmt::{Debug, Display};
 
 use indexmap::IndexMap;
+use num_bigint::BigInt;
 
+use super::gc::Tracer;
 use super::{List, Object, Ptr, ReturnAddr, Str};
 use crate::internal::error::Result;
 use crate::internal::object::native::LocalBoxFuture;
-use crate::internal::object::{list, string};
+use crate::internal::object::{bytes, int, list, set, string, table, tuple};
 use crate::internal::value::Value;
 use crate::internal::vm::global::Global;
-use crate::internal::vm::thread::util::is_truthy;
+use crate::internal::vm::thread::util::{is_truthy, stringify};
 use crate::internal::vm::thread::{AsyncFrame, CallResult};
+use crate::internal::vm::Vm;
 use crate::public;
 use crate::public::{Bind, Scope, Unbind};
 
@@ -55,9 +58,7 @@ impl Object for BuiltinFunction {
     "BuiltinFunction"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn eq(_scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
     // This is a pointer comparison.
@@ -106,9 +107,7 @@ impl Object for BuiltinAsyncFunction {
     "BuiltinAsyncFunction"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
     Ok(CallResult::Poll(AsyncFrame {
@@ -132,6 +131,7 @@ declare_object_type!(BuiltinAsyncFunction);
 #[derive(Debug)]
 pub struct BuiltinType {
   pub name: &'static str,
+  init: Option<BuiltinFunction>,
   methods: IndexMap<&'static str, BuiltinFunction>,
 }
 
@@ -139,6 +139,7 @@ impl BuiltinType {
   pub fn builder(name: &'static str) -> BuiltinTypeBuilder {
     BuiltinTypeBuilder {
       name,
+      init: None,
       methods: IndexMap::new(),
     }
   }
@@ -146,10 +147,17 @@ impl BuiltinType {
 
 pub struct BuiltinTypeBuilder {
   name: &'static str,
+  init: Option<BuiltinFunction>,
   methods: IndexMap<&'static str, BuiltinFunction>,
 }
 
 impl BuiltinTypeBuilder {
+  /// Make the type callable, constructing a new instance with `f`.
+  pub fn init(mut self, f: Callback) -> Self {
+    self.init = Some(BuiltinFunction::new(self.name, f));
+    self
+  }
+
   pub fn method(mut self, name: &'static str, f: Callback) -> Self {
     self.methods.insert(name, BuiltinFunction::new(name, f));
     self
@@ -158,6 +166,7 @@ impl BuiltinTypeBuilder {
   pub fn finish(self) -> BuiltinType {
     BuiltinType {
       name: self.name,
+      init: self.init,
       methods: self.methods,
     }
   }
@@ -168,7 +177,13 @@ macro_rules! builtin_type {
     $crate::internal::object::builtin::BuiltinType::builder(stringify!($name))
       $(.method(stringify!($method_name), $method_cb))*
       .finish()
-  }
+  };
+  ($name:ident($init:expr) { $($method_name:ident : $method_cb:expr),* }) => {
+    $crate::internal::object::builtin::BuiltinType::builder(stringify!($name))
+      .init($init)
+      $(.method(stringify!($method_name), $method_cb))*
+      .finish()
+  };
 }
 
 impl Display for BuiltinType {
@@ -191,16 +206,20 @@ impl Object for BuiltinType {
   }
 
   fn named_field_opt(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Option<Value>> {
-    Ok(
-      this
-        .methods
-        .get(name.as_str())
-        .map(|method| Value::object(scope.alloc(method.clone()))),
-    )
+    this
+      .methods
+      .get(name.as_str())
+      .map(|method| Ok(Value::object(scope.try_alloc(method.clone(), 0)?)))
+      .transpose()
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
+  default_instance_of!();
+
+  fn call(scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
+    match &this.init {
+      Some(init) => init.call(scope).map(CallResult::Return),
+      None => fail!("`{this}` is not callable"),
+    }
   }
 }
 
@@ -240,13 +259,17 @@ impl Display for BuiltinMethod {
 }
 
 impl Object for BuiltinMethod {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.value(&self.this);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "BuiltinMethod"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
     BuiltinMethod::call(this.as_ref(), scope).map(CallResult::Return)
@@ -297,11 +320,14 @@ macro_rules! builtin_method_static {
 
 fn to_int(scope: Scope<'_>) -> Result<Value> {
   let value = scope.param::<public::Value>(0)?.unbind();
-  if value.is_int() {
+  if int::is_int(&value) {
     Ok(value)
   } else if value.is_float() {
-    let value = unsafe { value.to_float_unchecked() };
-    Ok(Value::int(value as i32))
+    let float = unsafe { value.clone().to_float_unchecked() };
+    match int::from_f64(&scope.thread.global, float)? {
+      Some(value) => Ok(value),
+      None => fail!("cannot convert `{value}` to an int"),
+    }
   } else {
     fail!("cannot convert `{value}` to an int")
   }
@@ -309,9 +335,8 @@ fn to_int(scope: Scope<'_>) -> Result<Value> {
 
 fn to_float(scope: Scope<'_>) -> Result<Value> {
   let value = scope.param::<public::Value>(0)?.unbind();
-  if value.is_int() {
-    let value = unsafe { value.to_int_unchecked() };
-    Ok(Value::float(value as f64))
+  if let Some(value) = int::to_f64(&value) {
+    Ok(Value::float(value))
   } else if value.is_float() {
     Ok(value)
   } else {
@@ -330,26 +355,28 @@ fn to_str(scope: Scope<'_>) -> Result<Value> {
   if let Some(str) = value.clone().to_object::<Str>() {
     Ok(Value::object(str))
   } else {
-    let str = scope.alloc(Str::owned(value));
+    let str = stringify(scope.clone(), value)?;
+    let extra = str.len();
+    let str = scope.try_alloc(Str::owned(str), extra)?;
     Ok(Value::object(str))
   }
 }
 
 fn parse_int(scope: Scope<'_>) -> Result<Value> {
   let value = scope.param::<public::Value>(0)?.unbind();
-  if value.is_int() {
+  if int::is_int(&value) {
     return Ok(value);
   } else if value.is_float() {
-    return Ok(Value::int(unsafe { value.to_float_unchecked() } as i32));
-  } else if value.is_object() {
-    if let Some(value) = value.clone().to_object::<Str>() {
-      return Ok(Value::int(
-        value
-          .as_str()
-          .parse()
-          .map_err(|e| error!("failed to parse `{value}` as int: {e}"))?,
-      ));
-    };
+    let float = unsafe { value.clone().to_float_unchecked() };
+    if let Some(value) = int::from_f64(&scope.thread.global, float)? {
+      return Ok(value);
+    }
+  } else if let Some(value) = value.clone().to_object::<Str>() {
+    let int = value
+      .as_str()
+      .parse::<BigInt>()
+      .map_err(|e| error!("failed to parse `{value}` as int: {e}"))?;
+    return int::from_bigint(&scope.thread.global, int);
   }
 
   fail!("could not parse `{value}` as int");
@@ -401,11 +428,12 @@ async fn collect(mut scope: Scope<'_>) -> Result<Value> {
     .ok_or_else(|| error!("`done` is not callable"))?
     .bind(scope.global());
 
-  let list = List::new();
+  let list = scope.try_alloc(List::new(), 0)?;
   while !is_truthy(scope.call(done.clone(), &[]).await?.unbind()) {
-    list.push(scope.call(next.clone(), &[]).await?.unbind());
+    let item = scope.call(next.clone(), &[]).await?.unbind();
+    list.reserve(List::ELEMENT_SIZE)?;
+    list.push(item);
   }
-  let list = scope.alloc(list);
 
   Ok(Value::object(list))
 }
@@ -452,6 +480,25 @@ pub fn register_builtin_functions(global: &Global) {
   bind_builtin_fn!(global, parse_int);
   bind_builtin_fn!(global, async collect);
 
+  bytes::register_builtin_functions(global);
   list::register_builtin_functions(global);
+  set::register_builtin_functions(global);
   string::register_builtin_functions(global);
+  table::register_builtin_functions(global);
+  tuple::register_builtin_functions(global);
+}
+
+/// Builtin classes which are implemented in Hebi.
+const BUILTIN_CLASSES: &str = r#"
+class Error:
+  message = none
+  init(self, message = none):
+    self.message = message
+"#;
+
+pub fn register_builtin_classes(vm: &mut Vm) {
+  let chunk = vm
+    .compile_named("<builtin>", BUILTIN_CLASSES)
+    .expect("failed to compile builtin classes");
+  pollster::block_on(vm.entry(chunk)).expect("failed to register builtin classes");
 }
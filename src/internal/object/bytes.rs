@@ -0,0 +1,396 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Write};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use super::builtin::BuiltinMethod;
+use super::gc::Tracer;
+use super::list::to_index;
+use super::{int, List, Object, Ptr, Str, Tuple};
+use crate::internal::error::Result;
+use crate::internal::value::Value;
+use crate::internal::vm::global::Global;
+use crate::public;
+use crate::public::{Scope, Unbind};
+
+/// An immutable sequence of bytes.
+#[derive(Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes {
+  data: Box<[u8]>,
+}
+
+impl Bytes {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  pub fn as_slice(&self) -> &[u8] {
+    &self.data
+  }
+
+  pub fn get(&self, index: usize) -> Option<u8> {
+    self.data.get(index).copied()
+  }
+
+  pub fn concat(&self, other: &[u8]) -> Self {
+    let mut out = Vec::with_capacity(self.len() + other.len());
+    out.extend_from_slice(&self.data);
+    out.extend_from_slice(other);
+    Self::from(out)
+  }
+
+  pub fn to_hex(&self) -> String {
+    let mut out = String::with_capacity(self.len() * 2);
+    for byte in self.data.iter() {
+      let _ = write!(out, "{byte:02x}");
+    }
+    out
+  }
+
+  pub fn from_hex(s: &str) -> Result<Self> {
+    if !s.len().is_multiple_of(2) {
+      fail!("invalid hex string: odd number of digits");
+    }
+    let digit = |c: u8| -> Result<u8> {
+      match (c as char).to_digit(16) {
+        Some(v) => Ok(v as u8),
+        None => fail!("invalid hex string: `{}` is not a hex digit", c as char),
+      }
+    };
+    let data = s
+      .as_bytes()
+      .chunks(2)
+      .map(|pair| Ok(digit(pair[0])? << 4 | digit(pair[1])?))
+      .collect::<Result<Vec<_>>>()?;
+    Ok(Self::from(data))
+  }
+
+  pub fn to_base64(&self) -> String {
+    BASE64.encode(&self.data)
+  }
+
+  pub fn from_base64(s: &str) -> Result<Self> {
+    match BASE64.decode(s) {
+      Ok(data) => Ok(Self::from(data)),
+      Err(e) => fail!("invalid base64 string: {e}"),
+    }
+  }
+}
+
+impl From<Vec<u8>> for Bytes {
+  fn from(data: Vec<u8>) -> Self {
+    Self {
+      data: data.into_boxed_slice(),
+    }
+  }
+}
+
+impl From<&[u8]> for Bytes {
+  fn from(data: &[u8]) -> Self {
+    Self { data: data.into() }
+  }
+}
+
+impl Display for Bytes {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<bytes>")
+  }
+}
+
+impl Debug for Bytes {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "b\"")?;
+    for byte in self.data.iter() {
+      write!(f, "{}", std::ascii::escape_default(*byte))?;
+    }
+    write!(f, "\"")
+  }
+}
+
+fn alloc_bytes(scope: &Scope<'_>, bytes: Bytes) -> Result<Value> {
+  let extra = bytes.len();
+  Ok(Value::object(scope.try_alloc(bytes, extra)?))
+}
+
+fn alloc_str(scope: &Scope<'_>, str: String) -> Result<Value> {
+  let extra = str.len();
+  Ok(Value::object(scope.try_alloc(Str::owned(str), extra)?))
+}
+
+fn bytes_len(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  Ok(int::from_i64(&scope.thread.global, this.len() as i64))
+}
+
+fn bytes_is_empty(this: Ptr<Bytes>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.is_empty()))
+}
+
+fn bytes_get(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  let index = scope.param::<public::Value>(0)?.unbind();
+  let index = to_index(index, this.len())?;
+  Ok(
+    this
+      .get(index)
+      .map(|v| int::from_i64(&scope.thread.global, v.into()))
+      .unwrap_or_else(Value::none),
+  )
+}
+
+/// `slice(start, end = len)`, where negative indices count from the end.
+fn bytes_slice(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  let len = this.len();
+  let start_arg = scope.param::<public::Value>(0)?.unbind();
+  let start = to_index(start_arg.clone(), len)?;
+  let (end_arg, end) = match scope.num_args() {
+    1 => (int::from_i64(&scope.thread.global, len as i64), len),
+    _ => {
+      let end_arg = scope.param::<public::Value>(1)?.unbind();
+      let end = to_index(end_arg.clone(), len)?;
+      (end_arg, end)
+    }
+  };
+  if start > end || end > len {
+    fail!("slice `{start_arg}..{end_arg}` out of bounds, len was `{len}`");
+  }
+  alloc_bytes(&scope, Bytes::from(&this.as_slice()[start..end]))
+}
+
+fn bytes_to_str(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  match std::str::from_utf8(this.as_slice()) {
+    Ok(str) => alloc_str(&scope, str.to_owned()),
+    Err(e) => fail!("bytes are not valid utf-8: {e}"),
+  }
+}
+
+fn bytes_to_hex(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  alloc_str(&scope, this.to_hex())
+}
+
+fn bytes_to_base64(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  alloc_str(&scope, this.to_base64())
+}
+
+#[derive(Debug)]
+pub struct BytesIter {
+  bytes: Ptr<Bytes>,
+  index: Cell<usize>,
+}
+
+impl Display for BytesIter {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<bytes iter>")
+  }
+}
+
+fn bytes_iter_iter(this: Ptr<BytesIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(this))
+}
+
+fn bytes_iter_next(this: Ptr<BytesIter>, scope: Scope<'_>) -> Result<Value> {
+  if let Some(item) = this.bytes.get(this.index.get()) {
+    this.index.set(this.index.get() + 1);
+    Ok(int::from_i64(&scope.thread.global, item.into()))
+  } else {
+    Ok(Value::none())
+  }
+}
+
+fn bytes_iter_done(this: Ptr<BytesIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.index.get() >= this.bytes.len()))
+}
+
+impl Object for BytesIter {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.bytes);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "BytesIter"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "iter" => builtin_method!(bytes_iter_iter),
+      "next" => builtin_method!(bytes_iter_next),
+      "done" => builtin_method!(bytes_iter_done),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+}
+
+declare_object_type!(BytesIter);
+
+fn bytes_iter(this: Ptr<Bytes>, scope: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(scope.try_alloc(
+    BytesIter {
+      bytes: this,
+      index: Cell::new(0),
+    },
+    0,
+  )?))
+}
+
+impl Object for Bytes {
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "Bytes"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "len" => builtin_method!(bytes_len),
+      "is_empty" => builtin_method!(bytes_is_empty),
+      "get" => builtin_method!(bytes_get),
+      "slice" => builtin_method!(bytes_slice),
+      "to_str" => builtin_method!(bytes_to_str),
+      "to_hex" => builtin_method!(bytes_to_hex),
+      "to_base64" => builtin_method!(bytes_to_base64),
+      "iter" => builtin_method!(bytes_iter),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+
+  fn keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
+    let len = this.len();
+    let index = to_index(key.clone(), len)?;
+    let value = this
+      .get(index)
+      .ok_or_else(|| error!("index `{key}` out of bounds, len was `{len}`"))?;
+    Ok(int::from_i64(&scope.thread.global, value.into()))
+  }
+
+  fn keyed_field_opt(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
+    let len = this.len();
+    let index = to_index(key, len)?;
+    Ok(
+      this
+        .get(index)
+        .map(|v| int::from_i64(&scope.thread.global, v.into())),
+    )
+  }
+
+  fn set_keyed_field(_: Scope<'_>, this: Ptr<Self>, _: Value, _: Value) -> Result<()> {
+    fail!("`{this}` is immutable")
+  }
+
+  fn add(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    alloc_bytes(&scope, this.concat(other.as_slice()))
+  }
+
+  fn cmp(_: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Ordering> {
+    Ok(this.as_slice().cmp(other.as_slice()))
+  }
+
+  fn eq(_: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    Ok(this.as_slice() == other.as_slice())
+  }
+}
+
+fn to_byte(value: Value) -> Result<u8> {
+  match int::to_i64(&value).and_then(|v| u8::try_from(v).ok()) {
+    Some(byte) => Ok(byte),
+    None => fail!("`{value}` is not a valid byte"),
+  }
+}
+
+/// `Bytes(value = none)`
+///
+/// Strings are encoded as UTF-8, lists and tuples must hold ints in the range
+/// `0..=255`.
+fn bytes_new(scope: Scope<'_>) -> Result<Value> {
+  if scope.num_args() == 0 {
+    return alloc_bytes(&scope, Bytes::new());
+  }
+  let value = scope.param::<public::Value>(0)?.unbind();
+  let bytes = if let Some(str) = value.clone().to_object::<Str>() {
+    Bytes::from(str.as_str().as_bytes())
+  } else if let Some(bytes) = value.clone().to_object::<Bytes>() {
+    Bytes::from(bytes.as_slice())
+  } else if let Some(list) = value.clone().to_object::<List>() {
+    let data = list.iter().map(to_byte).collect::<Result<Vec<_>>>()?;
+    Bytes::from(data)
+  } else if let Some(tuple) = value.clone().to_object::<Tuple>() {
+    let data = tuple.iter().map(to_byte).collect::<Result<Vec<_>>>()?;
+    Bytes::from(data)
+  } else {
+    fail!("cannot convert `{value}` to bytes");
+  };
+  alloc_bytes(&scope, bytes)
+}
+
+fn from_hex(scope: Scope<'_>) -> Result<Value> {
+  let str = scope.param::<public::Str>(0)?;
+  alloc_bytes(&scope, Bytes::from_hex(str.as_str())?)
+}
+
+fn from_base64(scope: Scope<'_>) -> Result<Value> {
+  let str = scope.param::<public::Str>(0)?;
+  alloc_bytes(&scope, Bytes::from_base64(str.as_str())?)
+}
+
+pub fn register_builtin_functions(global: &Global) {
+  bind_builtin_type!(
+    global,
+    builtin_type!(Bytes(bytes_new) {
+      len: builtin_method_static!(Bytes, bytes_len),
+      is_empty: builtin_method_static!(Bytes, bytes_is_empty),
+      get: builtin_method_static!(Bytes, bytes_get),
+      slice: builtin_method_static!(Bytes, bytes_slice),
+      to_str: builtin_method_static!(Bytes, bytes_to_str),
+      to_hex: builtin_method_static!(Bytes, bytes_to_hex),
+      to_base64: builtin_method_static!(Bytes, bytes_to_base64),
+      iter: builtin_method_static!(Bytes, bytes_iter),
+      from_hex: from_hex,
+      from_base64: from_base64
+    })
+  );
+}
+
+declare_object_type!(Bytes);
@@ -1,24 +1,27 @@
-use std::fmt::Display;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
 
 use indexmap::IndexMap;
 
+use super::gc::Tracer;
 use super::ptr::Ptr;
 use super::{BoundFunction, Function, FunctionDescriptor, Object, ReturnAddr, Str, Table};
 use crate::internal::error::Result;
 use crate::internal::value::Value;
 use crate::internal::vm::global::Global;
+use crate::internal::vm::thread::util::is_truthy;
 use crate::internal::vm::thread::CallResult;
 use crate::public::Scope;
 
-#[derive(Debug)]
 pub struct ClassInstance {
   pub name: Ptr<Str>,
   pub fields: Ptr<Table>,
+  pub class: Ptr<ClassType>,
   pub parent: Option<Ptr<ClassType>>,
 }
 
 impl ClassInstance {
-  pub fn new(global: Global, type_: &ClassType) -> Self {
+  pub fn new(global: Global, type_: Ptr<ClassType>) -> Self {
     let name = type_.name.clone();
     let fields = global.alloc(type_.fields.copy());
     for (key, method) in type_.methods.iter() {
@@ -28,9 +31,52 @@ impl ClassInstance {
     Self {
       name,
       fields,
+      class: type_,
       parent,
     }
   }
+
+  /// Call the method `name` of `this`, or return `None` if its class
+  /// doesn't define one.
+  ///
+  /// The method runs to completion before this returns.
+  pub fn call_method(
+    mut scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: &str,
+    args: &[Value],
+  ) -> Result<Option<Value>> {
+    let Some(method) = this.class.methods.get(name).cloned() else {
+      return Ok(None);
+    };
+    let method = scope.try_alloc(BoundFunction::new(this.into_any(), method), 0)?;
+    scope.thread.call_sync(method.into_any(), args).map(Some)
+  }
+
+  /// Call the magic method `name` of `this`, which implements `op`.
+  pub fn call_operator(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: &str,
+    op: &str,
+    args: &[Value],
+  ) -> Result<Value> {
+    match Self::call_method(scope, this.clone(), name, args)? {
+      Some(value) => Ok(value),
+      None => fail!("`{}` does not support {op}", this.name),
+    }
+  }
+}
+
+impl Debug for ClassInstance {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // `class` is omitted, because it's already identified by `name`
+    f.debug_struct("ClassInstance")
+      .field("name", &self.name)
+      .field("fields", &self.fields)
+      .field("parent", &self.parent)
+      .finish()
+  }
 }
 
 impl Display for ClassInstance {
@@ -40,12 +86,33 @@ impl Display for ClassInstance {
 }
 
 impl Object for ClassInstance {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.fields);
+    tracer.ptr(&self.class);
+    if let Some(parent) = self.parent.as_ref() {
+      tracer.ptr(parent);
+    }
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Instance"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
+  /// An instance is an instance of its class and all of its ancestors.
+  fn instance_of(this: Ptr<Self>, ty: Value) -> Result<bool> {
+    let Some(ty) = ty.to_object::<ClassType>() else {
+      return Ok(false);
+    };
+    let mut class = Some(this.class.clone());
+    while let Some(current) = class {
+      if current.ptr_eq(&ty) {
+        return Ok(true);
+      }
+      class = current.parent.clone();
+    }
+    Ok(false)
   }
 
   fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
@@ -57,7 +124,7 @@ impl Object for ClassInstance {
     // bind functions
     if let Some(function) = value.clone().to_object::<Function>() {
       return Ok(Value::object(
-        scope.alloc(BoundFunction::new(this.into_any(), function)),
+        scope.try_alloc(BoundFunction::new(this.into_any(), function), 0)?,
       ));
     }
 
@@ -71,7 +138,7 @@ impl Object for ClassInstance {
     if let Some(value) = value.clone() {
       if let Some(function) = value.to_object::<Function>() {
         return Ok(Some(Value::object(
-          scope.alloc(BoundFunction::new(this.into_any(), function)),
+          scope.try_alloc(BoundFunction::new(this.into_any(), function), 0)?,
         )));
       }
     }
@@ -86,6 +153,125 @@ impl Object for ClassInstance {
 
     Ok(())
   }
+
+  fn keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
+    Self::call_operator(scope, this, "__index__", "`[]`", &[key])
+  }
+
+  fn keyed_field_opt(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
+    Self::call_operator(scope, this, "__index__", "`[]`", &[key]).map(Some)
+  }
+
+  fn set_keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value, value: Value) -> Result<()> {
+    Self::call_operator(scope, this, "__setindex__", "`[]=`", &[key, value])?;
+    Ok(())
+  }
+
+  fn call(scope: Scope<'_>, this: Ptr<Self>, return_addr: ReturnAddr) -> Result<CallResult> {
+    let Some(method) = this.class.methods.get("__call__").cloned() else {
+      fail!("`{}` is not callable", this.name);
+    };
+    let method = scope.try_alloc(BoundFunction::new(this.into_any(), method), 0)?;
+    <BoundFunction as Object>::call(scope, method, return_addr)
+  }
+
+  fn contains(scope: Scope<'_>, this: Ptr<Self>, item: Value) -> Result<bool> {
+    let result = Self::call_operator(scope, this, "__contains__", "`in`", &[item])?;
+    Ok(is_truthy(result))
+  }
+
+  fn add(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__add__", "`+`", &[Value::object(other)])
+  }
+
+  fn subtract(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__sub__", "`-`", &[Value::object(other)])
+  }
+
+  fn multiply(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__mul__", "`*`", &[Value::object(other)])
+  }
+
+  fn divide(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__div__", "`/`", &[Value::object(other)])
+  }
+
+  fn remainder(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__rem__", "`%`", &[Value::object(other)])
+  }
+
+  fn pow(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__pow__", "`**`", &[Value::object(other)])
+  }
+
+  fn floor_divide(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__floordiv__", "`//`", &[Value::object(other)])
+  }
+
+  fn bit_and(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__and__", "`&`", &[Value::object(other)])
+  }
+
+  fn bit_or(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__or__", "`|`", &[Value::object(other)])
+  }
+
+  fn bit_xor(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__xor__", "`^`", &[Value::object(other)])
+  }
+
+  fn shift_left(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__lshift__", "`<<`", &[Value::object(other)])
+  }
+
+  fn shift_right(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__rshift__", "`>>`", &[Value::object(other)])
+  }
+
+  fn invert(scope: Scope<'_>, this: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__neg__", "unary `-`", &[])
+  }
+
+  fn bit_not(scope: Scope<'_>, this: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__invert__", "`~`", &[])
+  }
+
+  /// Derived from `__lt__`, so that instances may be sorted.
+  fn cmp(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Ordering> {
+    let args = [Value::object(other.clone())];
+    if is_truthy(Self::call_operator(
+      scope.clone(),
+      this.clone(),
+      "__lt__",
+      "comparison",
+      &args,
+    )?) {
+      return Ok(Ordering::Less);
+    }
+    let args = [Value::object(this)];
+    if is_truthy(Self::call_operator(
+      scope,
+      other,
+      "__lt__",
+      "comparison",
+      &args,
+    )?) {
+      return Ok(Ordering::Greater);
+    }
+    Ok(Ordering::Equal)
+  }
+
+  fn eq(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    match Self::call_method(
+      scope,
+      this.clone(),
+      "__eq__",
+      &[Value::object(other.clone())],
+    )? {
+      Some(result) => Ok(is_truthy(result)),
+      None => Ok(this.ptr_eq(&other)),
+    }
+  }
 }
 declare_object_type!(ClassInstance);
 
@@ -102,18 +288,19 @@ impl Display for ClassProxy {
 }
 
 impl Object for ClassProxy {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.this);
+    tracer.ptr(&self.class);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Instance"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    /*
-    class Foo(Bar):
-      fn test(self):
-        super is Foo ?
-        super is Bar ?
-    */
-    todo!()
+  fn instance_of(this: Ptr<Self>, ty: Value) -> Result<bool> {
+    <ClassInstance as Object>::instance_of(this.this.clone(), ty)
   }
 
   fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
@@ -125,7 +312,7 @@ impl Object for ClassProxy {
       .ok_or_else(|| error!("failed to get field `{name}`"))?;
 
     Ok(Value::object(
-      scope.alloc(BoundFunction::new(this.into_any(), method)),
+      scope.try_alloc(BoundFunction::new(this.into_any(), method), 0)?,
     ))
   }
 
@@ -135,7 +322,8 @@ impl Object for ClassProxy {
       .methods
       .get(name.as_str())
       .cloned()
-      .map(|method| scope.alloc(BoundFunction::new(this.into_any(), method)))
+      .map(|method| scope.try_alloc(BoundFunction::new(this.into_any(), method), 0))
+      .transpose()?
       .map(Value::object);
 
     Ok(method)
@@ -143,7 +331,7 @@ impl Object for ClassProxy {
 
   fn call(scope: Scope<'_>, this: Ptr<Self>, return_addr: ReturnAddr) -> Result<CallResult> {
     if let Some(init) = this.class.init.clone() {
-      let init = scope.alloc(BoundFunction::new(this.into_any(), init));
+      let init = scope.try_alloc(BoundFunction::new(this.into_any(), init), 0)?;
       <BoundFunction as Object>::call(scope, init, return_addr)
     } else {
       Ok(CallResult::Return(Value::none()))
@@ -189,15 +377,26 @@ impl Display for ClassType {
 }
 
 impl Object for ClassType {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    if let Some(init) = self.init.as_ref() {
+      tracer.ptr(init);
+    }
+    tracer.ptr(&self.fields);
+    for method in self.methods.values() {
+      tracer.ptr(method);
+    }
+    if let Some(parent) = self.parent.as_ref() {
+      tracer.ptr(parent);
+    }
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Class"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    // A is Type == true
-    // otherwise == false
-    todo!()
-  }
+  default_instance_of!();
 
   fn named_field(_: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
     let value = this
@@ -215,14 +414,14 @@ impl Object for ClassType {
   }
 
   fn call(scope: Scope<'_>, this: Ptr<Self>, return_addr: ReturnAddr) -> Result<CallResult> {
-    let instance = scope.alloc(ClassInstance::new(
-      scope.thread.global.clone(),
-      this.as_ref(),
-    ));
+    let instance = scope.try_alloc(
+      ClassInstance::new(scope.thread.global.clone(), this.clone()),
+      0,
+    )?;
 
     match this.init.as_ref() {
       Some(init) => {
-        let init = scope.alloc(BoundFunction::new(instance.into_any(), init.clone()));
+        let init = scope.try_alloc(BoundFunction::new(instance.into_any(), init.clone()), 0)?;
         <BoundFunction as Object>::call(scope, init, return_addr)
       }
       None => Ok(CallResult::Return(Value::object(instance))),
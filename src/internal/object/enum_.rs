@@ -0,0 +1,331 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Display};
+
+use indexmap::IndexMap;
+
+use super::gc::Tracer;
+use super::list::to_index;
+use super::{Object, Ptr, ReturnAddr, Str};
+use crate::internal::error::Result;
+use crate::internal::value::Value;
+use crate::internal::vm::thread::CallResult;
+use crate::public;
+use crate::public::{Scope, Unbind};
+
+/// The variants of an enum, each of which maps to the names of its payload
+/// fields. Unit variants have no fields.
+#[derive(Debug)]
+pub struct EnumDescriptor {
+  pub name: Ptr<Str>,
+  pub variants: IndexMap<Ptr<Str>, Vec<Ptr<Str>>>,
+}
+
+impl Display for EnumDescriptor {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<enum `{}` descriptor>", self.name)
+  }
+}
+
+impl Object for EnumDescriptor {
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "EnumDescriptor"
+  }
+
+  default_instance_of!();
+}
+
+declare_object_type!(EnumDescriptor);
+
+#[derive(Debug)]
+pub struct EnumType {
+  pub descriptor: Ptr<EnumDescriptor>,
+}
+
+impl EnumType {
+  pub fn new(descriptor: Ptr<EnumDescriptor>) -> Self {
+    Self { descriptor }
+  }
+
+  pub fn name(&self) -> &Ptr<Str> {
+    &self.descriptor.name
+  }
+
+  pub fn variant_index(&self, name: &str) -> Option<usize> {
+    self.descriptor.variants.get_index_of(name)
+  }
+
+  pub fn variant_name(&self, index: usize) -> &Ptr<Str> {
+    self.descriptor.variants.get_index(index).unwrap().0
+  }
+
+  pub fn variant_fields(&self, index: usize) -> &[Ptr<Str>] {
+    self.descriptor.variants.get_index(index).unwrap().1
+  }
+}
+
+impl Display for EnumType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<enum `{}`>", self.name())
+  }
+}
+
+impl Object for EnumType {
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "Enum"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("enum `{}` has no variant `{name}`", this.name()))?,
+    )
+  }
+
+  /// Unit variants evaluate to their only value, and payload variants to a
+  /// constructor for their values.
+  fn named_field_opt(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Option<Value>> {
+    let Some(index) = this.variant_index(name.as_str()) else {
+      return Ok(None);
+    };
+    if this.variant_fields(index).is_empty() {
+      let value = EnumValue::new(this, index, vec![]);
+      Ok(Some(Value::object(scope.try_alloc(value, 0)?)))
+    } else {
+      let variant = EnumVariant { ty: this, index };
+      Ok(Some(Value::object(scope.try_alloc(variant, 0)?)))
+    }
+  }
+}
+
+declare_object_type!(EnumType);
+
+/// Constructor for a variant which has a payload.
+#[derive(Debug)]
+pub struct EnumVariant {
+  pub ty: Ptr<EnumType>,
+  pub index: usize,
+}
+
+impl Display for EnumVariant {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "<variant `{}.{}`>",
+      self.ty.name(),
+      self.ty.variant_name(self.index)
+    )
+  }
+}
+
+impl Object for EnumVariant {
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "EnumVariant"
+  }
+
+  default_instance_of!();
+
+  fn call(scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
+    let name = format!("{}.{}", this.ty.name(), this.ty.variant_name(this.index));
+    let fields = this.ty.variant_fields(this.index);
+
+    let num_args = scope.num_args();
+    if num_args > fields.len() {
+      let plural = if fields.len() != 1 { "s" } else { "" };
+      fail!(
+        "`{name}` expected {} arg{plural}, got {num_args}",
+        fields.len()
+      );
+    }
+    let mut values = vec![None; fields.len()];
+    for (i, value) in values.iter_mut().enumerate().take(num_args) {
+      *value = Some(scope.param::<public::Value>(i)?.unbind());
+    }
+    if let Some(named) = scope.named.as_ref() {
+      for (key, value) in named.entries() {
+        let Some(i) = fields
+          .iter()
+          .position(|field| key.as_str() == Some(field.as_str()))
+        else {
+          fail!("`{name}` has no field `{key}`");
+        };
+        if values[i].is_some() {
+          fail!("field `{key}` was passed both by position and by name");
+        }
+        values[i] = Some(value);
+      }
+    }
+    let mut payload = Vec::with_capacity(fields.len());
+    for (value, field) in values.into_iter().zip(fields.iter()) {
+      let Some(value) = value else {
+        fail!("missing argument `{field}`");
+      };
+      payload.push(value);
+    }
+
+    let extra = payload.len() * std::mem::size_of::<Value>();
+    let value = EnumValue::new(this.ty.clone(), this.index, payload);
+    Ok(CallResult::Return(Value::object(
+      scope.try_alloc(value, extra)?,
+    )))
+  }
+}
+
+declare_object_type!(EnumVariant);
+
+/// A variant of an enum, along with its payload.
+pub struct EnumValue {
+  pub ty: Ptr<EnumType>,
+  pub index: usize,
+  // only ever mutated by `clear`
+  values: RefCell<Box<[Value]>>,
+}
+
+impl EnumValue {
+  pub fn new(ty: Ptr<EnumType>, index: usize, values: Vec<Value>) -> Self {
+    Self {
+      ty,
+      index,
+      values: RefCell::new(values.into_boxed_slice()),
+    }
+  }
+
+  pub fn variant_name(&self) -> &Ptr<Str> {
+    self.ty.variant_name(self.index)
+  }
+
+  pub fn len(&self) -> usize {
+    self.values.borrow().len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.borrow().is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<Value> {
+    self.values.borrow().get(index).cloned()
+  }
+
+  /// Retrieve the payload field called `name`.
+  pub fn field(&self, name: &str) -> Option<Value> {
+    let fields = self.ty.variant_fields(self.index);
+    let index = fields.iter().position(|field| field.as_str() == name)?;
+    self.get(index)
+  }
+}
+
+impl Display for EnumValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}.{}", self.ty.name(), self.variant_name())?;
+    let values = self.values.borrow();
+    if !values.is_empty() {
+      write!(f, "(")?;
+      for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+          write!(f, ", ")?;
+        }
+        write!(f, "{value}")?;
+      }
+      write!(f, ")")?;
+    }
+    Ok(())
+  }
+}
+
+impl Debug for EnumValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = format!("{}.{}", self.ty.name(), self.variant_name());
+    let mut f = f.debug_tuple(&name);
+    for value in self.values.borrow().iter() {
+      f.field(value);
+    }
+    f.finish()
+  }
+}
+
+impl Object for EnumValue {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.ty);
+    for value in self.values.borrow().iter() {
+      tracer.value(value);
+    }
+  }
+
+  fn clear(&self) {
+    let values = std::mem::take(&mut *self.values.borrow_mut());
+    drop(values);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "EnumValue"
+  }
+
+  /// A value is an instance of its enum and of its variant. Unit variants
+  /// are represented by their only value, so they are compared directly.
+  fn instance_of(this: Ptr<Self>, ty: Value) -> Result<bool> {
+    if let Some(ty) = ty.clone().to_object::<EnumType>() {
+      Ok(this.ty.ptr_eq(&ty))
+    } else if let Some(variant) = ty.clone().to_object::<EnumVariant>() {
+      Ok(this.ty.ptr_eq(&variant.ty) && this.index == variant.index)
+    } else if let Some(value) = ty.to_object::<EnumValue>() {
+      Ok(this.ty.ptr_eq(&value.ty) && this.index == value.index && value.is_empty())
+    } else {
+      Ok(false)
+    }
+  }
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(_: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Option<Value>> {
+    Ok(this.field(name.as_str()))
+  }
+
+  fn keyed_field(_: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
+    let len = this.len();
+    let index = to_index(key.clone(), len)?;
+    let value = this
+      .get(index)
+      .ok_or_else(|| error!("index `{key}` out of bounds, len was `{len}`"))?;
+    Ok(value)
+  }
+
+  fn keyed_field_opt(_: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
+    let len = this.len();
+    let index = to_index(key, len)?;
+    Ok(this.get(index))
+  }
+
+  fn set_keyed_field(_: Scope<'_>, this: Ptr<Self>, _: Value, _: Value) -> Result<()> {
+    fail!("`{this}` is immutable")
+  }
+
+  fn set_named_field(_: Scope<'_>, this: Ptr<Self>, _: Ptr<Str>, _: Value) -> Result<()> {
+    fail!("`{this}` is immutable")
+  }
+
+  fn eq(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    if !this.ty.ptr_eq(&other.ty) || this.index != other.index || this.len() != other.len() {
+      return Ok(false);
+    }
+
+    for i in 0..this.len() {
+      if !scope.are_equal(this.get(i).unwrap(), other.get(i).unwrap())? {
+        return Ok(false);
+      }
+    }
+
+    Ok(true)
+  }
+}
+
+declare_object_type!(EnumValue);
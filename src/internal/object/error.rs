@@ -0,0 +1,72 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Display};
+
+use super::{Object, Ptr, Str};
+use crate::internal::error::{Error, Result};
+use crate::internal::value::Value;
+use crate::internal::vm::global::Global;
+use crate::public::Scope;
+
+/// An error caught by a `catch` block.
+///
+/// Holds onto the original error, so that it can be re-raised without
+/// losing any information if the script throws it again.
+pub struct RuntimeError {
+  pub message: Ptr<Str>,
+  error: RefCell<Option<Error>>,
+}
+
+impl RuntimeError {
+  pub fn new(global: &Global, error: Error) -> Self {
+    Self {
+      message: global.alloc(Str::owned(&error)),
+      error: RefCell::new(Some(error)),
+    }
+  }
+
+  /// Take the original error out of this object.
+  ///
+  /// Returns `None` if it has already been taken.
+  pub fn take(&self) -> Option<Error> {
+    self.error.borrow_mut().take()
+  }
+}
+
+impl Object for RuntimeError {
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "RuntimeError"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(_: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Option<Value>> {
+    match name.as_str() {
+      "message" => Ok(Some(Value::object(this.message.clone()))),
+      _ => Ok(None),
+    }
+  }
+}
+
+declare_object_type!(RuntimeError);
+
+impl Display for RuntimeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl Debug for RuntimeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("RuntimeError")
+      .field("message", &self.message)
+      .finish()
+  }
+}
@@ -2,17 +2,20 @@ use std::cell::RefCell;
 use std::fmt::{Debug, Display};
 use std::ptr::NonNull;
 
+use super::builtin::BuiltinMethod;
+use super::gc::Tracer;
 use super::module::ModuleId;
 use super::ptr::Ptr;
 use super::{Any, List, Object, ReturnAddr, Str};
+use crate::internal::bytecode::spans::SpanTable;
 use crate::internal::bytecode::{disasm, opcode as op};
 use crate::internal::error::Result;
 use crate::internal::object;
 use crate::internal::value::constant::Constant;
 use crate::internal::value::Value;
 use crate::internal::vm::dispatch::LoadFrame;
-use crate::internal::vm::thread::util::check_args;
-use crate::internal::vm::thread::{Args, CallResult, Frame, Slot0, Thread};
+use crate::internal::vm::thread::util::{check_args, is_truthy, split_rest_args};
+use crate::internal::vm::thread::{Args, CallResult, Frame, Slot0, Thread, TryFrame};
 use crate::public::Scope;
 
 #[derive(Debug)]
@@ -73,14 +76,17 @@ impl Function {
     let descriptor = function.descriptor.as_ref();
     let bytecode = descriptor.instructions;
     check_args(&descriptor.params, false, args.count)?;
+    let (args, rest) = split_rest_args(thread, &descriptor.params, false, args)?;
+    let rest_index = descriptor.params.rest_index();
 
     let frame_size = descriptor.frame_size;
     let stack = unsafe { thread.stack.as_mut() };
+    let stack_base = stack.regs.len();
 
     thread.pc = 0;
     stack
       .frames
-      .push(Frame::new(function, stack.regs.len(), return_addr));
+      .push(Frame::new(function, stack_base, return_addr));
 
     stack.regs.reserve(frame_size);
 
@@ -101,20 +107,65 @@ impl Function {
         .extend((0..frame_size - args.count).map(|_| Value::none()));
     }
 
+    if let Some(rest) = rest {
+      stack.regs[stack_base + rest_index] = rest;
+    }
+
     Ok(LoadFrame { bytecode, pc: 0 })
   }
+
+  /// Create a suspended call to a generator function. The generator's
+  /// registers start out as they would for a normal call.
+  fn start_generator(
+    this: &Ptr<Self>,
+    scope: &Scope<'_>,
+    slot0: Option<Value>,
+    args: Args,
+    rest: Option<Value>,
+  ) -> Result<CallResult> {
+    let function = this.as_ref();
+    let frame_size = function.descriptor.frame_size;
+    let stack = unsafe { scope.thread.stack.as_ref() };
+
+    let mut regs = Vec::with_capacity(frame_size);
+    regs.extend(slot0);
+    regs.extend_from_slice(&stack.regs[args.start..args.start + args.count]);
+    regs.resize_with(frame_size, Value::none);
+    if let Some(rest) = rest {
+      regs[function.descriptor.params.rest_index()] = rest;
+    }
+
+    let extra = regs.capacity() * List::ELEMENT_SIZE;
+    let generator = scope
+      .thread
+      .global
+      .try_alloc(Generator::new(function, regs), extra)?;
+    Ok(CallResult::Return(Value::object(generator)))
+  }
 }
 
 impl Object for Function {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.upvalues);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Function"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(mut scope: Scope<'_>, this: Ptr<Self>, return_addr: ReturnAddr) -> Result<CallResult> {
+    let descriptor = this.descriptor.as_ref();
+    if descriptor.is_generator {
+      check_args(&descriptor.params, false, scope.num_args())?;
+      let (args, rest) = split_rest_args(&scope.thread, &descriptor.params, false, scope.args)?;
+      let slot0 = (!descriptor.params.has_self).then(|| Value::object(this.clone()));
+      return Function::start_generator(&this, &scope, slot0, args, rest);
+    }
+
     Self::prepare_call(this, &mut scope.thread, scope.args, return_addr)
       .map(|_| CallResult::Dispatch)
   }
@@ -128,20 +179,221 @@ impl Display for Function {
   }
 }
 
+/// The suspended frame of a call to a generator function.
+///
+/// Generators implement the iterator protocol. `done` runs the generator
+/// until its next `yield`, and stashes the yielded value so that `next`
+/// can return it without running the generator again.
 #[derive(Debug)]
 pub struct Generator {
   pub descriptor: Ptr<FunctionDescriptor>,
   pub upvalues: Ptr<List>,
   pub module: ModuleId,
+  state: RefCell<GeneratorState>,
+}
+
+#[derive(Debug, Default)]
+struct GeneratorState {
+  status: Status,
+  /// Registers of the suspended frame.
+  regs: Vec<Value>,
+  /// Offset of the instruction to resume at.
+  pc: usize,
+  /// Catch addresses of the `try` blocks which were active at the `yield`.
+  handlers: Vec<usize>,
+  /// A value yielded in response to `done`, to be returned by `next`.
+  peeked: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Status {
+  #[default]
+  Suspended,
+  Running,
+  Done,
+}
+
+/// The iterator method which resumed a generator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resume {
+  Next,
+  Done,
+}
+
+impl Generator {
+  fn new(function: &Function, regs: Vec<Value>) -> Self {
+    Self {
+      descriptor: function.descriptor.clone(),
+      upvalues: function.upvalues.clone(),
+      module: function.module_id,
+      state: RefCell::new(GeneratorState {
+        regs,
+        ..Default::default()
+      }),
+    }
+  }
+
+  pub fn is_done(&self) -> bool {
+    let state = self.state.borrow();
+    state.status == Status::Done && state.peeked.is_none()
+  }
+
+  /// Offset of the instruction to resume at.
+  pub fn pc(&self) -> usize {
+    self.state.borrow().pc
+  }
+
+  /// Push the suspended frame onto the call stack. If the generator can
+  /// answer `resume` without running, the answer is returned instead.
+  pub fn resume(
+    this: Ptr<Self>,
+    thread: &mut Thread,
+    resume: Resume,
+    return_addr: ReturnAddr,
+  ) -> Result<CallResult> {
+    let mut state = this.state.borrow_mut();
+    match (state.status, resume) {
+      (Status::Running, _) => fail!("generator `{}` is already running", this.descriptor.name),
+      (Status::Done, Resume::Next) => return Ok(CallResult::Return(Value::none())),
+      (Status::Done, Resume::Done) => return Ok(CallResult::Return(Value::bool(true))),
+      (Status::Suspended, _) => {}
+    }
+    if state.peeked.is_some() {
+      return Ok(CallResult::Return(match resume {
+        Resume::Next => state.peeked.take().unwrap_or_default(),
+        Resume::Done => Value::bool(false),
+      }));
+    }
+
+    state.status = Status::Running;
+    let stack = unsafe { thread.stack.as_mut() };
+    let frame_index = stack.frames.len();
+    stack
+      .frames
+      .push(Frame::resume(&this, resume, stack.regs.len(), return_addr));
+    stack.regs.append(&mut state.regs);
+    stack.handlers.extend(
+      state
+        .handlers
+        .drain(..)
+        .map(|catch_addr| TryFrame::new(frame_index, catch_addr)),
+    );
+
+    Ok(CallResult::Dispatch)
+  }
+
+  /// Save the state of the generator's frame at a `yield`, returning the
+  /// result of the call which resumed it.
+  pub fn suspend(
+    &self,
+    resume: Resume,
+    regs: Vec<Value>,
+    pc: usize,
+    handlers: Vec<usize>,
+    value: Value,
+  ) -> Value {
+    let mut state = self.state.borrow_mut();
+    state.status = Status::Suspended;
+    state.regs = regs;
+    state.pc = pc;
+    state.handlers = handlers;
+    match resume {
+      Resume::Next => value,
+      Resume::Done => {
+        state.peeked = Some(value);
+        Value::bool(false)
+      }
+    }
+  }
+
+  /// Mark the generator as done after it returns, returning the result of
+  /// the call which resumed it.
+  pub fn finish(&self, resume: Resume) -> Value {
+    self.abandon();
+    match resume {
+      Resume::Next => Value::none(),
+      Resume::Done => Value::bool(true),
+    }
+  }
+
+  /// Called when the generator's frame is popped. If this happens while it
+  /// is running, the frame was unwound by an error, and the generator may
+  /// not be resumed again.
+  pub fn abandon(&self) {
+    let mut state = self.state.borrow_mut();
+    if state.status == Status::Running {
+      state.status = Status::Done;
+      state.regs = Vec::new();
+      state.handlers = Vec::new();
+    }
+  }
+
+  /// Resume the generator from outside of the VM, returning `None` once it
+  /// is done.
+  pub async fn next(this: Ptr<Self>, thread: &mut Thread) -> Result<Option<Value>> {
+    let done = thread
+      .global
+      .alloc(GeneratorMethod::new(this.clone(), Resume::Done));
+    if is_truthy(thread.call(done.into_any(), &[]).await?) {
+      return Ok(None);
+    }
+    let next = thread
+      .global
+      .alloc(GeneratorMethod::new(this, Resume::Next));
+    thread.call(next.into_any(), &[]).await.map(Some)
+  }
+}
+
+fn generator_iter(this: Ptr<Generator>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(this))
 }
 
 impl Object for Generator {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.upvalues);
+    let state = self.state.borrow();
+    for value in state.regs.iter().chain(state.peeked.iter()) {
+      tracer.value(value);
+    }
+  }
+
+  fn clear(&self) {
+    let state = std::mem::take(&mut *self.state.borrow_mut());
+    drop(state);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Generator"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Option<Value>> {
+    let resume = match name.as_str() {
+      "iter" => {
+        let method = builtin_method!(generator_iter);
+        return Ok(Some(Value::object(unsafe {
+          scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+        })));
+      }
+      "next" => Resume::Next,
+      "done" => Resume::Done,
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(
+      scope.try_alloc(GeneratorMethod::new(this, resume), 0)?,
+    )))
   }
 }
 
@@ -153,15 +405,68 @@ impl Display for Generator {
   }
 }
 
+/// The `next` or `done` method of a generator.
+///
+/// Unlike builtin methods, calling these pushes a frame onto the call stack.
+#[derive(Debug)]
+pub struct GeneratorMethod {
+  generator: Ptr<Generator>,
+  resume: Resume,
+}
+
+impl GeneratorMethod {
+  pub fn new(generator: Ptr<Generator>, resume: Resume) -> Self {
+    Self { generator, resume }
+  }
+}
+
+impl Display for GeneratorMethod {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self.resume {
+      Resume::Next => "next",
+      Resume::Done => "done",
+    };
+    write!(f, "<bound fn `{}.{name}`>", self.generator.descriptor.name)
+  }
+}
+
+impl Object for GeneratorMethod {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.generator);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "GeneratorMethod"
+  }
+
+  default_instance_of!();
+
+  fn call(mut scope: Scope<'_>, this: Ptr<Self>, return_addr: ReturnAddr) -> Result<CallResult> {
+    check_args(&Params::empty(), false, scope.num_args())?;
+    Generator::resume(
+      this.generator.clone(),
+      &mut scope.thread,
+      this.resume,
+      return_addr,
+    )
+  }
+}
+
+declare_object_type!(GeneratorMethod);
+
 pub struct FunctionDescriptor {
   pub name: Ptr<Str>,
   pub is_generator: bool,
   pub params: Params,
+  /// Names of the positional params, used to match named arguments.
+  pub param_names: Vec<Ptr<Str>>,
   pub upvalues: RefCell<Vec<Upvalue>>,
   pub frame_size: usize,
   pub instructions: NonNull<[u8]>,
   pub constants: NonNull<[Constant]>,
-  // TODO: spans
+  pub spans: SpanTable,
 }
 
 #[derive(Debug)]
@@ -175,14 +480,17 @@ fn vec_to_nonnull_ptr<T>(v: Vec<T>) -> NonNull<[T]> {
 }
 
 impl FunctionDescriptor {
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     name: Ptr<Str>,
     is_generator: bool,
     params: Params,
+    param_names: Vec<Ptr<Str>>,
     upvalues: Vec<Upvalue>,
     frame_size: usize,
     instructions: Vec<u8>,
     constants: Vec<Constant>,
+    spans: SpanTable,
   ) -> Self {
     let instructions = vec_to_nonnull_ptr(instructions);
     let constants = vec_to_nonnull_ptr(constants);
@@ -190,10 +498,12 @@ impl FunctionDescriptor {
       name,
       is_generator,
       params,
+      param_names,
       upvalues: RefCell::new(upvalues),
       frame_size,
       instructions,
       constants,
+      spans,
     }
   }
 }
@@ -312,6 +622,7 @@ pub struct Params {
   pub has_self: bool,
   pub min: u16,
   pub max: u16,
+  pub has_rest: bool,
 }
 
 impl Params {
@@ -320,11 +631,17 @@ impl Params {
       has_self: false,
       min: 0,
       max: 0,
+      has_rest: false,
     }
   }
 
   pub fn is_empty(&self) -> bool {
-    self.min == 0 && self.max == 0
+    self.min == 0 && self.max == 0 && !self.has_rest
+  }
+
+  /// Register which holds the rest param, relative to the frame's base.
+  pub fn rest_index(&self) -> usize {
+    self.max as usize + 1
   }
 }
 
@@ -347,6 +664,10 @@ impl BoundFunction {
 
     Self { this, function }
   }
+
+  pub fn function(&self) -> &Ptr<Function> {
+    &self.function
+  }
 }
 
 impl Display for BoundFunction {
@@ -356,19 +677,30 @@ impl Display for BoundFunction {
 }
 
 impl Object for BoundFunction {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.this);
+    tracer.ptr(&self.function);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "BoundFunction"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(mut scope: Scope<'_>, this: Ptr<Self>, return_addr: ReturnAddr) -> Result<CallResult> {
     let bound_function = this.as_ref();
     let function = bound_function.function.as_ref();
     let descriptor = function.descriptor.as_ref();
     check_args(&descriptor.params, true, scope.num_args())?;
+    let (args, rest) = split_rest_args(&scope.thread, &descriptor.params, true, scope.args)?;
+
+    if descriptor.is_generator {
+      let slot0 = Some(Value::object(this.this.clone()));
+      return Function::start_generator(&bound_function.function, &scope, slot0, args, rest);
+    }
 
     scope.thread.pc = 0;
     let stack = unsafe { scope.thread.stack.as_mut() };
@@ -376,11 +708,14 @@ impl Object for BoundFunction {
       .frames
       .push(Frame::new(function, stack.regs.len(), return_addr));
 
-    let _ = scope.enter_nested(
+    let frame = scope.enter_nested(
       Slot0::Receiver(Value::object(this.this.clone())),
-      scope.args,
+      args,
       Some(descriptor.frame_size),
     );
+    if let Some(rest) = rest {
+      stack.regs[frame.args.start + descriptor.params.rest_index()] = rest;
+    }
 
     Ok(CallResult::Dispatch)
   }
@@ -0,0 +1,166 @@
+//! Cycle collector.
+//!
+//! Objects are freed as soon as their reference count drops to zero, which
+//! never happens for objects that are part of a reference cycle. To find
+//! those, whenever a reference to a traceable object is dropped without
+//! freeing it, the object is buffered as a possible root of a garbage cycle.
+//!
+//! A collection then performs trial deletion (Bacon & Rajan, "Concurrent
+//! Cycle Collection in Reference Counted Systems") on the subgraph reachable
+//! from the buffered roots:
+//!
+//! 1. References between objects in the subgraph are subtracted from each
+//!    object's reference count.
+//! 2. Objects with references left over are referenced from outside of the
+//!    subgraph, so they and everything reachable from them are alive.
+//! 3. The remaining objects are only referenced by each other. They are
+//!    cleared, which breaks the cycles and lets reference counting free them.
+//!
+//! Trial deletion works on a copy of the reference count, so a collection
+//! never frees an object directly.
+
+use std::cell::Cell;
+use std::mem::ManuallyDrop;
+use std::ops::Add;
+
+use super::ptr::{dealloc_zombie, Heap, RawPtr};
+use super::{Any, Ptr};
+use crate::internal::value::Value;
+
+/// Statistics about objects freed by the cycle collector.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GcStats {
+  /// The number of collections.
+  pub collections: u64,
+  /// The number of objects which were found to be garbage.
+  pub objects: u64,
+  /// The number of bytes which were freed.
+  pub bytes: usize,
+}
+
+impl Add for GcStats {
+  type Output = GcStats;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    GcStats {
+      collections: self.collections + rhs.collections,
+      objects: self.objects + rhs.objects,
+      bytes: self.bytes + rhs.bytes,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum Color {
+  /// In use, or not visited by the current collection.
+  #[default]
+  Black,
+  /// Visited by the current collection, and possibly garbage.
+  Gray,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct GcHeader {
+  pub(super) buffered: Cell<bool>,
+  color: Cell<Color>,
+  /// Reference count used during trial deletion.
+  refs: Cell<u64>,
+}
+
+/// Collects the references held by an object.
+pub struct Tracer<'a> {
+  edges: &'a mut Vec<RawPtr>,
+}
+
+impl<'a> Tracer<'a> {
+  pub fn value(&mut self, value: &Value) {
+    if let Some(addr) = value.object_addr() {
+      let ptr = ManuallyDrop::new(unsafe { Ptr::<Any>::from_addr(addr) });
+      self.ptr(&*ptr);
+    }
+  }
+
+  pub fn ptr<T>(&mut self, ptr: &Ptr<T>) {
+    let raw = ptr.raw();
+    // objects which cannot reference other objects can't be part of a cycle
+    if unsafe { raw.as_ref() }.vtable.traceable {
+      self.edges.push(raw);
+    }
+  }
+}
+
+fn trace(ptr: RawPtr, edges: &mut Vec<RawPtr>) {
+  let repr = unsafe { ptr.as_ref() };
+  (repr.vtable.trace)(&repr.data, &mut Tracer { edges });
+}
+
+/// Find and clear every garbage cycle reachable from the heap's possible
+/// roots, returning the number of objects which were cleared.
+pub(super) fn collect(heap: &Heap) -> u64 {
+  let mut stack = Vec::new();
+  for ptr in heap.take_roots() {
+    let repr = unsafe { ptr.as_ref() };
+    repr.gc.buffered.set(false);
+    if repr.refs.get() == 0 {
+      unsafe { dealloc_zombie(ptr) };
+    } else {
+      stack.push(ptr);
+    }
+  }
+
+  // find everything reachable from the roots
+  let mut nodes = Vec::new();
+  while let Some(ptr) = stack.pop() {
+    let repr = unsafe { ptr.as_ref() };
+    if repr.gc.color.get() == Color::Gray {
+      continue;
+    }
+    repr.gc.color.set(Color::Gray);
+    repr.gc.refs.set(repr.refs.get());
+    nodes.push(ptr);
+    trace(ptr, &mut stack);
+  }
+
+  // subtract internal references
+  let mut edges = Vec::new();
+  for &ptr in nodes.iter() {
+    trace(ptr, &mut edges);
+    for edge in edges.drain(..) {
+      let gc = &unsafe { edge.as_ref() }.gc;
+      gc.refs.set(gc.refs.get() - 1);
+    }
+  }
+
+  // anything with external references is alive, along with everything it references
+  stack.extend(
+    nodes
+      .iter()
+      .copied()
+      .filter(|ptr| unsafe { ptr.as_ref() }.gc.refs.get() > 0),
+  );
+  while let Some(ptr) = stack.pop() {
+    let repr = unsafe { ptr.as_ref() };
+    if repr.gc.color.get() == Color::Black {
+      continue;
+    }
+    repr.gc.color.set(Color::Black);
+    trace(ptr, &mut stack);
+  }
+
+  // whatever is left is garbage, which is kept alive while it's being cleared
+  let garbage = nodes
+    .iter()
+    .copied()
+    .filter(|ptr| unsafe { ptr.as_ref() }.gc.color.get() == Color::Gray)
+    .map(|ptr| {
+      unsafe { ptr.as_ref() }.gc.color.set(Color::Black);
+      unsafe { Ptr::<Any>::from_raw(ptr) }
+    })
+    .collect::<Vec<_>>();
+  for ptr in garbage.iter() {
+    let repr = unsafe { ptr.raw().as_ref() };
+    (repr.vtable.clear)(&repr.data);
+  }
+
+  garbage.len() as u64
+}
@@ -0,0 +1,316 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
+
+use super::{Object, Ptr};
+use crate::internal::error::Result;
+use crate::internal::value::Value;
+use crate::internal::vm::global::Global;
+use crate::public::Scope;
+
+/// An integer which does not fit into the inline `i32` payload of a value.
+///
+/// Integers are always stored in the smallest representation which can hold
+/// them: an `i32` is stored inline, an `Int::I64` is never in `i32` range, and
+/// an `Int::Big` is never in `i64` range.
+pub enum Int {
+  I64(i64),
+  Big(BigInt),
+}
+
+impl Display for Int {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Int::I64(v) => write!(f, "{v}"),
+      Int::Big(v) => write!(f, "{v}"),
+    }
+  }
+}
+
+impl Debug for Int {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Display::fmt(self, f)
+  }
+}
+
+impl Object for Int {
+  fn type_name(this: Ptr<Self>) -> &'static str {
+    match &*this {
+      Int::I64(_) => "int",
+      Int::Big(_) => "bigint",
+    }
+  }
+
+  default_instance_of!();
+
+  fn invert(scope: Scope<'_>, this: Ptr<Self>) -> Result<Value> {
+    neg(&scope.thread.global, &Value::object(this))
+  }
+
+  fn cmp(_: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Ordering> {
+    Ok(cmp(&Value::object(this), &Value::object(other)))
+  }
+
+  fn eq(_: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    Ok(cmp(&Value::object(this), &Value::object(other)).is_eq())
+  }
+}
+
+declare_object_type!(Int);
+
+/// Whether `value` is an integer, regardless of how it is stored.
+pub fn is_int(value: &Value) -> bool {
+  value.is_int() || value.clone().to_object::<Int>().is_some()
+}
+
+/// Whether `value` is the integer `0`.
+///
+/// Only inline integers can be zero, because boxed integers are never in
+/// `i32` range.
+pub fn is_zero(value: &Value) -> bool {
+  value.clone().to_int() == Some(0)
+}
+
+/// Returns the value of an integer if it fits into an `i64`.
+pub fn to_i64(value: &Value) -> Option<i64> {
+  if let Some(v) = value.clone().to_int() {
+    return Some(v as i64);
+  }
+  match &*value.clone().to_object::<Int>()? {
+    Int::I64(v) => Some(*v),
+    Int::Big(_) => None,
+  }
+}
+
+pub fn to_i128(value: &Value) -> Option<i128> {
+  if let Some(v) = to_i64(value) {
+    return Some(v as i128);
+  }
+  match &*value.clone().to_object::<Int>()? {
+    Int::I64(v) => Some(*v as i128),
+    Int::Big(v) => v.to_i128(),
+  }
+}
+
+pub fn to_bigint(value: &Value) -> Option<BigInt> {
+  if let Some(v) = to_i64(value) {
+    return Some(BigInt::from(v));
+  }
+  match &*value.clone().to_object::<Int>()? {
+    Int::I64(v) => Some(BigInt::from(*v)),
+    Int::Big(v) => Some(v.clone()),
+  }
+}
+
+/// Converts an integer to the nearest `f64`.
+pub fn to_f64(value: &Value) -> Option<f64> {
+  if let Some(v) = to_i64(value) {
+    return Some(v as f64);
+  }
+  match &*value.clone().to_object::<Int>()? {
+    Int::I64(v) => Some(*v as f64),
+    Int::Big(v) => v.to_f64(),
+  }
+}
+
+pub fn from_i64(global: &Global, v: i64) -> Value {
+  match i32::try_from(v) {
+    Ok(v) => Value::int(v),
+    Err(_) => Value::object(global.alloc(Int::I64(v))),
+  }
+}
+
+pub fn from_i128(global: &Global, v: i128) -> Value {
+  match i64::try_from(v) {
+    Ok(v) => from_i64(global, v),
+    Err(_) => Value::object(global.alloc(Int::Big(BigInt::from(v)))),
+  }
+}
+
+/// Fails if the digits of `v` would exceed the memory limit.
+pub fn from_bigint(global: &Global, v: BigInt) -> Result<Value> {
+  match v.to_i64() {
+    Some(v) => Ok(from_i64(global, v)),
+    None => {
+      let extra = (v.bits() / 8) as usize;
+      Ok(Value::object(global.try_alloc(Int::Big(v), extra)?))
+    }
+  }
+}
+
+/// Truncates `v` towards zero, or returns `None` if it is not finite.
+pub fn from_f64(global: &Global, v: f64) -> Result<Option<Value>> {
+  BigInt::from_f64(v.trunc())
+    .map(|v| from_bigint(global, v))
+    .transpose()
+}
+
+// The arithmetic functions below expect both operands to be integers, and fail
+// if the result would exceed the memory limit.
+
+pub fn add(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  if let (Some(lhs), Some(rhs)) = (lhs.clone().to_int(), rhs.clone().to_int()) {
+    if let Some(v) = lhs.checked_add(rhs) {
+      return Ok(Value::int(v));
+    }
+  }
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => Ok(from_i128(global, lhs as i128 + rhs as i128)),
+    _ => from_bigint(global, bigint(lhs) + bigint(rhs)),
+  }
+}
+
+pub fn sub(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  if let (Some(lhs), Some(rhs)) = (lhs.clone().to_int(), rhs.clone().to_int()) {
+    if let Some(v) = lhs.checked_sub(rhs) {
+      return Ok(Value::int(v));
+    }
+  }
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => Ok(from_i128(global, lhs as i128 - rhs as i128)),
+    _ => from_bigint(global, bigint(lhs) - bigint(rhs)),
+  }
+}
+
+pub fn mul(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  if let (Some(lhs), Some(rhs)) = (lhs.clone().to_int(), rhs.clone().to_int()) {
+    if let Some(v) = lhs.checked_mul(rhs) {
+      return Ok(Value::int(v));
+    }
+  }
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => Ok(from_i128(global, lhs as i128 * rhs as i128)),
+    _ => from_bigint(global, bigint(lhs) * bigint(rhs)),
+  }
+}
+
+pub fn neg(global: &Global, value: &Value) -> Result<Value> {
+  match to_i64(value) {
+    Some(v) => Ok(from_i128(global, -(v as i128))),
+    None => from_bigint(global, -bigint(value)),
+  }
+}
+
+/// Divides `lhs` by `rhs`, rounding towards negative infinity.
+///
+/// `rhs` must not be zero.
+pub fn floor_div(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => {
+      let (lhs, rhs) = (lhs as i128, rhs as i128);
+      let quotient = lhs / rhs;
+      if lhs % rhs != 0 && (lhs < 0) != (rhs < 0) {
+        Ok(from_i128(global, quotient - 1))
+      } else {
+        Ok(from_i128(global, quotient))
+      }
+    }
+    _ => {
+      let (lhs, rhs) = (bigint(lhs), bigint(rhs));
+      let quotient = &lhs / &rhs;
+      if !(&lhs % &rhs).is_zero() && lhs.is_negative() != rhs.is_negative() {
+        from_bigint(global, quotient - 1)
+      } else {
+        from_bigint(global, quotient)
+      }
+    }
+  }
+}
+
+/// The remainder of dividing `lhs` by `rhs`, which has the same sign as `lhs`.
+///
+/// `rhs` must not be zero.
+pub fn rem(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  match (to_i64(lhs), to_i64(rhs)) {
+    // `i64::MIN % -1` overflows, but not in `i128`
+    (Some(lhs), Some(rhs)) => Ok(from_i128(global, lhs as i128 % rhs as i128)),
+    _ => from_bigint(global, bigint(lhs) % bigint(rhs)),
+  }
+}
+
+pub fn bit_and(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => Ok(from_i64(global, lhs & rhs)),
+    _ => from_bigint(global, bigint(lhs) & bigint(rhs)),
+  }
+}
+
+pub fn bit_or(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => Ok(from_i64(global, lhs | rhs)),
+    _ => from_bigint(global, bigint(lhs) | bigint(rhs)),
+  }
+}
+
+pub fn bit_xor(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => Ok(from_i64(global, lhs ^ rhs)),
+    _ => from_bigint(global, bigint(lhs) ^ bigint(rhs)),
+  }
+}
+
+/// Two's complement bitwise negation, which is equal to `-value - 1`.
+pub fn bit_not(global: &Global, value: &Value) -> Result<Value> {
+  match to_i64(value) {
+    Some(v) => Ok(from_i64(global, !v)),
+    None => from_bigint(global, !bigint(value)),
+  }
+}
+
+/// The largest number of bits `shl` will produce, so that a single shift can't
+/// exhaust memory when there is no memory limit.
+const MAX_SHL_BITS: u64 = 1 << 26;
+
+pub fn shl(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  let amount = shift_amount(rhs)?;
+  if let Some(lhs) = to_i64(lhs) {
+    if lhs == 0 {
+      return Ok(Value::int(0));
+    }
+    if amount < 64 {
+      return Ok(from_i128(global, (lhs as i128) << amount));
+    }
+  }
+  let lhs = bigint(lhs);
+  if lhs.bits().saturating_add(amount as u64) > MAX_SHL_BITS {
+    fail!("shift count `{rhs}` is too large");
+  }
+  from_bigint(global, lhs << amount)
+}
+
+/// Arithmetic shift, which rounds towards negative infinity.
+pub fn shr(global: &Global, lhs: &Value, rhs: &Value) -> Result<Value> {
+  let amount = shift_amount(rhs)?;
+  match to_i64(lhs) {
+    Some(lhs) => Ok(from_i64(global, lhs >> amount.min(63))),
+    None => from_bigint(global, bigint(lhs) >> amount),
+  }
+}
+
+fn shift_amount(value: &Value) -> Result<usize> {
+  let negative = match to_i64(value) {
+    Some(v) => v < 0,
+    None => bigint(value).is_negative(),
+  };
+  if negative {
+    fail!("negative shift count `{value}`");
+  }
+  match to_i64(value).and_then(|v| usize::try_from(v).ok()) {
+    Some(amount) => Ok(amount),
+    None => fail!("shift count `{value}` is too large"),
+  }
+}
+
+pub fn cmp(lhs: &Value, rhs: &Value) -> Ordering {
+  match (to_i64(lhs), to_i64(rhs)) {
+    (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+    _ => bigint(lhs).cmp(&bigint(rhs)),
+  }
+}
+
+fn bigint(value: &Value) -> BigInt {
+  debug_assert!(is_int(value), "`{value}` is not an int");
+  to_bigint(value).unwrap_or_default()
+}
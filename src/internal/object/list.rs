@@ -3,6 +3,7 @@ use std::fmt::{Debug, Display};
 use std::vec::Vec;
 
 use super::builtin::BuiltinMethod;
+use super::gc::Tracer;
 use super::{Object, Ptr, Str};
 use crate::internal::error::Result;
 use crate::internal::value::Value;
@@ -17,6 +18,9 @@ pub struct List {
 }
 
 impl List {
+  /// Approximate number of bytes used by each element.
+  pub const ELEMENT_SIZE: usize = std::mem::size_of::<Value>();
+
   pub fn new() -> Self {
     Self::with_capacity(0)
   }
@@ -154,12 +158,19 @@ fn list_set(this: Ptr<List>, scope: Scope<'_>) -> Result<Value> {
 
 fn list_push(this: Ptr<List>, scope: Scope<'_>) -> Result<Value> {
   let value = scope.param::<public::Value>(0)?.unbind();
+  this.reserve(List::ELEMENT_SIZE)?;
   this.push(value);
   Ok(Value::none())
 }
 
 fn list_pop(this: Ptr<List>, _: Scope<'_>) -> Result<Value> {
-  Ok(this.pop().unwrap_or_else(Value::none))
+  match this.pop() {
+    Some(value) => {
+      this.release(List::ELEMENT_SIZE);
+      Ok(value)
+    }
+    None => Ok(Value::none()),
+  }
 }
 
 fn list_extend(this: Ptr<List>, scope: Scope<'_>) -> Result<Value> {
@@ -167,15 +178,16 @@ fn list_extend(this: Ptr<List>, scope: Scope<'_>) -> Result<Value> {
   if n < 0 {
     fail!("count must be positive (was {n})");
   }
+  this.reserve((n as usize).saturating_mul(List::ELEMENT_SIZE))?;
   this.extend(n as usize, value.unbind());
   Ok(Value::none())
 }
 
 fn list_join(this: Ptr<List>, scope: Scope<'_>) -> Result<Value> {
   let sep = scope.param::<public::Str>(0)?;
-  Ok(Value::object(
-    scope.alloc(Str::owned(this.iter().join(sep.as_str()))),
-  ))
+  let str = this.iter().join(sep.as_str()).to_string();
+  let extra = str.len();
+  Ok(Value::object(scope.try_alloc(Str::owned(str), extra)?))
 }
 
 #[derive(Debug)]
@@ -208,6 +220,12 @@ fn list_iter_done(this: Ptr<ListIter>, _: Scope<'_>) -> Result<Value> {
 }
 
 impl Object for ListIter {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.list);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "ListIter"
   }
@@ -235,7 +253,7 @@ impl Object for ListIter {
     };
 
     Ok(Some(Value::object(unsafe {
-      scope.alloc(BuiltinMethod::new(Value::object(this), method))
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
     })))
   }
 }
@@ -243,15 +261,31 @@ impl Object for ListIter {
 declare_object_type!(ListIter);
 
 fn list_iter(this: Ptr<List>, scope: Scope<'_>) -> Result<Value> {
-  Ok(Value::object(scope.alloc(ListIter {
-    list: this,
-    index: Cell::new(0),
-  })))
+  Ok(Value::object(scope.try_alloc(
+    ListIter {
+      list: this,
+      index: Cell::new(0),
+    },
+    0,
+  )?))
 }
 
 // TODO: list iter
 
 impl Object for List {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    for value in self.data.borrow().iter() {
+      tracer.value(value);
+    }
+  }
+
+  fn clear(&self) {
+    let data = std::mem::take(&mut *self.data.borrow_mut());
+    drop(data);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "List"
   }
@@ -285,7 +319,7 @@ impl Object for List {
     };
 
     Ok(Some(Value::object(unsafe {
-      scope.alloc(BuiltinMethod::new(Value::object(this), method))
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
     })))
   }
 
@@ -318,11 +352,10 @@ impl Object for List {
       return Ok(false);
     }
 
-    let this_data = this.data.borrow();
-    let other_data = other.data.borrow();
-    for i in 0..this_data.len() {
-      let lhs = this_data[i].clone();
-      let rhs = other_data[i].clone();
+    // `__eq__` may mutate either list, so they can't stay borrowed
+    let this_data = this.data.borrow().clone();
+    let other_data = other.data.borrow().clone();
+    for (lhs, rhs) in this_data.into_iter().zip(other_data) {
       let are_equal = scope.are_equal(lhs, rhs)?;
       if !are_equal {
         return Ok(false);
@@ -350,23 +383,29 @@ pub fn register_builtin_functions(global: &Global) {
   );
 }
 
-fn to_index(index: Value, len: usize) -> Result<usize> {
-  if index.is_int() {
-    let index = unsafe { index.to_int().unwrap_unchecked() };
-    let index = if index.is_negative() {
-      len - ((-index) as usize)
-    } else {
-      index as usize
-    };
-    return Ok(index);
+/// Converts `index` to an offset into a sequence of length `len`, where
+/// negative indices count from the end.
+///
+/// The result is not bounds checked. A negative index which reaches past the
+/// start becomes `usize::MAX`, which is out of bounds for every sequence.
+pub(super) fn to_index(index: Value, len: usize) -> Result<usize> {
+  let value = if index.is_int() {
+    unsafe { index.clone().to_int().unwrap_unchecked() as i64 }
   } else if index.is_float() {
-    let index = unsafe { index.clone().to_float().unwrap_unchecked() };
-    if index.is_finite() && index.fract() == 0.0 && (MIN_SAFE_INT..=MAX_SAFE_INT).contains(&index) {
-      return Ok(index as usize);
+    let v = unsafe { index.clone().to_float().unwrap_unchecked() };
+    if !(v.is_finite() && v.fract() == 0.0 && (MIN_SAFE_INT..=MAX_SAFE_INT).contains(&v)) {
+      fail!("`{index}` is not a valid index");
     }
+    v as i64
+  } else {
+    fail!("`{index}` is not a valid index");
   };
 
-  fail!("`{index}` is not a valid index")
+  if value.is_negative() {
+    Ok(len.checked_sub(value.unsigned_abs() as usize).unwrap_or(usize::MAX))
+  } else {
+    Ok(value as usize)
+  }
 }
 
 declare_object_type!(List);
@@ -3,6 +3,8 @@ use std::num::NonZeroU64;
 
 use indexmap::{IndexMap, IndexSet};
 
+use super::enum_::{EnumDescriptor, EnumType};
+use super::gc::Tracer;
 use super::native::{NativeAsyncFunction, NativeClass, NativeFunction};
 use super::ptr::Ptr;
 use super::{Function, FunctionDescriptor, Object, Str, Table};
@@ -17,6 +19,9 @@ pub trait ModuleLoader: Send {
   fn load(&self, path: &str) -> Result<Cow<'static, str>>;
 }
 
+/// Name of the global module.
+pub const MAIN_MODULE: &str = "__main__";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ModuleId(Option<NonZeroU64>);
 
@@ -162,6 +167,28 @@ impl Module {
       module_vars.insert(name, Value::object(class));
     }
 
+    for (name, desc) in module.data.enums.iter() {
+      let name = global.alloc(Str::owned(name.clone()));
+      let variants = desc
+        .variants
+        .iter()
+        .map(|(variant, fields)| {
+          let fields = fields
+            .iter()
+            .map(|field| global.intern(field.clone()))
+            .collect();
+          (global.intern(variant.clone()), fields)
+        })
+        .collect();
+      let descriptor = global.alloc(EnumDescriptor {
+        name: name.clone(),
+        variants,
+      });
+      let ty = global.alloc(EnumType::new(descriptor));
+      global.register_enum_raw(desc.type_id, ty.clone());
+      module_vars.insert(name, Value::object(ty));
+    }
+
     Self {
       module_id,
       name,
@@ -172,6 +199,12 @@ impl Module {
 }
 
 impl Object for Module {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.module_vars);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Module"
   }
@@ -1,4 +1,5 @@
 use std::any::{Any as StdAny, TypeId};
+use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 use std::pin::Pin;
 use std::string::String as StdString;
@@ -6,10 +7,12 @@ use std::sync::Arc;
 
 use indexmap::IndexMap;
 
+use super::gc::Tracer;
 use super::{Any, Object, Ptr, ReturnAddr, Str};
 use crate::internal::error::Result;
 use crate::internal::value::Value;
 use crate::internal::vm::global::Global;
+use crate::internal::vm::thread::util::is_truthy;
 use crate::internal::vm::thread::{AsyncFrame, CallResult, Slot0};
 use crate::public::Scope;
 
@@ -49,9 +52,7 @@ impl Object for NativeFunction {
     "NativeFunction"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
     NativeFunction::call(this.as_ref(), scope).map(CallResult::Return)
@@ -90,9 +91,7 @@ impl Object for NativeAsyncFunction {
     "NativeAsyncFunction"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
     Ok(CallResult::Poll(AsyncFrame {
@@ -110,6 +109,62 @@ pub struct NativeClassInstance {
   pub class: Ptr<NativeClass>,
 }
 
+impl NativeClassInstance {
+  /// Call the operator hook `name` registered for the class of `this`, or
+  /// return `None` if there is no such hook.
+  pub fn call_hook(
+    mut scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: &str,
+    args: &[Value],
+  ) -> Result<Option<Value>> {
+    let Some(hook) = this.class.operators.get(name).cloned() else {
+      return Ok(None);
+    };
+    let args = scope.thread.push_args(args);
+    let scope = scope.enter_nested(Slot0::Receiver(Value::object(this)), args, None);
+    let result = NativeFunction::call(hook.as_ref(), scope.clone());
+    scope.leave();
+    result.map(Some)
+  }
+
+  /// Call the operator hook `name` of `this`, which implements `op`.
+  ///
+  /// Comparison operators are all implemented by the `__cmp__` hook.
+  pub fn call_operator(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: &str,
+    op: &str,
+    args: &[Value],
+  ) -> Result<Value> {
+    let matches: fn(Ordering) -> bool = match name {
+      "__lt__" => Ordering::is_lt,
+      "__le__" => Ordering::is_le,
+      "__gt__" => Ordering::is_gt,
+      "__ge__" => Ordering::is_ge,
+      _ => {
+        return match Self::call_hook(scope, this.clone(), name, args)? {
+          Some(value) => Ok(value),
+          None => fail!("`{}` does not support {op}", this.class.name),
+        }
+      }
+    };
+    let ordering = Self::compare(scope, this, args)?;
+    Ok(Value::bool(matches(ordering)))
+  }
+
+  fn compare(scope: Scope<'_>, this: Ptr<Self>, args: &[Value]) -> Result<Ordering> {
+    let Some(ordering) = Self::call_hook(scope, this.clone(), "__cmp__", args)? else {
+      fail!("`{}` does not support comparison", this.class.name);
+    };
+    Ok(match ordering.clone().to_int() {
+      Some(ordering) => ordering.cmp(&0),
+      None => fail!("`__cmp__` must return an ordering, got `{ordering}`"),
+    })
+  }
+}
+
 impl Display for NativeClassInstance {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(f, "<native class `{}` instance>", self.class.name)
@@ -117,13 +172,19 @@ impl Display for NativeClassInstance {
 }
 
 impl Object for NativeClassInstance {
+  const TRACEABLE: bool = true;
+
+  /// The Rust value is `Send`, so it cannot hold references to objects, which
+  /// leaves the class as the only reference to trace.
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.class);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "NativeClassInstance"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn named_field(mut scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
     if let Some(getter) = this.class.fields.get(name.as_str()).map(|field| &field.get) {
@@ -136,10 +197,10 @@ impl Object for NativeClassInstance {
       scope.leave();
       result
     } else if let Some(method) = this.class.methods.get(name.as_str()) {
-      Ok(Value::object(scope.alloc(NativeBoundFunction::new(
-        this.clone(),
-        method.clone(),
-      ))))
+      Ok(Value::object(scope.try_alloc(
+        NativeBoundFunction::new(this.clone(), method.clone()),
+        0,
+      )?))
     } else {
       fail!("`{this}` has no field `{name}`")
     }
@@ -160,10 +221,10 @@ impl Object for NativeClassInstance {
       scope.leave();
       result
     } else if let Some(method) = this.class.methods.get(name.as_str()) {
-      Ok(Some(Value::object(scope.alloc(NativeBoundFunction::new(
-        this.clone(),
-        method.clone(),
-      )))))
+      Ok(Some(Value::object(scope.try_alloc(
+        NativeBoundFunction::new(this.clone(), method.clone()),
+        0,
+      )?)))
     } else {
       Ok(None)
     }
@@ -190,6 +251,96 @@ impl Object for NativeClassInstance {
       fail!("`{this}` has no field `{name}`")
     }
   }
+
+  fn keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
+    Self::call_operator(scope, this, "__index__", "`[]`", &[key])
+  }
+
+  fn keyed_field_opt(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
+    Self::call_operator(scope, this, "__index__", "`[]`", &[key]).map(Some)
+  }
+
+  fn set_keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value, value: Value) -> Result<()> {
+    Self::call_operator(scope, this, "__setindex__", "`[]=`", &[key, value])?;
+    Ok(())
+  }
+
+  fn contains(scope: Scope<'_>, this: Ptr<Self>, item: Value) -> Result<bool> {
+    let result = Self::call_operator(scope, this, "__contains__", "`in`", &[item])?;
+    Ok(is_truthy(result))
+  }
+
+  fn add(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__add__", "`+`", &[Value::object(other)])
+  }
+
+  fn subtract(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__sub__", "`-`", &[Value::object(other)])
+  }
+
+  fn multiply(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__mul__", "`*`", &[Value::object(other)])
+  }
+
+  fn divide(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__div__", "`/`", &[Value::object(other)])
+  }
+
+  fn remainder(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__rem__", "`%`", &[Value::object(other)])
+  }
+
+  fn pow(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__pow__", "`**`", &[Value::object(other)])
+  }
+
+  fn floor_divide(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__floordiv__", "`//`", &[Value::object(other)])
+  }
+
+  fn bit_and(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__and__", "`&`", &[Value::object(other)])
+  }
+
+  fn bit_or(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__or__", "`|`", &[Value::object(other)])
+  }
+
+  fn bit_xor(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__xor__", "`^`", &[Value::object(other)])
+  }
+
+  fn shift_left(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__lshift__", "`<<`", &[Value::object(other)])
+  }
+
+  fn shift_right(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__rshift__", "`>>`", &[Value::object(other)])
+  }
+
+  fn invert(scope: Scope<'_>, this: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__neg__", "unary `-`", &[])
+  }
+
+  fn bit_not(scope: Scope<'_>, this: Ptr<Self>) -> Result<Value> {
+    Self::call_operator(scope, this, "__invert__", "`~`", &[])
+  }
+
+  fn cmp(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Ordering> {
+    Self::compare(scope, this, &[Value::object(other)])
+  }
+
+  fn eq(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    match Self::call_hook(
+      scope,
+      this.clone(),
+      "__eq__",
+      &[Value::object(other.clone())],
+    )? {
+      Some(result) => Ok(is_truthy(result)),
+      None => Ok(this.ptr_eq(&other)),
+    }
+  }
 }
 
 declare_object_type!(NativeClassInstance);
@@ -202,6 +353,8 @@ pub struct NativeClass {
   pub fields: IndexMap<Ptr<Str>, NativeField>,
   pub methods: IndexMap<Ptr<Str>, Ptr<Any>>,
   pub static_methods: IndexMap<Ptr<Str>, Ptr<Any>>,
+  /// Operator hooks, keyed by the name of the equivalent magic method.
+  pub operators: IndexMap<&'static str, Ptr<NativeFunction>>,
 }
 
 impl NativeClass {
@@ -249,6 +402,15 @@ impl NativeClass {
       static_methods.insert(name, method);
     }
 
+    let mut operators = IndexMap::with_capacity(desc.operators.len());
+    for (name, cb) in desc.operators.iter() {
+      let hook = global.alloc(NativeFunction {
+        name: global.intern(*name),
+        cb: cb.clone(),
+      });
+      operators.insert(*name, hook);
+    }
+
     Self {
       name,
       type_id,
@@ -256,6 +418,7 @@ impl NativeClass {
       fields,
       methods,
       static_methods,
+      operators,
     }
   }
 }
@@ -271,9 +434,7 @@ impl Object for NativeClass {
     "NativeClass"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn named_field(_: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
     if let Some(method) = this.static_methods.get(name.as_str()) {
@@ -331,9 +492,7 @@ impl Object for NativeBoundFunction {
     "NativeBoundFunction"
   }
 
-  fn instance_of(_: Ptr<Self>, _: Value) -> Result<bool> {
-    todo!()
-  }
+  default_instance_of!();
 
   fn call(mut scope: Scope<'_>, this: Ptr<Self>, _: ReturnAddr) -> Result<CallResult> {
     let scope = scope.enter_nested(
@@ -378,6 +537,13 @@ pub struct NativeClassDescriptor {
   pub(crate) fields: IndexMap<StdString, NativeFieldDescriptor>,
   pub(crate) methods: IndexMap<StdString, NativeMethodDescriptor>,
   pub(crate) static_methods: IndexMap<StdString, NativeMethodDescriptor>,
+  pub(crate) operators: IndexMap<&'static str, SyncCallback>,
+}
+
+pub struct NativeEnumDescriptor {
+  pub(crate) name: StdString,
+  pub(crate) type_id: TypeId,
+  pub(crate) variants: IndexMap<StdString, Vec<StdString>>,
 }
 
 #[derive(Clone)]
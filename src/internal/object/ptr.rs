@@ -1,12 +1,14 @@
 use std::alloc::Layout;
 use std::any::TypeId;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::ptr::{self, NonNull};
+use std::rc::Rc;
 use std::{alloc, mem};
 
+use super::gc::{self, GcHeader, GcStats};
 use super::{Type, VTable};
 use crate::internal::error::Result;
 use crate::internal::vm::global::Global;
@@ -14,12 +16,168 @@ use crate::internal::vm::global::Global;
 // TODO: identity eq specialization similar to `std::rc::Rc`
 
 #[repr(C)]
-struct Repr<T: Sized + 'static> {
-  layout: Layout,
+pub(super) struct Repr<T: Sized + 'static> {
+  pub(super) layout: Layout,
   type_id: TypeId,
-  refs: Cell<u64>,
-  vtable: &'static super::VTable<T>,
-  data: T,
+  pub(super) refs: Cell<u64>,
+  heap: Rc<Heap>,
+  /// Bytes of out-of-line storage owned by `data`, as reported through
+  /// [`Ptr::reserve`].
+  extra: Cell<usize>,
+  pub(super) gc: GcHeader,
+  pub(super) vtable: &'static super::VTable<T>,
+  pub(super) data: T,
+}
+
+/// Pointer to an object of any type, which does not own a reference to it.
+pub(super) type RawPtr = NonNull<Repr<()>>;
+
+/// Tracks the number of bytes used by live objects, along with the objects
+/// which may be part of a garbage cycle.
+///
+/// Every object holds a reference to the heap it was allocated in, so that
+/// it can give its memory back when it is dropped.
+#[derive(Debug, Default)]
+pub struct Heap {
+  used: Cell<usize>,
+  limit: Cell<Option<usize>>,
+  /// Objects which had a reference to them dropped without being freed,
+  /// which makes them possible roots of a garbage cycle.
+  roots: RefCell<Vec<RawPtr>>,
+  /// Bytes allocated since the last collection.
+  allocated: Cell<usize>,
+  /// How many bytes may be allocated before the next collection.
+  threshold: Cell<usize>,
+  stats: Cell<GcStats>,
+}
+
+impl Heap {
+  /// The minimum number of bytes which must be allocated between automatic
+  /// collections.
+  const MIN_THRESHOLD: usize = 1024 * 1024;
+
+  pub fn new(limit: Option<usize>) -> Self {
+    Self {
+      used: Cell::new(0),
+      limit: Cell::new(limit),
+      roots: RefCell::new(Vec::new()),
+      allocated: Cell::new(0),
+      threshold: Cell::new(Self::MIN_THRESHOLD),
+      stats: Cell::new(GcStats::default()),
+    }
+  }
+
+  pub fn used(&self) -> usize {
+    self.used.get()
+  }
+
+  pub fn limit(&self) -> Option<usize> {
+    self.limit.get()
+  }
+
+  /// Replace the memory limit. Memory which is already in use is kept, even if
+  /// it exceeds the new limit.
+  pub(crate) fn set_limit(&self, limit: Option<usize>) {
+    self.limit.set(limit);
+  }
+
+  /// Account for `bytes` more memory, failing if that would exceed the limit.
+  pub(crate) fn reserve(&self, bytes: usize) -> Result<()> {
+    let used = self.used.get().saturating_add(bytes);
+    if let Some(limit) = self.limit.get() {
+      if used > limit {
+        fail!("out of memory: allocating {bytes} bytes would exceed the limit of {limit} bytes");
+      }
+    }
+    self.used.set(used);
+    self
+      .allocated
+      .set(self.allocated.get().saturating_add(bytes));
+    Ok(())
+  }
+
+  /// Account for `bytes` more memory, even if that exceeds the limit.
+  pub(crate) fn charge(&self, bytes: usize) {
+    self.used.set(self.used.get().saturating_add(bytes));
+    self
+      .allocated
+      .set(self.allocated.get().saturating_add(bytes));
+  }
+
+  fn release(&self, bytes: usize) {
+    self.used.set(self.used.get().saturating_sub(bytes));
+  }
+
+  fn possible_root(&self, ptr: RawPtr) {
+    let repr = unsafe { ptr.as_ref() };
+    if !repr.gc.buffered.get() {
+      repr.gc.buffered.set(true);
+      self.roots.borrow_mut().push(ptr);
+    }
+  }
+
+  pub(super) fn take_roots(&self) -> Vec<RawPtr> {
+    std::mem::take(&mut *self.roots.borrow_mut())
+  }
+
+  /// Whether enough memory was allocated since the last collection to make
+  /// another one worthwhile.
+  pub(crate) fn should_collect(&self) -> bool {
+    self.allocated.get() >= self.threshold.get()
+  }
+
+  /// Free every object which is only kept alive by reference cycles.
+  pub(crate) fn collect(&self) -> GcStats {
+    let used = self.used();
+    let objects = gc::collect(self);
+    self.free_zombies();
+
+    let freed = GcStats {
+      collections: 1,
+      objects,
+      bytes: used.saturating_sub(self.used()),
+    };
+    self.stats.set(self.stats.get() + freed);
+    self.allocated.set(0);
+    self.threshold.set(self.used().max(Self::MIN_THRESHOLD));
+    freed
+  }
+
+  /// Totals over every collection performed so far.
+  pub(crate) fn stats(&self) -> GcStats {
+    self.stats.get()
+  }
+
+  /// Drop objects which were freed while they were possible roots.
+  ///
+  /// Their memory is kept around until they are removed from `roots`,
+  /// because the collector may still hold a pointer to them.
+  fn free_zombies(&self) {
+    self.roots.borrow_mut().retain(|&ptr| {
+      if unsafe { ptr.as_ref() }.refs.get() > 0 {
+        return true;
+      }
+      unsafe { dealloc_zombie(ptr) };
+      false
+    });
+  }
+}
+
+impl Drop for Heap {
+  fn drop(&mut self) {
+    // every object holds a reference to the heap,
+    // so anything left in `roots` must be a zombie
+    for ptr in self.roots.get_mut().drain(..) {
+      unsafe { dealloc_zombie(ptr) };
+    }
+  }
+}
+
+/// # Safety
+/// - `ptr` must point to a zombie, which was dropped while it was buffered.
+pub(super) unsafe fn dealloc_zombie(ptr: RawPtr) {
+  let layout = ptr.as_ref().layout;
+  alloc::dealloc(ptr.as_ptr() as *mut u8, layout)
 }
 
 pub struct Ptr<T: Sized + 'static> {
@@ -35,6 +193,24 @@ impl<T: Sized + 'static> Ptr<T> {
     self.repr().refs.get()
   }
 
+  pub(super) fn raw(&self) -> RawPtr {
+    self.repr.cast()
+  }
+
+  /// Create an owned reference to the object at `ptr`.
+  ///
+  /// # Safety
+  /// - `ptr` must point to a live object of type `T`
+  pub(super) unsafe fn from_raw(ptr: RawPtr) -> Self {
+    let ptr = ptr.cast::<Repr<T>>();
+    Self::incref(ptr);
+    Self { repr: ptr }
+  }
+
+  pub(crate) fn addr(&self) -> usize {
+    self.repr.as_ptr() as usize
+  }
+
   pub(crate) fn into_addr(self) -> usize {
     let ptr = self.repr.as_ptr();
     mem::forget(self);
@@ -77,6 +253,37 @@ impl<T: Sized + 'static> Ptr<T> {
   pub fn ty(&self) -> TypeId {
     self.repr().type_id
   }
+
+  /// Account for `bytes` of out-of-line storage owned by this object, such
+  /// as the buffer of a list which is about to grow.
+  ///
+  /// Fails if that would exceed the memory limit. The memory is given back
+  /// when the object is dropped, or earlier through [`Ptr::release`].
+  pub fn reserve(&self, bytes: usize) -> Result<()> {
+    let repr = self.repr();
+    repr.heap.reserve(bytes)?;
+    repr.extra.set(repr.extra.get() + bytes);
+    Ok(())
+  }
+
+  /// Account for `bytes` of out-of-line storage owned by this object, even if
+  /// that exceeds the memory limit.
+  pub fn charge(&self, bytes: usize) {
+    let repr = self.repr();
+    repr.heap.charge(bytes);
+    repr.extra.set(repr.extra.get() + bytes);
+  }
+
+  /// Give back `bytes` of out-of-line storage, such as the slot of an element
+  /// which was removed from a list.
+  ///
+  /// Never releases more than was previously accounted for.
+  pub fn release(&self, bytes: usize) {
+    let repr = self.repr();
+    let bytes = bytes.min(repr.extra.get());
+    repr.heap.release(bytes);
+    repr.extra.set(repr.extra.get() - bytes);
+  }
 }
 
 impl<T: Sized + 'static> Deref for Ptr<T> {
@@ -91,13 +298,30 @@ impl<T: Sized + 'static> Drop for Ptr<T> {
   fn drop(&mut self) {
     if self.refs() > 1 {
       unsafe { Self::decref(self.repr) };
+      let repr = self.repr();
+      if repr.vtable.traceable {
+        repr.heap.possible_root(self.raw());
+      }
     } else {
       unsafe { ptr::drop_in_place((&mut self.repr.as_mut().data) as *mut _) };
 
-      let ptr = self.repr.as_ptr() as *mut u8;
-      let layout = self.repr().layout;
-      // TODO: replace with `alloc::Global.deallocate` when `alloc::Global` is stable
-      unsafe { alloc::dealloc(ptr, layout) }
+      let repr = self.repr();
+      let layout = repr.layout;
+      repr.heap.release(layout.size() + repr.extra.get());
+      let heap = unsafe { ptr::read(ptr::addr_of!(repr.heap)) };
+
+      if repr.gc.buffered.get() {
+        // the heap still holds a pointer to this object, so its memory
+        // is freed once it is removed from the heap's `roots`
+        repr.refs.set(0);
+      } else {
+        let ptr = self.repr.as_ptr() as *mut u8;
+        // TODO: replace with `alloc::Global.deallocate` when `alloc::Global` is stable
+        unsafe { alloc::dealloc(ptr, layout) }
+      }
+
+      // this may drop the heap, which frees any zombies, so it must happen last
+      drop(heap);
     }
   }
 }
@@ -160,11 +384,22 @@ impl<T> AsRef<T> for Ptr<T> {
 }
 
 impl<T: Type + Sized + 'static> Ptr<T> {
-  pub(crate) unsafe fn alloc_raw(v: T) -> Self {
+  pub(crate) fn layout() -> Layout {
+    Layout::new::<Repr<T>>()
+  }
+
+  /// Allocate `v` in `heap`.
+  ///
+  /// The caller is responsible for accounting for the size of the allocation,
+  /// which is `Ptr::<T>::layout().size() + extra`.
+  pub(crate) unsafe fn alloc_raw(heap: Rc<Heap>, v: T, extra: usize) -> Self {
     let object = Box::new(Repr {
-      layout: Layout::new::<Repr<T>>(),
+      layout: Self::layout(),
       type_id: TypeId::of::<T>(),
       refs: Cell::new(1),
+      heap,
+      extra: Cell::new(extra),
+      gc: GcHeader::default(),
       vtable: <T as Type>::vtable(),
       data: v,
     });
@@ -175,8 +410,22 @@ impl<T: Type + Sized + 'static> Ptr<T> {
 }
 
 impl Global {
+  /// Allocate `v`, regardless of the memory limit.
+  ///
+  /// Allocations which are driven by a script should use
+  /// [`Global::try_alloc`] instead.
   pub fn alloc<T: Type + 'static>(&self, v: T) -> Ptr<T> {
-    unsafe { Ptr::alloc_raw(v) }
+    self.heap.charge(Ptr::<T>::layout().size());
+    unsafe { Ptr::alloc_raw(self.heap.clone(), v, 0) }
+  }
+
+  /// Allocate `v` along with `extra` bytes of out-of-line storage owned by
+  /// it, failing if that would exceed the memory limit.
+  pub fn try_alloc<T: Type + 'static>(&self, v: T, extra: usize) -> Result<Ptr<T>> {
+    self
+      .heap
+      .reserve(Ptr::<T>::layout().size().saturating_add(extra))?;
+    Ok(unsafe { Ptr::alloc_raw(self.heap.clone(), v, extra) })
   }
 }
 
@@ -415,6 +664,21 @@ mod tests {
     }
   }
 
+  #[test]
+  fn heap_accounting() {
+    let global = Global::default();
+    let before = global.memory_usage();
+
+    let v = global.alloc(Bar { value: 100 });
+    let size = Ptr::<Bar>::layout().size();
+    assert_eq!(global.memory_usage(), before + size);
+    v.reserve(64).unwrap();
+    assert_eq!(global.memory_usage(), before + size + 64);
+    let v = v.into_any();
+    drop(v);
+    assert_eq!(global.memory_usage(), before);
+  }
+
   #[test]
   fn any_casting() {
     let cx = Global::default();
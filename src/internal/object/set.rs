@@ -0,0 +1,357 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+use indexmap::{Equivalent, IndexSet};
+
+use super::builtin::BuiltinMethod;
+use super::gc::Tracer;
+use super::table::Key;
+use super::{Object, Ptr, Str};
+use crate::internal::error::Result;
+use crate::internal::value::Value;
+use crate::internal::vm::global::Global;
+use crate::public;
+use crate::public::{Scope, Unbind};
+
+/// An unordered collection of unique values.
+///
+/// Values are hashed the same way as table keys. Iteration follows insertion
+/// order.
+#[derive(Default)]
+pub struct Set {
+  data: RefCell<IndexSet<Key>>,
+}
+
+impl Set {
+  /// Approximate number of bytes used by each element, including its hash.
+  pub const ELEMENT_SIZE: usize = std::mem::size_of::<(Key, u64)>();
+
+  pub fn new() -> Self {
+    Self::with_capacity(0)
+  }
+
+  pub fn with_capacity(n: usize) -> Self {
+    Self {
+      data: RefCell::new(IndexSet::with_capacity(n)),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.borrow().len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.borrow().is_empty()
+  }
+
+  /// Returns `false` if the set already contained `key`.
+  pub fn insert(&self, key: Key) -> bool {
+    self.data.borrow_mut().insert(key)
+  }
+
+  /// Returns `false` if the set did not contain `key`.
+  pub fn remove<K: Equivalent<Key> + ?Sized + Hash>(&self, key: &K) -> bool {
+    self.data.borrow_mut().shift_remove(key)
+  }
+
+  pub fn contains_key<K: Equivalent<Key> + ?Sized + Hash>(&self, key: &K) -> bool {
+    self.data.borrow().contains(key)
+  }
+
+  pub fn get_index(&self, index: usize) -> Option<Value> {
+    self
+      .data
+      .borrow()
+      .get_index(index)
+      .map(|key| key.value().clone())
+  }
+
+  pub fn iter(&self) -> Iter<'_> {
+    Iter {
+      set: self,
+      index: 0,
+    }
+  }
+
+  fn keys(&self) -> Vec<Key> {
+    self.data.borrow().iter().cloned().collect()
+  }
+}
+
+#[derive(Clone)]
+pub struct Iter<'a> {
+  set: &'a Set,
+  index: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+  type Item = Value;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let value = self.set.get_index(self.index)?;
+    self.index += 1;
+    Some(value)
+  }
+}
+
+impl FromIterator<Key> for Set {
+  fn from_iter<I: IntoIterator<Item = Key>>(iter: I) -> Self {
+    Self {
+      data: RefCell::new(iter.into_iter().collect()),
+    }
+  }
+}
+
+impl Display for Set {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<set>")
+  }
+}
+
+impl Debug for Set {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_set().entries(self.data.borrow().iter()).finish()
+  }
+}
+
+fn set_len(this: Ptr<Set>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::int(this.len() as i32))
+}
+
+fn set_is_empty(this: Ptr<Set>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.is_empty()))
+}
+
+fn set_add(this: Ptr<Set>, scope: Scope<'_>) -> Result<Value> {
+  let value = scope.param::<public::Value>(0)?.unbind();
+  let key = Key::new(scope, value)?;
+  if !this.contains_key(&key) {
+    this.reserve(Set::ELEMENT_SIZE)?;
+  }
+  Ok(Value::bool(this.insert(key)))
+}
+
+fn set_remove(this: Ptr<Set>, scope: Scope<'_>) -> Result<Value> {
+  let value = scope.param::<public::Value>(0)?.unbind();
+  let key = Key::new(scope, value)?;
+  let removed = this.remove(&key);
+  if removed {
+    this.release(Set::ELEMENT_SIZE);
+  }
+  Ok(Value::bool(removed))
+}
+
+fn set_contains(this: Ptr<Set>, scope: Scope<'_>) -> Result<Value> {
+  let value = scope.param::<public::Value>(0)?.unbind();
+  let key = Key::new(scope, value)?;
+  Ok(Value::bool(this.contains_key(&key)))
+}
+
+#[derive(Debug)]
+pub struct SetIter {
+  set: Ptr<Set>,
+  index: Cell<usize>,
+}
+
+impl Display for SetIter {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<set iter>")
+  }
+}
+
+fn set_iter_iter(this: Ptr<SetIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(this))
+}
+
+fn set_iter_next(this: Ptr<SetIter>, _: Scope<'_>) -> Result<Value> {
+  if let Some(item) = this.set.get_index(this.index.get()) {
+    this.index.set(this.index.get() + 1);
+    Ok(item)
+  } else {
+    Ok(Value::none())
+  }
+}
+
+fn set_iter_done(this: Ptr<SetIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.index.get() >= this.set.len()))
+}
+
+impl Object for SetIter {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.set);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "SetIter"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "iter" => builtin_method!(set_iter_iter),
+      "next" => builtin_method!(set_iter_next),
+      "done" => builtin_method!(set_iter_done),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+}
+
+declare_object_type!(SetIter);
+
+fn set_iter(this: Ptr<Set>, scope: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(scope.try_alloc(
+    SetIter {
+      set: this,
+      index: Cell::new(0),
+    },
+    0,
+  )?))
+}
+
+/// Allocate a set holding `keys`.
+fn alloc_set(scope: &Scope<'_>, keys: impl IntoIterator<Item = Key>) -> Result<Value> {
+  let set = Set::from_iter(keys);
+  let extra = set.len() * Set::ELEMENT_SIZE;
+  Ok(Value::object(scope.try_alloc(set, extra)?))
+}
+
+impl Object for Set {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    for key in self.data.borrow().iter() {
+      tracer.value(key.value());
+    }
+  }
+
+  fn clear(&self) {
+    let data = std::mem::take(&mut *self.data.borrow_mut());
+    drop(data);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "Set"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "len" => builtin_method!(set_len),
+      "is_empty" => builtin_method!(set_is_empty),
+      "add" => builtin_method!(set_add),
+      "remove" => builtin_method!(set_remove),
+      "contains" => builtin_method!(set_contains),
+      "iter" => builtin_method!(set_iter),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+
+  fn contains(scope: Scope<'_>, this: Ptr<Self>, item: Value) -> Result<bool> {
+    let key = Key::new(scope, item)?;
+    Ok(this.contains_key(&key))
+  }
+
+  fn bit_or(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    let keys = this.keys().into_iter().chain(other.keys());
+    alloc_set(&scope, keys)
+  }
+
+  fn bit_and(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    let keys = this
+      .keys()
+      .into_iter()
+      .filter(|key| other.contains_key(key));
+    alloc_set(&scope, keys)
+  }
+
+  fn subtract(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    let keys = this
+      .keys()
+      .into_iter()
+      .filter(|key| !other.contains_key(key));
+    alloc_set(&scope, keys)
+  }
+
+  fn bit_xor(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
+    let lhs = this
+      .keys()
+      .into_iter()
+      .filter(|key| !other.contains_key(key));
+    let rhs = other
+      .keys()
+      .into_iter()
+      .filter(|key| !this.contains_key(key));
+    alloc_set(&scope, lhs.chain(rhs))
+  }
+
+  fn eq(_: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    if this.len() != other.len() {
+      return Ok(false);
+    }
+
+    Ok(this.keys().iter().all(|key| other.contains_key(key)))
+  }
+}
+
+/// `Set(...values)`
+fn set_new(scope: Scope<'_>) -> Result<Value> {
+  let values = scope.rest_params::<public::Value>(0)?;
+  let keys = values
+    .into_iter()
+    .map(|value| Key::new(scope.clone(), value.unbind()))
+    .collect::<Result<Vec<_>>>()?;
+  alloc_set(&scope, keys)
+}
+
+pub fn register_builtin_functions(global: &Global) {
+  bind_builtin_type!(
+    global,
+    builtin_type!(Set(set_new) {
+      len: builtin_method_static!(Set, set_len),
+      is_empty: builtin_method_static!(Set, set_is_empty),
+      add: builtin_method_static!(Set, set_add),
+      remove: builtin_method_static!(Set, set_remove),
+      contains: builtin_method_static!(Set, set_contains),
+      iter: builtin_method_static!(Set, set_iter)
+    })
+  );
+}
+
+declare_object_type!(Set);
@@ -100,7 +100,7 @@ impl Object for LinesIter {
     };
 
     Ok(Some(Value::object(unsafe {
-      scope.alloc(BuiltinMethod::new(Value::object(this), method))
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
     })))
   }
 }
@@ -118,23 +118,25 @@ fn str_lines_next(this: Ptr<LinesIter>, scope: Scope<'_>) -> Result<Value> {
 
   if let Some(offset) = this.offset.get() {
     let start: usize = offset + 1;
-    let str = match this.str.as_str()[start..].find('\n') {
+    let line = match this.str.as_str()[start..].find('\n') {
       Some(end) => {
         let end = start + end;
         this.offset.set(Some(end));
-        scope.alloc(Str::owned(&this.str.as_str()[start..end]))
+        &this.str.as_str()[start..end]
       }
       None => {
         this.done.set(true);
-        scope.alloc(Str::owned(&this.str.as_str()[start..]))
+        &this.str.as_str()[start..]
       }
     };
+    let str = scope.try_alloc(Str::owned(line), line.len())?;
     Ok(Value::object(str))
   } else {
     let str = match this.str.as_str().find('\n') {
       Some(end) => {
         this.offset.set(Some(end));
-        scope.alloc(Str::owned(&this.str.as_str()[..end]))
+        let line = &this.str.as_str()[..end];
+        scope.try_alloc(Str::owned(line), line.len())?
       }
       None => {
         this.done.set(true);
@@ -150,11 +152,14 @@ fn str_lines_done(this: Ptr<LinesIter>, _: Scope<'_>) -> Result<Value> {
 }
 
 fn str_lines(this: Ptr<Str>, scope: Scope<'_>) -> Result<Value> {
-  Ok(Value::object(scope.alloc(LinesIter {
-    str: this,
-    offset: Cell::new(None),
-    done: Cell::new(false),
-  })))
+  Ok(Value::object(scope.try_alloc(
+    LinesIter {
+      str: this,
+      offset: Cell::new(None),
+      done: Cell::new(false),
+    },
+    0,
+  )?))
 }
 
 impl Object for Str {
@@ -185,12 +190,14 @@ impl Object for Str {
     };
 
     Ok(Some(Value::object(unsafe {
-      scope.alloc(BuiltinMethod::new(Value::object(this), method))
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
     })))
   }
 
   fn add(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Value> {
-    Ok(Value::object(scope.alloc(this.concat(other.as_str()))))
+    let value = this.concat(other.as_str());
+    let extra = value.len();
+    Ok(Value::object(scope.try_alloc(value, extra)?))
   }
 
   fn cmp(_: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<Ordering> {
@@ -1,21 +1,222 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::fmt::{Debug, Display};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 
 use indexmap::{Equivalent, IndexMap};
+use num_bigint::BigInt;
 
+use super::builtin::BuiltinMethod;
+use super::class::ClassInstance;
+use super::enum_::EnumValue;
+use super::gc::Tracer;
+use super::native::NativeClassInstance;
 use super::ptr::Ptr;
-use super::{Object, Str};
+use super::{int, Bytes, List, Object, Str, Tuple};
 use crate::internal::error::Result;
 use crate::internal::value::Value;
+use crate::internal::vm::global::Global;
 use crate::public::Scope;
 
+/// The key of a table entry.
+///
+/// String keys hash the same way as `str`, so that tables can be indexed by
+/// `&str` without allocating a key. Other values are hashed by a structural
+/// identity computed once, when the key is created.
+#[derive(Clone)]
+pub struct Key {
+  value: Value,
+  id: KeyId,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum KeyId {
+  Str(Ptr<Str>),
+  Bytes(Box<[u8]>),
+  Int(i64),
+  BigInt(BigInt),
+  /// Floats are keyed by their bit pattern, so `1.0` and `1` are different
+  /// keys. `0.0` and `-0.0` compare equal, so `-0.0` is stored as `0.0`.
+  Float(u64),
+  Bool(bool),
+  None,
+  Tuple(Vec<KeyId>),
+  /// A value of the enum at the address, keyed by its variant and payload.
+  Enum(usize, usize, Vec<KeyId>),
+  /// Objects are compared by identity.
+  Object(usize),
+  /// An instance of the class at the address, compared by the value returned
+  /// from its `__hash__` hook.
+  Hashed(usize, Box<KeyId>),
+}
+
+impl Key {
+  /// Create a key from `value`.
+  ///
+  /// Fails if `value` is a mutable container, because its identity could
+  /// change while it is used as a key. Class instances are compared by
+  /// identity, unless they implement `__hash__`, in which case instances of
+  /// the same class are equal if their `__hash__` results are equal.
+  pub fn new(scope: Scope<'_>, value: Value) -> Result<Self> {
+    let id = key_id(Some(&scope), &value)?;
+    Ok(Self { value, id })
+  }
+
+  /// Create a key from `value` without calling any `__hash__` hooks, so
+  /// instances are always compared by identity.
+  pub fn plain(value: Value) -> Result<Self> {
+    let id = key_id(None, &value)?;
+    Ok(Self { value, id })
+  }
+
+  pub fn str(key: Ptr<Str>) -> Self {
+    Self {
+      value: Value::object(key.clone()),
+      id: KeyId::Str(key),
+    }
+  }
+
+  pub fn value(&self) -> &Value {
+    &self.value
+  }
+
+  pub fn into_value(self) -> Value {
+    self.value
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match &self.id {
+      KeyId::Str(key) => Some(key.as_str()),
+      _ => None,
+    }
+  }
+
+  pub fn to_str(&self) -> Option<Ptr<Str>> {
+    match &self.id {
+      KeyId::Str(key) => Some(key.clone()),
+      _ => None,
+    }
+  }
+}
+
+fn key_id(scope: Option<&Scope<'_>>, value: &Value) -> Result<KeyId> {
+  if let Some(v) = value.clone().to_float() {
+    // `0.0 == -0.0`, so they must be the same key
+    let v = if v == 0.0 { 0.0 } else { v };
+    return Ok(KeyId::Float(v.to_bits()));
+  }
+  if int::is_int(value) {
+    return Ok(match int::to_i64(value) {
+      Some(v) => KeyId::Int(v),
+      None => KeyId::BigInt(int::to_bigint(value).unwrap_or_default()),
+    });
+  }
+  if let Some(v) = value.clone().to_bool() {
+    return Ok(KeyId::Bool(v));
+  }
+  if value.is_none() {
+    return Ok(KeyId::None);
+  }
+
+  let Some(object) = value.clone().to_any() else {
+    unreachable!("invalid type");
+  };
+  if let Some(str) = object.clone_cast::<Str>() {
+    Ok(KeyId::Str(str))
+  } else if let Some(bytes) = object.clone_cast::<Bytes>() {
+    Ok(KeyId::Bytes(bytes.as_slice().into()))
+  } else if let Some(tuple) = object.clone_cast::<Tuple>() {
+    let ids = tuple
+      .iter()
+      .map(|value| key_id(scope, &value))
+      .collect::<Result<_>>()?;
+    Ok(KeyId::Tuple(ids))
+  } else if let Some(value) = object.clone_cast::<EnumValue>() {
+    let ids = (0..value.len())
+      .map(|i| key_id(scope, &value.get(i).unwrap()))
+      .collect::<Result<_>>()?;
+    Ok(KeyId::Enum(value.ty.addr(), value.index, ids))
+  } else if object.is::<List>() || object.is::<Table>() {
+    fail!("`{}` is not hashable", object.type_name());
+  } else if let Some((class, hash)) = match scope {
+    Some(scope) => hash_hook(scope, value)?,
+    None => None,
+  } {
+    // the result is not hashed by its own hook, which could recurse forever
+    Ok(KeyId::Hashed(class, Box::new(key_id(None, &hash)?)))
+  } else {
+    Ok(KeyId::Object(object.addr()))
+  }
+}
+
+/// Calls the `__hash__` hook of a class instance, if it has one.
+fn hash_hook(scope: &Scope<'_>, value: &Value) -> Result<Option<(usize, Value)>> {
+  if let Some(this) = value.clone().to_object::<ClassInstance>() {
+    let class = this.class.addr();
+    let hash = ClassInstance::call_method(scope.clone(), this, "__hash__", &[])?;
+    Ok(hash.map(|hash| (class, hash)))
+  } else if let Some(this) = value.clone().to_object::<NativeClassInstance>() {
+    let class = this.class.addr();
+    let hash = NativeClassInstance::call_hook(scope.clone(), this, "__hash__", &[])?;
+    Ok(hash.map(|hash| (class, hash)))
+  } else {
+    Ok(None)
+  }
+}
+
+impl Hash for Key {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    match &self.id {
+      // must be the same as for `str`, see `Equivalent<Key> for str`
+      KeyId::Str(key) => key.as_str().hash(state),
+      id => id.hash(state),
+    }
+  }
+}
+
+impl PartialEq for Key {
+  fn eq(&self, other: &Self) -> bool {
+    self.id == other.id
+  }
+}
+
+impl Eq for Key {}
+
+impl Equivalent<Key> for str {
+  fn equivalent(&self, key: &Key) -> bool {
+    key.as_str() == Some(self)
+  }
+}
+
+impl Equivalent<Key> for Ptr<Str> {
+  fn equivalent(&self, key: &Key) -> bool {
+    key.as_str() == Some(self.as_str())
+  }
+}
+
+impl Display for Key {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Display::fmt(&self.value, f)
+  }
+}
+
+impl Debug for Key {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.id {
+      KeyId::Str(key) => Debug::fmt(key, f),
+      _ => Debug::fmt(&self.value, f),
+    }
+  }
+}
+
 #[derive(Default)]
 pub struct Table {
-  data: RefCell<IndexMap<Ptr<Str>, Value>>,
+  data: RefCell<IndexMap<Key, Value>>,
 }
 
 impl Table {
+  /// Approximate number of bytes used by each entry, including its hash.
+  pub const ENTRY_SIZE: usize = std::mem::size_of::<(Key, Value, u64)>();
+
   pub fn new() -> Self {
     Self::with_capacity(0)
   }
@@ -30,20 +231,23 @@ impl Table {
     self.data.borrow().len()
   }
 
-  #[allow(dead_code)] // TODO: expose
   pub fn is_empty(&self) -> bool {
     self.data.borrow().is_empty()
   }
 
   pub fn insert(&self, key: Ptr<Str>, value: Value) -> Option<Value> {
+    self.insert_key(Key::str(key), value)
+  }
+
+  pub fn insert_key(&self, key: Key, value: Value) -> Option<Value> {
     self.data.borrow_mut().insert(key, value)
   }
 
-  pub fn get<K: Equivalent<Ptr<Str>> + ?Sized + Hash>(&self, key: &K) -> Option<Value> {
+  pub fn get<K: Equivalent<Key> + ?Sized + Hash>(&self, key: &K) -> Option<Value> {
     self.data.borrow().get(key).cloned()
   }
 
-  pub fn set<K: Equivalent<Ptr<Str>> + ?Sized + Hash>(&self, key: &K, value: Value) -> bool {
+  pub fn set<K: Equivalent<Key> + ?Sized + Hash>(&self, key: &K, value: Value) -> bool {
     if let Some(slot) = self.data.borrow_mut().get_mut(key) {
       *slot = value;
       true
@@ -52,6 +256,14 @@ impl Table {
     }
   }
 
+  pub fn get_entry(&self, index: usize) -> Option<(Value, Value)> {
+    self
+      .data
+      .borrow()
+      .get_index(index)
+      .map(|(key, value)| (key.value.clone(), value.clone()))
+  }
+
   pub fn get_index(&self, index: usize) -> Option<Value> {
     self
       .data
@@ -105,7 +317,7 @@ pub struct Keys<'a> {
 }
 
 impl<'a> Iterator for Keys<'a> {
-  type Item = Ptr<Str>;
+  type Item = Key;
 
   fn next(&mut self) -> Option<Self::Item> {
     match self.table.data.borrow().get_index(self.index) {
@@ -143,7 +355,7 @@ pub struct Entries<'a> {
 }
 
 impl<'a> Iterator for Entries<'a> {
-  type Item = (Ptr<Str>, Value);
+  type Item = (Key, Value);
 
   fn next(&mut self) -> Option<Self::Item> {
     match self.table.data.borrow().get_index(self.index) {
@@ -172,36 +384,200 @@ impl Debug for Table {
   }
 }
 
+fn table_len(this: Ptr<Table>, scope: Scope<'_>) -> Result<Value> {
+  Ok(int::from_i64(&scope.thread.global, this.len() as i64))
+}
+
+fn table_is_empty(this: Ptr<Table>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.is_empty()))
+}
+
+#[derive(Clone, Copy, Debug)]
+enum IterKind {
+  Keys,
+  Values,
+  Entries,
+}
+
+#[derive(Debug)]
+pub struct TableIter {
+  table: Ptr<Table>,
+  kind: IterKind,
+  index: Cell<usize>,
+}
+
+impl Display for TableIter {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<table iter>")
+  }
+}
+
+fn table_iter_iter(this: Ptr<TableIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(this))
+}
+
+fn table_iter_next(this: Ptr<TableIter>, scope: Scope<'_>) -> Result<Value> {
+  let Some((key, value)) = this.table.get_entry(this.index.get()) else {
+    return Ok(Value::none());
+  };
+  this.index.set(this.index.get() + 1);
+  match this.kind {
+    IterKind::Keys => Ok(key),
+    IterKind::Values => Ok(value),
+    IterKind::Entries => {
+      let entry = Tuple::from(vec![key, value]);
+      let extra = 2 * Tuple::ELEMENT_SIZE;
+      Ok(Value::object(scope.try_alloc(entry, extra)?))
+    }
+  }
+}
+
+fn table_iter_done(this: Ptr<TableIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.index.get() >= this.table.len()))
+}
+
+impl Object for TableIter {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.table);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "TableIter"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "iter" => builtin_method!(table_iter_iter),
+      "next" => builtin_method!(table_iter_next),
+      "done" => builtin_method!(table_iter_done),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+}
+
+declare_object_type!(TableIter);
+
+fn table_iter(this: Ptr<Table>, scope: Scope<'_>, kind: IterKind) -> Result<Value> {
+  Ok(Value::object(scope.try_alloc(
+    TableIter {
+      table: this,
+      kind,
+      index: Cell::new(0),
+    },
+    0,
+  )?))
+}
+
+fn table_keys(this: Ptr<Table>, scope: Scope<'_>) -> Result<Value> {
+  table_iter(this, scope, IterKind::Keys)
+}
+
+fn table_values(this: Ptr<Table>, scope: Scope<'_>) -> Result<Value> {
+  table_iter(this, scope, IterKind::Values)
+}
+
+/// Iterate over `(key, value)` tuples.
+fn table_entries(this: Ptr<Table>, scope: Scope<'_>) -> Result<Value> {
+  table_iter(this, scope, IterKind::Entries)
+}
+
 impl Object for Table {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    for (key, value) in self.data.borrow().iter() {
+      tracer.value(&key.value);
+      tracer.value(value);
+    }
+  }
+
+  fn clear(&self) {
+    let data = std::mem::take(&mut *self.data.borrow_mut());
+    drop(data);
+  }
+
   fn type_name(_: Ptr<Self>) -> &'static str {
     "Table"
   }
 
   default_instance_of!();
 
-  fn keyed_field(_: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
-    let Some(key) = key.clone().to_object::<Str>() else {
-      fail!("`{key}` is not a string");
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "len" => builtin_method!(table_len),
+      "is_empty" => builtin_method!(table_is_empty),
+      "keys" => builtin_method!(table_keys),
+      "values" => builtin_method!(table_values),
+      "entries" => builtin_method!(table_entries),
+      "iter" => builtin_method!(table_keys),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+
+  fn keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
+    let value = match key.clone().to_object::<Str>() {
+      Some(key) => this.get(key.as_str()),
+      None => this.get(&Key::new(scope, key.clone())?),
+    };
+    let Some(value) = value else {
+      fail!("`{this}` has no index `{key}`");
     };
-    let value = this
-      .get(key.as_str())
-      .ok_or_else(|| error!("`{this}` has no index `{key}`"))?;
     Ok(value)
   }
 
-  fn keyed_field_opt(_: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
-    let Some(key) = key.clone().to_object::<Str>() else {
-      fail!("`{key}` is not a string");
+  fn keyed_field_opt(scope: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
+    let value = match key.clone().to_object::<Str>() {
+      Some(key) => this.get(key.as_str()),
+      None => this.get(&Key::new(scope, key)?),
     };
-    let value = this.get(key.as_str());
     Ok(value)
   }
 
-  fn set_keyed_field(_: Scope<'_>, this: Ptr<Self>, key: Value, value: Value) -> Result<()> {
-    let Some(key) = key.clone().to_object::<Str>() else {
-      fail!("`{key}` is not a string");
+  fn set_keyed_field(scope: Scope<'_>, this: Ptr<Self>, key: Value, value: Value) -> Result<()> {
+    let key = match key.clone().to_object::<Str>() {
+      Some(key) => Key::str(key),
+      None => Key::new(scope, key)?,
     };
-    this.insert(key, value);
+    if this.get(&key).is_none() {
+      this.reserve(Table::ENTRY_SIZE)?;
+    }
+    this.insert_key(key, value);
     Ok(())
   }
 
@@ -210,15 +586,15 @@ impl Object for Table {
       return Ok(false);
     }
 
-    let this_data = this.data.borrow();
-    let other_data = other.data.borrow();
-    for (this_key, this_value) in this_data.iter() {
-      let other_value = match other_data.get(this_key) {
+    // `__eq__` may mutate either table, so they can't stay borrowed
+    let this_data = this.data.borrow().clone();
+    for (this_key, this_value) in this_data {
+      let other_value = match other.get(&this_key) {
         Some(value) => value,
         None => return Ok(false),
       };
 
-      let are_equal = scope.are_equal(this_value.clone(), other_value.clone())?;
+      let are_equal = scope.are_equal(this_value, other_value)?;
       if !are_equal {
         return Ok(false);
       }
@@ -228,4 +604,18 @@ impl Object for Table {
   }
 }
 
+pub fn register_builtin_functions(global: &Global) {
+  bind_builtin_type!(
+    global,
+    builtin_type!(Table {
+      len: builtin_method_static!(Table, table_len),
+      is_empty: builtin_method_static!(Table, table_is_empty),
+      keys: builtin_method_static!(Table, table_keys),
+      values: builtin_method_static!(Table, table_values),
+      entries: builtin_method_static!(Table, table_entries),
+      iter: builtin_method_static!(Table, table_keys)
+    })
+  );
+}
+
 declare_object_type!(Table);
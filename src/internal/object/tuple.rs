@@ -0,0 +1,278 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Display};
+
+use super::builtin::BuiltinMethod;
+use super::gc::Tracer;
+use super::list::to_index;
+use super::{Object, Ptr, Str};
+use crate::internal::error::Result;
+use crate::internal::value::Value;
+use crate::internal::vm::global::Global;
+use crate::public;
+use crate::public::{Scope, Unbind};
+
+/// A fixed-size, immutable sequence of values.
+#[derive(Default)]
+pub struct Tuple {
+  // only ever mutated by `clear`
+  data: RefCell<Box<[Value]>>,
+}
+
+impl Tuple {
+  /// Approximate number of bytes used by each element.
+  pub const ELEMENT_SIZE: usize = std::mem::size_of::<Value>();
+
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.borrow().len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.borrow().is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<Value> {
+    self.data.borrow().get(index).cloned()
+  }
+
+  pub fn iter(&self) -> Iter<'_> {
+    Iter {
+      tuple: self,
+      index: 0,
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct Iter<'a> {
+  tuple: &'a Tuple,
+  index: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+  type Item = Value;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let value = self.tuple.get(self.index)?;
+    self.index += 1;
+    Some(value)
+  }
+}
+
+impl From<Vec<Value>> for Tuple {
+  fn from(values: Vec<Value>) -> Self {
+    Self {
+      data: RefCell::new(values.into_boxed_slice()),
+    }
+  }
+}
+
+impl Display for Tuple {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<tuple>")
+  }
+}
+
+impl Debug for Tuple {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.is_empty() {
+      return write!(f, "()");
+    }
+    let mut f = f.debug_tuple("");
+    for value in self.data.borrow().iter() {
+      f.field(value);
+    }
+    f.finish()
+  }
+}
+
+fn tuple_len(this: Ptr<Tuple>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::int(this.len() as i32))
+}
+
+fn tuple_is_empty(this: Ptr<Tuple>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.is_empty()))
+}
+
+fn tuple_get(this: Ptr<Tuple>, scope: Scope<'_>) -> Result<Value> {
+  let index = scope.param::<public::Value>(0)?.unbind();
+  let index = to_index(index, this.len())?;
+  Ok(this.get(index).unwrap_or_else(Value::none))
+}
+
+#[derive(Debug)]
+pub struct TupleIter {
+  tuple: Ptr<Tuple>,
+  index: Cell<usize>,
+}
+
+impl Display for TupleIter {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<tuple iter>")
+  }
+}
+
+fn tuple_iter_iter(this: Ptr<TupleIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(this))
+}
+
+fn tuple_iter_next(this: Ptr<TupleIter>, _: Scope<'_>) -> Result<Value> {
+  if let Some(item) = this.tuple.get(this.index.get()) {
+    this.index.set(this.index.get() + 1);
+    Ok(item)
+  } else {
+    Ok(Value::none())
+  }
+}
+
+fn tuple_iter_done(this: Ptr<TupleIter>, _: Scope<'_>) -> Result<Value> {
+  Ok(Value::bool(this.index.get() >= this.tuple.len()))
+}
+
+impl Object for TupleIter {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    tracer.ptr(&self.tuple);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "TupleIter"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "iter" => builtin_method!(tuple_iter_iter),
+      "next" => builtin_method!(tuple_iter_next),
+      "done" => builtin_method!(tuple_iter_done),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+}
+
+declare_object_type!(TupleIter);
+
+fn tuple_iter(this: Ptr<Tuple>, scope: Scope<'_>) -> Result<Value> {
+  Ok(Value::object(scope.try_alloc(
+    TupleIter {
+      tuple: this,
+      index: Cell::new(0),
+    },
+    0,
+  )?))
+}
+
+impl Object for Tuple {
+  const TRACEABLE: bool = true;
+
+  fn trace(&self, tracer: &mut Tracer<'_>) {
+    for value in self.data.borrow().iter() {
+      tracer.value(value);
+    }
+  }
+
+  fn clear(&self) {
+    let data = std::mem::take(&mut *self.data.borrow_mut());
+    drop(data);
+  }
+
+  fn type_name(_: Ptr<Self>) -> &'static str {
+    "Tuple"
+  }
+
+  default_instance_of!();
+
+  fn named_field(scope: Scope<'_>, this: Ptr<Self>, name: Ptr<Str>) -> Result<Value> {
+    Ok(
+      this
+        .named_field_opt(scope, name.clone())?
+        .ok_or_else(|| error!("`{this}` has no field `{name}`"))?,
+    )
+  }
+
+  fn named_field_opt(
+    scope: Scope<'_>,
+    this: Ptr<Self>,
+    name: Ptr<super::Str>,
+  ) -> Result<Option<Value>> {
+    let method = match name.as_str() {
+      "len" => builtin_method!(tuple_len),
+      "is_empty" => builtin_method!(tuple_is_empty),
+      "get" => builtin_method!(tuple_get),
+      "iter" => builtin_method!(tuple_iter),
+      _ => fail!("`{this}` has no field `{name}`"),
+    };
+
+    Ok(Some(Value::object(unsafe {
+      scope.try_alloc(BuiltinMethod::new(Value::object(this), method), 0)?
+    })))
+  }
+
+  fn keyed_field(_: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Value> {
+    let len = this.len();
+    let index = to_index(key.clone(), len)?;
+    let value = this
+      .get(index)
+      .ok_or_else(|| error!("index `{key}` out of bounds, len was `{len}`"))?;
+    Ok(value)
+  }
+
+  fn keyed_field_opt(_: Scope<'_>, this: Ptr<Self>, key: Value) -> Result<Option<Value>> {
+    let len = this.len();
+    let index = to_index(key, len)?;
+    Ok(this.get(index))
+  }
+
+  fn set_keyed_field(_: Scope<'_>, this: Ptr<Self>, _: Value, _: Value) -> Result<()> {
+    fail!("`{this}` is immutable")
+  }
+
+  fn eq(scope: Scope<'_>, this: Ptr<Self>, other: Ptr<Self>) -> Result<bool> {
+    if this.len() != other.len() {
+      return Ok(false);
+    }
+
+    for (lhs, rhs) in this.iter().zip(other.iter()) {
+      if !scope.are_equal(lhs, rhs)? {
+        return Ok(false);
+      }
+    }
+
+    Ok(true)
+  }
+}
+
+pub fn register_builtin_functions(global: &Global) {
+  bind_builtin_type!(
+    global,
+    builtin_type!(Tuple {
+      len: builtin_method_static!(Tuple, tuple_len),
+      is_empty: builtin_method_static!(Tuple, tuple_is_empty),
+      get: builtin_method_static!(Tuple, tuple_get),
+      iter: builtin_method_static!(Tuple, tuple_iter)
+    })
+  );
+}
+
+declare_object_type!(Tuple);
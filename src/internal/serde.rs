@@ -3,10 +3,10 @@ use std::string::String as StdString;
 use serde::de::{DeserializeSeed, Visitor};
 use serde::ser::{Serialize, SerializeMap, SerializeSeq};
 
-use super::object::{List, Ptr, Str, Table};
+use super::object::table::Key;
+use super::object::{int, Bytes, Int, List, Set, Str, Table, Tuple};
 use super::value::Value;
 use super::vm::global::Global;
-use crate::util::{MAX_SAFE_INT, MIN_SAFE_INT};
 
 impl Serialize for Value {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -36,6 +36,23 @@ impl Serialize for Value {
       } else if value.is::<List>() {
         let value = unsafe { value.cast_unchecked::<List>() };
         value.serialize(serializer)
+      } else if value.is::<Tuple>() {
+        let value = unsafe { value.cast_unchecked::<Tuple>() };
+        value.serialize(serializer)
+      } else if value.is::<Set>() {
+        let value = unsafe { value.cast_unchecked::<Set>() };
+        value.serialize(serializer)
+      } else if value.is::<Bytes>() {
+        let value = unsafe { value.cast_unchecked::<Bytes>() };
+        serializer.serialize_bytes(value.as_slice())
+      } else if value.is::<Int>() {
+        let value = unsafe { value.cast_unchecked::<Int>() };
+        match &*value {
+          Int::I64(v) => serializer.serialize_i64(*v),
+          Int::Big(_) => Err(serde::ser::Error::custom(format!(
+            "cannot serialize `{value}`, because it does not fit into an i64"
+          ))),
+        }
       } else {
         Err(serde::ser::Error::custom(format!(
           "cannot serialize `{value}`"
@@ -56,7 +73,7 @@ impl Serialize for Table {
   {
     let mut map = serializer.serialize_map(Some(self.len()))?;
     for (key, value) in self.entries() {
-      map.serialize_entry(key.as_ref(), &value)?;
+      map.serialize_entry(key.value(), &value)?;
     }
     map.end()
   }
@@ -75,37 +92,39 @@ impl Serialize for List {
   }
 }
 
-impl Serialize for Str {
+impl Serialize for Tuple {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: serde::Serializer,
   {
-    serializer.serialize_str(self.as_str())
+    let mut tuple = serializer.serialize_seq(Some(self.len()))?;
+    for value in self.iter() {
+      tuple.serialize_element(&value)?;
+    }
+    tuple.end()
   }
 }
 
-macro_rules! try_to_f64 {
-  ($ty:ty, $v:expr) => {{
-    let v = $v;
-    let fv = v as f64;
-    if fv < MIN_SAFE_INT {
-      Err(serde::de::Error::custom(format!(
-        "{} is out of bounds ({} < {})",
-        std::any::type_name::<$ty>(),
-        v,
-        MIN_SAFE_INT
-      )))
-    } else if fv > MAX_SAFE_INT {
-      Err(serde::de::Error::custom(format!(
-        "{} is out of bounds ({} < {})",
-        std::any::type_name::<$ty>(),
-        v,
-        MAX_SAFE_INT
-      )))
-    } else {
-      Ok(fv)
+impl Serialize for Set {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut set = serializer.serialize_seq(Some(self.len()))?;
+    for value in self.iter() {
+      set.serialize_element(&value)?;
     }
-  }};
+    set.end()
+  }
+}
+
+impl Serialize for Str {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.serialize_str(self.as_str())
+  }
 }
 
 pub struct ValueDeserializer {
@@ -125,53 +144,23 @@ impl<'de> DeserializeSeed<'de> for ValueDeserializer {
   }
 }
 
-struct StringDeserializer {
+/// Deserializes any value which can be used as a table key.
+struct KeyDeserializer {
   global: Global,
 }
 
-impl<'de> DeserializeSeed<'de> for StringDeserializer {
-  type Value = Ptr<Str>;
+impl<'de> DeserializeSeed<'de> for KeyDeserializer {
+  type Value = Key;
 
   fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
   where
     D: serde::Deserializer<'de>,
   {
-    struct V {
-      global: Global,
-    }
-
-    impl<'de> Visitor<'de> for V {
-      type Value = Ptr<Str>;
-
-      fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a string")
-      }
-
-      fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-      where
-        E: serde::de::Error,
-      {
-        Ok(self.global.alloc(Str::owned(v)))
-      }
-
-      fn visit_string<E>(self, v: StdString) -> Result<Self::Value, E>
-      where
-        E: serde::de::Error,
-      {
-        Ok(self.global.alloc(Str::owned(v)))
-      }
-
-      fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
-      where
-        E: serde::de::Error,
-      {
-        Ok(self.global.alloc(Str::owned(v)))
-      }
-    }
-
-    deserializer.deserialize_str(V {
+    let value = ValueDeserializer {
       global: self.global,
-    })
+    }
+    .deserialize(deserializer)?;
+    Key::plain(value).map_err(serde::de::Error::custom)
   }
 }
 
@@ -218,22 +207,14 @@ impl<'de> Visitor<'de> for ValueVisitor {
   where
     E: serde::de::Error,
   {
-    if v < i32::MIN as i64 || v > i32::MAX as i64 {
-      try_to_f64!(i64, v).map(Value::float)
-    } else {
-      Ok(Value::int(v as i32))
-    }
+    Ok(int::from_i64(&self.global, v))
   }
 
   fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
   where
     E: serde::de::Error,
   {
-    if v < i32::MIN as i128 || v > i32::MAX as i128 {
-      try_to_f64!(i128, v).map(Value::float)
-    } else {
-      Ok(Value::int(v as i32))
-    }
+    Ok(int::from_i128(&self.global, v))
   }
 
   fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
@@ -254,33 +235,21 @@ impl<'de> Visitor<'de> for ValueVisitor {
   where
     E: serde::de::Error,
   {
-    if v > i32::MAX as u32 {
-      try_to_f64!(u32, v).map(Value::float)
-    } else {
-      Ok(Value::int(v as i32))
-    }
+    Ok(int::from_i64(&self.global, v as i64))
   }
 
   fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
   where
     E: serde::de::Error,
   {
-    if v > i32::MAX as u64 {
-      try_to_f64!(u64, v).map(Value::float)
-    } else {
-      Ok(Value::int(v as i32))
-    }
+    Ok(int::from_i128(&self.global, v as i128))
   }
 
   fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
   where
     E: serde::de::Error,
   {
-    if v > i32::MAX as u128 {
-      try_to_f64!(u128, v).map(Value::float)
-    } else {
-      Ok(Value::int(v as i32))
-    }
+    int::from_bigint(&self.global, v.into()).map_err(E::custom)
   }
 
   fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
@@ -325,35 +294,19 @@ impl<'de> Visitor<'de> for ValueVisitor {
     Ok(Value::object(self.global.alloc(Str::owned(v))))
   }
 
-  // TODO: some kind of Bytes object?
-  /*
   fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
   where
     E: serde::de::Error,
   {
-    let _ = v;
-    Err(serde::de::Error::invalid_type(
-      serde::de::Unexpected::Bytes(v),
-      &self,
-    ))
-  }
-  */
-  /*
-  fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
-  where
-    E: serde::de::Error,
-  {
-    self.visit_bytes(v)
+    Ok(Value::object(self.global.alloc(Bytes::from(v))))
   }
-  */
-  /*
+
   fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
   where
     E: serde::de::Error,
   {
-    self.visit_bytes(&v)
+    Ok(Value::object(self.global.alloc(Bytes::from(v))))
   }
-  */
 
   fn visit_none<E>(self) -> Result<Self::Value, E>
   where
@@ -416,14 +369,14 @@ impl<'de> Visitor<'de> for ValueVisitor {
       .global
       .alloc(Table::with_capacity(map.size_hint().unwrap_or(0)));
     while let Some((key, value)) = map.next_entry_seed(
-      StringDeserializer {
+      KeyDeserializer {
         global: self.global.clone(),
       },
       ValueDeserializer {
         global: self.global.clone(),
       },
     )? {
-      table.insert(key, value);
+      table.insert_key(key, value);
     }
     Ok(Value::object(table))
   }
@@ -457,6 +410,74 @@ mod tests {
       .deserialize(&mut serde_json::Deserializer::from_str(json))
       .unwrap();
 
-    assert_eq!(value.to_float(), Some(5360574452_f64));
+    assert_eq!(int::to_i64(&value), Some(5360574452));
+  }
+
+  #[test]
+  fn serialize_tuple() {
+    let global = Global::default();
+
+    let tuple = Tuple::from(vec![
+      Value::int(1),
+      Value::object(global.alloc(Str::owned("a"))),
+      Value::object(global.alloc(Tuple::new())),
+    ]);
+
+    let json = serde_json::to_string(&Value::object(global.alloc(tuple))).unwrap();
+
+    assert_eq!(json, r#"[1,"a",[]]"#);
+  }
+
+  #[test]
+  fn serialize_table_non_string_keys() {
+    let global = Global::default();
+
+    let table = Table::new();
+    table.insert(global.alloc(Str::owned("a")), Value::int(0));
+    table.insert_key(Key::plain(Value::int(1)).unwrap(), Value::bool(true));
+
+    let json = serde_json::to_string(&Value::object(global.alloc(table))).unwrap();
+
+    assert_eq!(json, r#"{"a":0,"1":true}"#);
+  }
+
+  #[test]
+  fn serialize_set() {
+    let global = Global::default();
+
+    let set = Set::new();
+    set.insert(Key::plain(Value::int(1)).unwrap());
+    set.insert(Key::plain(Value::object(global.alloc(Str::owned("a")))).unwrap());
+    set.insert(Key::plain(Value::int(1)).unwrap());
+
+    let json = serde_json::to_string(&Value::object(global.alloc(set))).unwrap();
+
+    assert_eq!(json, r#"[1,"a"]"#);
+  }
+
+  #[test]
+  fn serialize_bytes() {
+    let global = Global::default();
+
+    let bytes = Bytes::from(vec![1, 2, 255]);
+
+    let json = serde_json::to_string(&Value::object(global.alloc(bytes))).unwrap();
+
+    assert_eq!(json, "[1,2,255]");
+  }
+
+  #[test]
+  fn deserialize_table() {
+    let global = Global::default();
+
+    let json = r#"{"a":0,"1":true}"#;
+
+    let value = ValueDeserializer { global }
+      .deserialize(&mut serde_json::Deserializer::from_str(json))
+      .unwrap();
+
+    let table = value.to_object::<Table>().unwrap();
+    assert_eq!(table.get("a").and_then(|v| v.to_int()), Some(0));
+    assert_eq!(table.get("1").and_then(|v| v.to_bool()), Some(true));
   }
 }
@@ -4,26 +4,41 @@ pub mod parser;
 
 use std::error::Error as StdError;
 use std::fmt::Display;
+use std::sync::Arc;
 
 pub use ast::Module;
 pub use parser::parse;
 
-use crate::span::SpannedError;
+use crate::span::{Source, SpannedError};
 use crate::util::JoinIter;
 
 #[derive(Debug)]
 pub struct SyntaxError {
   errors: Vec<SpannedError>,
+  source: Option<Arc<Source>>,
 }
 
 impl SyntaxError {
   fn new(errors: Vec<SpannedError>) -> Self {
-    Self { errors }
+    Self {
+      errors,
+      source: None,
+    }
+  }
+
+  pub(crate) fn with_source(mut self, source: Arc<Source>) -> Self {
+    self.source = Some(source);
+    self
   }
 
   pub fn errors(&self) -> &[SpannedError] {
     &self.errors
   }
+
+  /// The source which failed to parse.
+  pub fn source(&self) -> Option<&Source> {
+    self.source.as_deref()
+  }
 }
 
 impl Display for SyntaxError {
@@ -2,6 +2,7 @@
 
 use std::fmt::Display;
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 
 use crate::span::{Span, Spanned};
 use crate::Cow;
@@ -70,10 +71,14 @@ pub enum StmtKind<'src> {
   Ctrl(Box<Ctrl<'src>>),
   Func(Box<Func<'src>>),
   Class(Box<Class<'src>>),
+  Enum(Box<Enum<'src>>),
   Expr(Box<Expr<'src>>),
   Pass,
   Print(Box<Print<'src>>),
   Import(Box<Import<'src>>),
+  Try(Box<Try<'src>>),
+  Destructure(Box<Destructure<'src>>),
+  Match(Box<Match<'src>>),
 }
 
 #[cfg_attr(test, derive(Debug))]
@@ -107,11 +112,17 @@ pub struct Func<'src> {
 pub struct Params<'src> {
   pub has_self: bool,
   pub pos: Vec<Param<'src>>,
+  /// Collects any arguments past the positional params into a list.
+  pub rest: Option<Ident<'src>>,
 }
 
 impl<'src> Params<'src> {
   pub fn contains(&self, param: &Ident<'src>) -> bool {
     self.pos.iter().any(|v| v.name.as_ref() == param.as_ref())
+      || self
+        .rest
+        .as_ref()
+        .is_some_and(|v| v.as_ref() == param.as_ref())
   }
 }
 
@@ -155,6 +166,20 @@ impl<'src> ClassMembers<'src> {
   }
 }
 
+#[cfg_attr(test, derive(Debug))]
+pub struct Enum<'src> {
+  pub name: Ident<'src>,
+  pub variants: Vec<Variant<'src>>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct Variant<'src> {
+  pub name: Ident<'src>,
+  /// Names of the values carried by the variant, which is empty for unit
+  /// variants.
+  pub fields: Vec<Ident<'src>>,
+}
+
 #[cfg_attr(test, derive(Debug))]
 pub struct Field<'src> {
   pub name: Ident<'src>,
@@ -176,18 +201,20 @@ pub enum Loop<'src> {
 
 #[cfg_attr(test, derive(Debug))]
 pub struct For<'src> {
-  pub item: Ident<'src>,
+  pub item: Pattern<'src>,
   pub iter: ForIter<'src>,
   pub body: Vec<Stmt<'src>>,
 }
 
 #[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
 pub enum ForIter<'src> {
   Range(IterRange<'src>),
   Expr(Expr<'src>),
 }
 
 #[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
 pub struct IterRange<'src> {
   pub start: Expr<'src>,
   pub end: Expr<'src>,
@@ -231,6 +258,13 @@ pub enum ExprKind<'src> {
   GetIndex(Box<GetIndex<'src>>),
   SetIndex(Box<SetIndex<'src>>),
   Call(Box<Call<'src>>),
+  FString(Box<FString<'src>>),
+  Comprehension(Box<Comprehension<'src>>),
+  /// An anonymous function. Statements are not `Clone`, so the function
+  /// is shared instead.
+  Func(Rc<Func<'src>>),
+  /// A list spread into the arguments of a call.
+  Spread(Box<Expr<'src>>),
   GetSelf,
   GetSuper,
 }
@@ -239,11 +273,12 @@ pub enum ExprKind<'src> {
 #[derive(Clone)]
 pub enum Literal<'src> {
   None,
-  Int(i32),
+  Int(i64),
   Float(f64),
   Bool(bool),
   String(Cow<'src, str>),
   List(Vec<Expr<'src>>),
+  Tuple(Vec<Expr<'src>>),
   Table(Vec<(Expr<'src>, Expr<'src>)>),
 }
 
@@ -263,6 +298,12 @@ pub enum BinaryOp {
   Mul,
   Rem,
   Pow,
+  FloorDiv,
+  BitAnd,
+  BitOr,
+  BitXor,
+  Shl,
+  Shr,
   Eq,
   Neq,
   More,
@@ -289,6 +330,7 @@ pub enum UnaryOp {
   Plus,
   Minus,
   Not,
+  BitNot,
   Opt,
 }
 
@@ -333,6 +375,33 @@ pub struct SetIndex<'src> {
   pub value: Expr<'src>,
 }
 
+/// `f"a {b} c"`
+///
+/// Each part is either a string literal, or an embedded expression which is
+/// converted to a string.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
+pub struct FString<'src> {
+  pub parts: Vec<Expr<'src>>,
+}
+
+/// `[value for item in iter if cond]` or `{key: value for item in iter if cond}`
+#[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
+pub struct Comprehension<'src> {
+  pub kind: ComprehensionKind<'src>,
+  pub item: Pattern<'src>,
+  pub iter: ForIter<'src>,
+  pub cond: Option<Expr<'src>>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
+pub enum ComprehensionKind<'src> {
+  List(Expr<'src>),
+  Table(Expr<'src>, Expr<'src>),
+}
+
 #[cfg_attr(test, derive(Debug))]
 #[derive(Clone, Copy)]
 pub enum AssignOp {
@@ -377,11 +446,19 @@ pub struct Return<'src> {
   pub value: Option<Expr<'src>>,
 }
 
+#[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
+pub struct Throw<'src> {
+  pub value: Expr<'src>,
+}
+
 #[cfg_attr(test, derive(Debug))]
 #[derive(Clone)]
 pub struct Call<'src> {
   pub target: Expr<'src>,
   pub args: Vec<Expr<'src>>,
+  /// Arguments passed by name, which always follow the positional ones.
+  pub named: Vec<(Ident<'src>, Expr<'src>)>,
 }
 
 #[cfg_attr(test, derive(Debug))]
@@ -390,6 +467,25 @@ pub struct Var<'src> {
   pub value: Expr<'src>,
 }
 
+#[cfg_attr(test, derive(Debug))]
+#[derive(Clone)]
+pub enum Pattern<'src> {
+  /// `a`
+  Ident(Ident<'src>),
+  /// `a, b`, `(a, b)` or `[a, b]`, which binds the items of a list or tuple.
+  Seq(Vec<Ident<'src>>),
+  /// `{a, b}`, which binds the entries of a table.
+  Table(Vec<Ident<'src>>),
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct Destructure<'src> {
+  pub pattern: Pattern<'src>,
+  pub value: Expr<'src>,
+  /// `:=` declares new variables, while `=` assigns to existing ones.
+  pub is_decl: bool,
+}
+
 #[cfg_attr(test, derive(Debug))]
 pub struct If<'src> {
   pub branches: Vec<Branch<'src>>,
@@ -402,10 +498,72 @@ pub struct Branch<'src> {
   pub body: Vec<Stmt<'src>>,
 }
 
+#[cfg_attr(test, derive(Debug))]
+pub struct Match<'src> {
+  pub value: Expr<'src>,
+  pub arms: Vec<MatchArm<'src>>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct MatchArm<'src> {
+  pub pattern: MatchPattern<'src>,
+  pub guard: Option<Expr<'src>>,
+  pub body: Vec<Stmt<'src>>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub enum MatchPattern<'src> {
+  /// `_`, which matches anything.
+  Wildcard,
+  /// `a`, which matches anything and binds it to `a`.
+  Bind(Ident<'src>),
+  /// `1`, `"a"`, `none`, etc., which match values equal to the literal.
+  Literal(Expr<'src>),
+  /// `0..10` or `0..=10`
+  Range(Box<MatchRange<'src>>),
+  /// `is T`, which matches values that are `T`.
+  Type(Expr<'src>),
+  /// `E.A` or `E.B(a, b)`, which matches values of the enum variant, and
+  /// the variant's values if there are patterns for them.
+  Variant(Box<MatchVariant<'src>>),
+  /// `[a, b]` or `(a, b)`, which matches a list or tuple with exactly as
+  /// many items as there are patterns.
+  Seq(Vec<MatchPattern<'src>>),
+  /// `{a, b: pattern}`, which matches a table with all of the given keys.
+  Table(Vec<(Ident<'src>, MatchPattern<'src>)>),
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct MatchVariant<'src> {
+  pub path: Expr<'src>,
+  pub fields: Option<Vec<MatchPattern<'src>>>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct MatchRange<'src> {
+  pub start: Expr<'src>,
+  pub end: Expr<'src>,
+  pub inclusive: bool,
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct Try<'src> {
+  pub body: Vec<Stmt<'src>>,
+  pub catch: Option<Catch<'src>>,
+  pub finally: Option<Vec<Stmt<'src>>>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+pub struct Catch<'src> {
+  pub name: Option<Ident<'src>>,
+  pub body: Vec<Stmt<'src>>,
+}
+
 #[cfg_attr(test, derive(Debug))]
 pub enum Ctrl<'src> {
   Return(Return<'src>),
   Yield(Yield<'src>),
+  Throw(Throw<'src>),
   Continue,
   Break,
 }
@@ -444,6 +602,46 @@ pub fn branch<'src>(cond: Expr<'src>, body: Vec<Stmt<'src>>) -> Branch<'src> {
   Branch { cond, body }
 }
 
+pub fn match_stmt<'src>(
+  s: impl Into<Span>,
+  value: Expr<'src>,
+  arms: Vec<MatchArm<'src>>,
+) -> Stmt<'src> {
+  Stmt::new(s, StmtKind::Match(Box::new(Match { value, arms })))
+}
+
+pub fn match_arm<'src>(
+  pattern: MatchPattern<'src>,
+  guard: Option<Expr<'src>>,
+  body: Vec<Stmt<'src>>,
+) -> MatchArm<'src> {
+  MatchArm {
+    pattern,
+    guard,
+    body,
+  }
+}
+
+pub fn try_stmt<'src>(
+  s: impl Into<Span>,
+  body: Vec<Stmt<'src>>,
+  catch: Option<Catch<'src>>,
+  finally: Option<Vec<Stmt<'src>>>,
+) -> Stmt<'src> {
+  Stmt::new(
+    s,
+    StmtKind::Try(Box::new(Try {
+      body,
+      catch,
+      finally,
+    })),
+  )
+}
+
+pub fn catch<'src>(name: Option<Ident<'src>>, body: Vec<Stmt<'src>>) -> Catch<'src> {
+  Catch { name, body }
+}
+
 pub fn return_stmt(s: impl Into<Span>, value: Option<Expr>) -> Stmt {
   Stmt::new(s, StmtKind::Ctrl(Box::new(Ctrl::Return(Return { value }))))
 }
@@ -459,6 +657,10 @@ pub fn continue_stmt<'src>(s: impl Into<Span>) -> Stmt<'src> {
   Stmt::new(s, StmtKind::Ctrl(Box::new(Ctrl::Continue)))
 }
 
+pub fn throw_stmt(s: impl Into<Span>, value: Expr) -> Stmt {
+  Stmt::new(s, StmtKind::Ctrl(Box::new(Ctrl::Throw(Throw { value }))))
+}
+
 pub fn break_stmt<'src>(s: impl Into<Span>) -> Stmt<'src> {
   Stmt::new(s, StmtKind::Ctrl(Box::new(Ctrl::Break)))
 }
@@ -488,8 +690,20 @@ pub fn expr_call<'src>(
   s: impl Into<Span>,
   target: Expr<'src>,
   args: Vec<Expr<'src>>,
+  named: Vec<(Ident<'src>, Expr<'src>)>,
 ) -> Expr<'src> {
-  Expr::new(s, ExprKind::Call(Box::new(Call { target, args })))
+  Expr::new(
+    s,
+    ExprKind::Call(Box::new(Call {
+      target,
+      args,
+      named,
+    })),
+  )
+}
+
+pub fn expr_spread<'src>(s: impl Into<Span>, value: Expr<'src>) -> Expr<'src> {
+  Expr::new(s, ExprKind::Spread(Box::new(value)))
 }
 
 pub fn expr_get_field<'src>(
@@ -508,6 +722,10 @@ pub fn expr_list(s: impl Into<Span>, items: Vec<Expr>) -> Expr {
   Expr::new(s, ExprKind::Literal(Box::new(Literal::List(items))))
 }
 
+pub fn expr_tuple(s: impl Into<Span>, items: Vec<Expr>) -> Expr {
+  Expr::new(s, ExprKind::Literal(Box::new(Literal::Tuple(items))))
+}
+
 pub fn ident_key(v: Ident) -> Expr {
   Expr::new(
     v.span,
@@ -519,6 +737,32 @@ pub fn expr_table<'src>(s: impl Into<Span>, items: Vec<(Expr<'src>, Expr<'src>)>
   Expr::new(s, ExprKind::Literal(Box::new(Literal::Table(items))))
 }
 
+pub fn expr_fstring(s: impl Into<Span>, parts: Vec<Expr>) -> Expr {
+  Expr::new(s, ExprKind::FString(Box::new(FString { parts })))
+}
+
+pub fn expr_comprehension<'src>(
+  s: impl Into<Span>,
+  kind: ComprehensionKind<'src>,
+  item: Pattern<'src>,
+  iter: ForIter<'src>,
+  cond: Option<Expr<'src>>,
+) -> Expr<'src> {
+  Expr::new(
+    s,
+    ExprKind::Comprehension(Box::new(Comprehension {
+      kind,
+      item,
+      iter,
+      cond,
+    })),
+  )
+}
+
+pub fn expr_func(s: impl Into<Span>, func: Func) -> Expr {
+  Expr::new(s, ExprKind::Func(Rc::new(func)))
+}
+
 pub fn expr_get_var(name: Ident) -> Expr {
   Expr::new(name.span, ExprKind::GetVar(Box::new(GetVar { name })))
 }
@@ -542,6 +786,22 @@ pub fn var_stmt<'src>(name: Ident<'src>, value: Expr<'src>) -> Stmt<'src> {
   )
 }
 
+pub fn destructure_stmt<'src>(
+  s: impl Into<Span>,
+  pattern: Pattern<'src>,
+  value: Expr<'src>,
+  is_decl: bool,
+) -> Stmt<'src> {
+  Stmt::new(
+    s,
+    StmtKind::Destructure(Box::new(Destructure {
+      pattern,
+      value,
+      is_decl,
+    })),
+  )
+}
+
 pub fn func_stmt(s: impl Into<Span>, func: Func) -> Stmt {
   Stmt::new(s, StmtKind::Func(Box::new(func)))
 }
@@ -576,6 +836,14 @@ pub fn class_stmt<'src>(
   )
 }
 
+pub fn enum_stmt<'src>(
+  s: impl Into<Span>,
+  name: Ident<'src>,
+  variants: Vec<Variant<'src>>,
+) -> Stmt<'src> {
+  Stmt::new(s, StmtKind::Enum(Box::new(Enum { name, variants })))
+}
+
 pub fn assign<'src>(target: Expr<'src>, kind: AssignKind, value: Expr<'src>) -> Option<Stmt<'src>> {
   let span = Span::from(target.span.start..value.span.end);
   match kind {
@@ -667,7 +935,7 @@ pub fn while_loop_stmt<'src>(
 
 pub fn for_loop_stmt<'src>(
   s: impl Into<Span>,
-  item: Ident<'src>,
+  item: Pattern<'src>,
   iter: ForIter<'src>,
   body: Vec<Stmt<'src>>,
 ) -> Stmt<'src> {
@@ -699,15 +967,13 @@ pub mod lit {
   pub fn int<'src>(s: impl Into<Span>, lexeme: &'src str) -> Result<Expr<'src>, SpannedError> {
     let s = s.into();
     let value = lexeme
+      .replace('_', "")
       .parse::<i64>()
       .map_err(|e| SpannedError::new(format!("invalid number {e}"), s))?;
-    let lit = if value < (i32::MIN as i64) || (i32::MAX as i64) < value {
-      // TODO: bigint?
-      Literal::Float(value as f64)
-    } else {
-      Literal::Int(value as i32)
-    };
-    Ok(Expr::new(s, ExprKind::Literal(Box::new(lit))))
+    Ok(Expr::new(
+      s,
+      ExprKind::Literal(Box::new(Literal::Int(value))),
+    ))
   }
 
   pub fn float<'src>(s: impl Into<Span>, lexeme: &'src str) -> Result<Expr<'src>, SpannedError> {
@@ -736,7 +1002,7 @@ pub mod lit {
   // Adapted from https://docs.rs/snailquote/0.3.0/x86_64-pc-windows-msvc/src/snailquote/lib.rs.html.
   /// Unescapes the given string in-place. Returns `None` if the string contains
   /// an invalid escape sequence.
-  fn unescape_in_place(s: &mut String) -> Option<()> {
+  pub fn unescape_in_place(s: &mut String) -> Option<()> {
     let mut out = String::with_capacity(s.len());
     let mut chars = s.chars();
     while let Some(ch) = chars.next() {
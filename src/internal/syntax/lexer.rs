@@ -54,6 +54,37 @@ impl<'src> Lexer<'src> {
     lex
   }
 
+  /// Create a lexer which only produces the tokens in `range` of `src`.
+  ///
+  /// Spans of the tokens are still relative to the start of `src`.
+  pub fn with_range(src: &'src str, range: Range<usize>) -> Self {
+    let end = range.end;
+    let eof = Token {
+      ws: None,
+      span: (end..end).into(),
+      kind: TokenKind::Tok_Eof,
+    };
+
+    let mut inner = TokenKind::lexer(&src[..end]);
+    inner.bump(range.start);
+    let mut lex = Self {
+      src,
+      inner,
+      previous: eof.clone(),
+      current: eof.clone(),
+      ws: None,
+      eof,
+    };
+    lex.bump();
+
+    lex
+  }
+
+  #[inline]
+  pub fn src(&self) -> &'src str {
+    self.src
+  }
+
   #[inline]
   pub fn previous(&self) -> &Token {
     &self.previous
@@ -133,6 +164,8 @@ pub enum TokenKind {
   Kw_Yield,
   #[token("class")]
   Kw_Class,
+  #[token("enum")]
+  Kw_Enum,
   #[token("self")]
   Kw_Self,
   #[token("super")]
@@ -161,8 +194,20 @@ pub enum TokenKind {
   Kw_Elif,
   #[token("else")]
   Kw_Else,
+  #[token("match")]
+  Kw_Match,
+  #[token("case")]
+  Kw_Case,
   #[token("pass")]
   Kw_Pass,
+  #[token("try")]
+  Kw_Try,
+  #[token("catch")]
+  Kw_Catch,
+  #[token("finally")]
+  Kw_Finally,
+  #[token("throw")]
+  Kw_Throw,
 
   // Brackets
   #[token("{")]
@@ -223,6 +268,8 @@ pub enum TokenKind {
   Op_Minus,
   #[token("/")]
   Op_Slash,
+  #[token("//")]
+  Op_SlashSlash,
   #[token("*")]
   Op_Star,
   #[token("%")]
@@ -245,6 +292,18 @@ pub enum TokenKind {
   Op_PipePipe,
   #[token("&&")]
   Op_AndAnd,
+  #[token("|")]
+  Op_Pipe,
+  #[token("&")]
+  Op_And,
+  #[token("^")]
+  Op_Caret,
+  #[token("~")]
+  Op_Tilde,
+  #[token("<<")]
+  Op_LessLess,
+  #[token(">>")]
+  Op_MoreMore,
   #[token("..")]
   Op_Range,
   #[token("..=")]
@@ -265,6 +324,9 @@ pub enum TokenKind {
   Lit_Bool,
   #[regex(r#""([^"\\]|\\.)*""#)] // fix highlighting -> "
   Lit_String,
+  /// `f"a {b} c"`
+  #[token("f\"", lex_fstring)]
+  Lit_FString,
   /// `a`, `b_c`, `__x0`, etc.
   #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
   Lit_Ident,
@@ -293,6 +355,7 @@ impl TokenKind {
       TokenKind::Kw_Fn => "fn",
       TokenKind::Kw_Yield => "yield",
       TokenKind::Kw_Class => "class",
+      TokenKind::Kw_Enum => "enum",
       TokenKind::Kw_Self => "self",
       TokenKind::Kw_Super => "super",
       TokenKind::Kw_For => "for",
@@ -307,7 +370,13 @@ impl TokenKind {
       TokenKind::Kw_If => "if",
       TokenKind::Kw_Elif => "elif",
       TokenKind::Kw_Else => "else",
+      TokenKind::Kw_Match => "match",
+      TokenKind::Kw_Case => "case",
       TokenKind::Kw_Pass => "pass",
+      TokenKind::Kw_Try => "try",
+      TokenKind::Kw_Catch => "catch",
+      TokenKind::Kw_Finally => "finally",
+      TokenKind::Kw_Throw => "throw",
       TokenKind::Brk_CurlyL => "{",
       TokenKind::Brk_CurlyR => "}",
       TokenKind::Brk_ParenL => "(",
@@ -334,6 +403,7 @@ impl TokenKind {
       TokenKind::Op_Plus => "+",
       TokenKind::Op_Minus => "-",
       TokenKind::Op_Slash => "/",
+      TokenKind::Op_SlashSlash => "//",
       TokenKind::Op_Star => "*",
       TokenKind::Op_Percent => "%",
       TokenKind::Op_StarStar => "**",
@@ -345,6 +415,12 @@ impl TokenKind {
       TokenKind::Op_LessEqual => "<=",
       TokenKind::Op_PipePipe => "||",
       TokenKind::Op_AndAnd => "&&",
+      TokenKind::Op_Pipe => "|",
+      TokenKind::Op_And => "&",
+      TokenKind::Op_Caret => "^",
+      TokenKind::Op_Tilde => "~",
+      TokenKind::Op_LessLess => "<<",
+      TokenKind::Op_MoreMore => ">>",
       TokenKind::Op_Range => "..",
       TokenKind::Op_RangeInc => "..=",
       TokenKind::Lit_None => "none",
@@ -352,6 +428,7 @@ impl TokenKind {
       TokenKind::Lit_Float => "float",
       TokenKind::Lit_Bool => "bool",
       TokenKind::Lit_String => "string",
+      TokenKind::Lit_FString => "f-string",
       TokenKind::Lit_Ident => "identifier",
       TokenKind::_Tok_Indent => "<indentation>",
       TokenKind::_Tok_Whitespace => "<whitespace>",
@@ -362,6 +439,83 @@ impl TokenKind {
   }
 }
 
+/// Lex the rest of an f-string after its opening `f"`.
+fn lex_fstring(lex: &mut logos::Lexer<'_, TokenKind>) -> bool {
+  match fstring_end(lex.remainder()) {
+    Some(end) => {
+      lex.bump(end);
+      true
+    }
+    None => false,
+  }
+}
+
+/// Find the end of the body of an f-string, where `s` starts right after the
+/// opening `f"`. Returns the offset right after the closing `"`.
+fn fstring_end(s: &str) -> Option<usize> {
+  let bytes = s.as_bytes();
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'"' => return Some(i + 1),
+      b'\\' => i += 2,
+      b'{' if bytes.get(i + 1) == Some(&b'{') => i += 2,
+      b'{' => i += 1 + fstring_expr_end(&s[i + 1..])? + 1,
+      _ => i += 1,
+    }
+  }
+  None
+}
+
+/// Find the `}` which closes an expression embedded in an f-string, where `s`
+/// starts right after the opening `{`. Braces and strings which are part of
+/// the expression are skipped.
+pub fn fstring_expr_end(s: &str) -> Option<usize> {
+  let bytes = s.as_bytes();
+  let mut depth = 0usize;
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'{' => depth += 1,
+      b'}' if depth == 0 => return Some(i),
+      b'}' => depth -= 1,
+      b'f' if bytes.get(i + 1) == Some(&b'"') && !is_ident_byte(bytes, i.checked_sub(1)) => {
+        i += 2 + fstring_end(&s[i + 2..])?;
+        continue;
+      }
+      b'"' => {
+        i += 1 + string_end(&s[i + 1..])?;
+        continue;
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  None
+}
+
+/// Find the end of a string literal, where `s` starts right after the opening
+/// `"`. Returns the offset right after the closing `"`.
+fn string_end(s: &str) -> Option<usize> {
+  let bytes = s.as_bytes();
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'"' => return Some(i + 1),
+      b'\\' => i += 2,
+      _ => i += 1,
+    }
+  }
+  None
+}
+
+fn is_ident_byte(bytes: &[u8], index: Option<usize>) -> bool {
+  index
+    .and_then(|i| bytes.get(i))
+    .map(|b| b.is_ascii_alphanumeric() || *b == b'_')
+    .unwrap_or(false)
+}
+
 fn measure_indent(s: &str) -> u64 {
   let pos = s.rfind('\n').unwrap_or(0);
   (s.len() - pos - 1) as u64
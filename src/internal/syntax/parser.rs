@@ -3,7 +3,7 @@
 
 use self::indent::IndentStack;
 use super::lexer::TokenKind::*;
-use super::lexer::{Lexer, Token, TokenKind};
+use super::lexer::{self, Lexer, Token, TokenKind};
 use super::{ast, SyntaxError};
 use crate::internal::vm::global::Global;
 use crate::span::{Span, SpannedError};
@@ -58,6 +58,17 @@ impl<'src> State<'src> {
     }
   }
 
+  /// Control flow may not jump out of a `finally` block,
+  /// so any enclosing loop is hidden from it.
+  pub fn with_finally(&self) -> Self {
+    Self {
+      ignore_indent: false,
+      current_loop: None,
+      current_func: self.current_func.clone(),
+      current_class: self.current_class,
+    }
+  }
+
   pub fn with_loop(&self) -> Self {
     Self {
       ignore_indent: false,
@@ -250,14 +261,17 @@ impl<'src> Parser<'src> {
     self.bump();
     while !self.current().is(Tok_Eof) {
       // break when exiting a block (dedent)
-      // but not in an if statement, because it is composed of multiple blocks
-      if self.dedent().is_ok() && ![Kw_Else, Kw_Elif].contains(&self.current().kind) {
+      // but not in an if or try statement, because they are composed of multiple blocks
+      if self.dedent().is_ok()
+        && ![Kw_Else, Kw_Elif, Kw_Catch, Kw_Finally].contains(&self.current().kind)
+      {
         break;
       }
 
       match self.current().kind {
         // break on keywords that begin statements
-        Kw_Import | Kw_From | Kw_Fn | Kw_Class | Kw_For | Kw_While | Kw_Loop | Kw_If => break,
+        Kw_Import | Kw_From | Kw_Fn | Kw_Class | Kw_Enum | Kw_For | Kw_While | Kw_Loop | Kw_If
+        | Kw_Try => break,
         // handle any errors
         Tok_Error => self.errors.push(SpannedError::new(
           format!("invalid token `{}`", self.lex.lexeme(self.current())),
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use super::*;
 
 impl<'src> Parser<'src> {
@@ -67,7 +69,7 @@ impl<'src> Parser<'src> {
   }
 
   fn comp_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
-    let mut left = self.add_expr()?;
+    let mut left = self.bit_or_expr()?;
     while self.no_indent().is_ok() {
       let op = match self.current().kind {
         Op_Less => ast::BinaryOp::Less,
@@ -80,6 +82,67 @@ impl<'src> Parser<'src> {
       };
       self.bump(); // bump operator
       self.no_indent()?;
+      let right = self.bit_or_expr()?;
+      left = ast::expr_binary(left.span.start..right.span.end, op, left, right);
+    }
+    Ok(left)
+  }
+
+  fn bit_or_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+    let mut left = self.bit_xor_expr()?;
+    while self.no_indent().is_ok() && self.bump_if(Op_Pipe) {
+      self.no_indent()?;
+      let right = self.bit_xor_expr()?;
+      left = ast::expr_binary(
+        left.span.start..right.span.end,
+        ast::BinaryOp::BitOr,
+        left,
+        right,
+      );
+    }
+    Ok(left)
+  }
+
+  fn bit_xor_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+    let mut left = self.bit_and_expr()?;
+    while self.no_indent().is_ok() && self.bump_if(Op_Caret) {
+      self.no_indent()?;
+      let right = self.bit_and_expr()?;
+      left = ast::expr_binary(
+        left.span.start..right.span.end,
+        ast::BinaryOp::BitXor,
+        left,
+        right,
+      );
+    }
+    Ok(left)
+  }
+
+  fn bit_and_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+    let mut left = self.shift_expr()?;
+    while self.no_indent().is_ok() && self.bump_if(Op_And) {
+      self.no_indent()?;
+      let right = self.shift_expr()?;
+      left = ast::expr_binary(
+        left.span.start..right.span.end,
+        ast::BinaryOp::BitAnd,
+        left,
+        right,
+      );
+    }
+    Ok(left)
+  }
+
+  fn shift_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+    let mut left = self.add_expr()?;
+    while self.no_indent().is_ok() {
+      let op = match self.current().kind {
+        Op_LessLess => ast::BinaryOp::Shl,
+        Op_MoreMore => ast::BinaryOp::Shr,
+        _ => break,
+      };
+      self.bump(); // bump operator
+      self.no_indent()?;
       let right = self.add_expr()?;
       left = ast::expr_binary(left.span.start..right.span.end, op, left, right);
     }
@@ -108,6 +171,7 @@ impl<'src> Parser<'src> {
       let op = match self.current().kind {
         Op_Star => ast::BinaryOp::Mul,
         Op_Slash => ast::BinaryOp::Div,
+        Op_SlashSlash => ast::BinaryOp::FloorDiv,
         Op_Percent => ast::BinaryOp::Rem,
         _ => break,
       };
@@ -139,6 +203,7 @@ impl<'src> Parser<'src> {
       Op_Minus => ast::UnaryOp::Minus,
       Op_Plus => ast::UnaryOp::Plus,
       Op_Bang => ast::UnaryOp::Not,
+      Op_Tilde => ast::UnaryOp::BitNot,
       Tok_Question => ast::UnaryOp::Opt,
       _ => return self.postfix_expr(),
     };
@@ -149,13 +214,13 @@ impl<'src> Parser<'src> {
     Ok(ast::expr_unary(start..right.span.end, op, right))
   }
 
-  fn postfix_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+  pub(super) fn postfix_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
     let mut expr = self.primary_expr()?;
     while self.no_indent().is_ok() {
       match self.current().kind {
         Brk_ParenL => {
-          let args = self.call_args()?; // bumps `(`
-          expr = ast::expr_call(expr.span.start..self.previous().span.end, expr, args);
+          let (args, named) = self.call_args()?; // bumps `(`
+          expr = ast::expr_call(expr.span.start..self.previous().span.end, expr, args, named);
         }
         Brk_SquareL => {
           self.bump(); // bump `[`
@@ -174,7 +239,7 @@ impl<'src> Parser<'src> {
     Ok(expr)
   }
 
-  fn primary_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+  pub(super) fn primary_expr(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
     self.check_recursion_limit(self.current().span)?;
 
     if self.bump_if(Lit_None) {
@@ -204,12 +269,22 @@ impl<'src> Parser<'src> {
       }
     }
 
+    if self.bump_if(Lit_FString) {
+      let span = self.previous().span;
+      return self.fstring(span);
+    }
+
     if self.bump_if(Brk_SquareL) {
       let start = self.previous().span.start;
 
       let mut items = vec![];
       if !self.current().is(Brk_SquareR) {
-        items.push(self.expr()?);
+        let value = self.expr()?;
+        if self.bump_if(Kw_For) {
+          let kind = ast::ComprehensionKind::List(value);
+          return self.comprehension(start, kind, Brk_SquareR);
+        }
+        items.push(value);
         while self.bump_if(Tok_Comma) && !self.current().is(Brk_SquareR) {
           items.push(self.expr()?);
         }
@@ -225,7 +300,25 @@ impl<'src> Parser<'src> {
 
       let mut items = vec![];
       if !self.current().is(Brk_CurlyR) {
-        items.push(self.table_field()?);
+        let name = if self.current().is(Lit_Ident) {
+          Some(self.ident()?)
+        } else {
+          None
+        };
+        let key = match &name {
+          Some(name) => ast::ident_key(name.clone()),
+          None => self.table_key()?,
+        };
+        self.expect(Tok_Colon)?;
+        let value = self.expr()?;
+        if self.bump_if(Kw_For) {
+          // a bare key such as the `k` in `{k: v for k, v in pairs}`
+          // refers to a variable instead of being a string
+          let key = name.map(ast::expr_get_var).unwrap_or(key);
+          let kind = ast::ComprehensionKind::Table(key, value);
+          return self.comprehension(start, kind, Brk_CurlyR);
+        }
+        items.push((key, value));
         while self.bump_if(Tok_Comma) && !self.current().is(Brk_CurlyR) {
           items.push(self.table_field()?);
         }
@@ -236,6 +329,10 @@ impl<'src> Parser<'src> {
       return Ok(ast::expr_table(start..end, items));
     }
 
+    if self.bump_if(Kw_Fn) {
+      return self.lambda();
+    }
+
     if self.bump_if(Kw_Self) {
       if self.state.current_class.is_none()
         || !self
@@ -287,13 +384,92 @@ impl<'src> Parser<'src> {
     }
 
     if self.bump_if(Brk_ParenL) {
+      let start = self.previous().span.start;
       let state = self.state.with_ignore_indent();
-      let expr = self.with_state(state, |p| p.expr())?;
+      return self.with_state(state, |p| p.group_or_tuple(start));
+    }
+
+    Err(SpannedError::new("unexpected token", self.current().span))
+  }
+
+  /// Parse the rest of a comprehension, starting after `for`.
+  fn comprehension(
+    &mut self,
+    start: usize,
+    kind: ast::ComprehensionKind<'src>,
+    end: TokenKind,
+  ) -> Result<ast::Expr<'src>, SpannedError> {
+    let item_start = self.current().span.start;
+    let item = self.pattern()?;
+    let item_span = Span::from(item_start..self.previous().span.end);
+    self.expect(Kw_In)?;
+    let iter = self.for_iter()?;
+    if matches!(iter, ast::ForIter::Range(_)) && !matches!(item, ast::Pattern::Ident(_)) {
+      fail!(@item_span, "cannot destructure a range");
+    }
+    let cond = if self.bump_if(Kw_If) {
+      Some(self.expr()?)
+    } else {
+      None
+    };
+    self.expect(end)?;
+    let end = self.previous().span.end;
+    Ok(ast::expr_comprehension(start..end, kind, item, iter, cond))
+  }
+
+  /// Parse the rest of an anonymous function, which is either
+  /// `fn(x): x * 2` or `fn(x):` followed by an indented block.
+  fn lambda(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+    let start = self.previous().span.start;
+    let name = ast::Ident::new(self.previous().span, Cow::borrowed("<lambda>"));
+    self.no_indent()?;
+    let params = self.func_params()?;
+    self.no_indent()?;
+    self.expect(Tok_Colon)?;
+
+    let state = self.state.with_func(name.lexeme(), params.has_self);
+    let (state, body) = if self.current().ws.is_none() && !self.current().is(Tok_Eof) {
+      // the body is a single expression on the same line
+      let state = State {
+        ignore_indent: self.state.ignore_indent,
+        ..state
+      };
+      let (state, value) = self.with_state2(state, Self::expr)?;
+      (state, vec![ast::return_stmt(value.span, Some(value))])
+    } else {
+      self.with_state2(state, Self::body)?
+    };
+    let has_yield = state
+      .current_func
+      .expect(
+        "`state.current_func` set to `None` by a mysterious force outside of `Parser::lambda`",
+      )
+      .has_yield;
+    let func = ast::func(name, params, body, has_yield);
+    let end = self.previous().span.end;
+    Ok(ast::expr_func(start..end, func))
+  }
+
+  /// Parse the rest of a parenthesized expression, which is a tuple if it is
+  /// empty or contains a comma: `()`, `(a,)`, `(a, b)`.
+  fn group_or_tuple(&mut self, start: usize) -> Result<ast::Expr<'src>, SpannedError> {
+    if self.bump_if(Brk_ParenR) {
+      return Ok(ast::expr_tuple(start..self.previous().span.end, vec![]));
+    }
+
+    let expr = self.expr()?;
+    if !self.current().is(Tok_Comma) {
       self.expect(Brk_ParenR)?;
       return Ok(expr);
     }
 
-    Err(SpannedError::new("unexpected token", self.current().span))
+    let mut items = vec![expr];
+    while self.bump_if(Tok_Comma) && !self.current().is(Brk_ParenR) {
+      items.push(self.expr()?);
+    }
+    self.expect(Brk_ParenR)?;
+    let end = self.previous().span.end;
+    Ok(ast::expr_tuple(start..end, items))
   }
 
   fn table_field(&mut self) -> Result<(ast::Expr<'src>, ast::Expr<'src>), SpannedError> {
@@ -314,20 +490,161 @@ impl<'src> Parser<'src> {
     }
   }
 
-  fn call_args(&mut self) -> Result<Vec<ast::Expr<'src>>, SpannedError> {
+  /// Split the f-string at `span` into string literals and embedded
+  /// expressions.
+  fn fstring(&mut self, span: Span) -> Result<ast::Expr<'src>, SpannedError> {
+    let src = self.lex.src();
+    // strip `f"` and `"`
+    let start = span.start + 2;
+    let body = &src[start..span.end - 1];
+
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut literal_start = start;
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+      match ch {
+        '{' if chars.next_if(|(_, ch)| *ch == '{').is_some() => literal.push('{'),
+        '}' if chars.next_if(|(_, ch)| *ch == '}').is_some() => literal.push('}'),
+        '}' => fail!(@(start + i..start + i + 1), "single `}}` in f-string, use `}}}}` instead"),
+        '{' => {
+          let expr_start = start + i + 1;
+          let Some(len) = lexer::fstring_expr_end(&body[i + 1..]) else {
+            fail!(@(start + i..span.end), "unterminated `{{` in f-string");
+          };
+          let expr_end = expr_start + len;
+
+          self.fstring_literal(&mut parts, &mut literal, literal_start..start + i)?;
+          parts.push(self.fstring_expr(expr_start..expr_end)?);
+          literal_start = expr_end + 1;
+
+          // skip to the closing `}`
+          while chars.next_if(|(j, _)| start + j < expr_end).is_some() {}
+          chars.next();
+        }
+        '\\' => {
+          literal.push(ch);
+          if let Some((_, next)) = chars.next() {
+            literal.push(next);
+          }
+        }
+        _ => literal.push(ch),
+      }
+    }
+    self.fstring_literal(&mut parts, &mut literal, literal_start..span.end - 1)?;
+
+    Ok(ast::expr_fstring(span, parts))
+  }
+
+  fn fstring_literal(
+    &mut self,
+    parts: &mut Vec<ast::Expr<'src>>,
+    literal: &mut String,
+    span: Range<usize>,
+  ) -> Result<(), SpannedError> {
+    if literal.is_empty() {
+      return Ok(());
+    }
+    let mut literal = std::mem::take(literal);
+    if ast::lit::unescape_in_place(&mut literal).is_none() {
+      fail!(@span, "invalid escape sequence");
+    }
+    parts.push(ast::Expr::new(
+      span,
+      ast::ExprKind::Literal(Box::new(ast::Literal::String(Cow::from(literal)))),
+    ));
+    Ok(())
+  }
+
+  fn fstring_expr(&mut self, range: Range<usize>) -> Result<ast::Expr<'src>, SpannedError> {
+    if self.lex.src()[range.clone()].trim().is_empty() {
+      fail!(@(range.start - 1..range.end + 1), "empty expression in f-string");
+    }
+
+    let lex = Lexer::with_range(self.lex.src(), range);
+    let outer = std::mem::replace(&mut self.lex, lex);
+    let state = self.state.with_ignore_indent();
+    let result = self.with_state(state, |p| {
+      let expr = p.expr()?;
+      if !p.current().is(Tok_Eof) {
+        fail!(@p.current().span, "expected `}}`");
+      }
+      Ok(expr)
+    });
+    self.lex = outer;
+    result
+  }
+
+  #[allow(clippy::type_complexity)]
+  fn call_args(
+    &mut self,
+  ) -> Result<
+    (
+      Vec<ast::Expr<'src>>,
+      Vec<(ast::Ident<'src>, ast::Expr<'src>)>,
+    ),
+    SpannedError,
+  > {
     let mut args = Vec::new();
+    let mut named = Vec::new();
     self.expect(Brk_ParenL)?;
     if !self.current().is(Brk_ParenR) {
       let state = self.state.with_ignore_indent();
       self.with_state(state, |p| {
-        args.push(p.expr()?);
+        p.call_arg(&mut args, &mut named)?;
         while p.bump_if(Tok_Comma) && !p.current().is(Brk_ParenR) {
-          args.push(p.expr()?);
+          p.call_arg(&mut args, &mut named)?;
         }
         Ok(())
       })?;
     }
     self.expect(Brk_ParenR)?;
-    Ok(args)
+    Ok((args, named))
+  }
+
+  fn call_arg(
+    &mut self,
+    args: &mut Vec<ast::Expr<'src>>,
+    named: &mut Vec<(ast::Ident<'src>, ast::Expr<'src>)>,
+  ) -> Result<(), SpannedError> {
+    if let Some(name) = self.named_arg_name() {
+      if named.iter().any(|(v, _)| v.as_ref() == name.as_ref()) {
+        fail!(@name.span, "duplicate named argument `{name}`");
+      }
+      let value = self.expr()?;
+      named.push((name, value));
+      return Ok(());
+    }
+
+    if !named.is_empty() {
+      fail!(@self.current().span, "positional argument follows named argument");
+    }
+    if self.bump_if(Op_Star) {
+      let start = self.previous().span.start;
+      let value = self.expr()?;
+      args.push(ast::expr_spread(start..value.span.end, value));
+    } else {
+      args.push(self.expr()?);
+    }
+    Ok(())
+  }
+
+  /// Parse the `name =` part of a named argument. Nothing is consumed if
+  /// the argument is not named.
+  fn named_arg_name(&mut self) -> Option<ast::Ident<'src>> {
+    if !self.current().is(Lit_Ident) {
+      return None;
+    }
+
+    let lex = self.lex.clone();
+    let num_errors = self.errors.len();
+    let name = self.ident().ok()?;
+    if self.bump_if(Op_Equal) {
+      Some(name)
+    } else {
+      self.lex = lex;
+      self.errors.truncate(num_errors);
+      None
+    }
   }
 }
@@ -29,7 +29,10 @@ impl<'src> Parser<'src> {
       Kw_Loop => Some(self.loop_stmt()?),
       Kw_Fn => Some(self.func_stmt()?),
       Kw_Class => Some(self.class_stmt()?),
+      Kw_Enum => Some(self.enum_stmt()?),
       Kw_Import | Kw_From => Some(self.import_stmt()?),
+      Kw_Try => Some(self.try_stmt()?),
+      Kw_Match => Some(self.match_stmt()?),
       _ => None,
     })
   }
@@ -126,15 +129,269 @@ impl<'src> Parser<'src> {
     Ok(ast::branch(cond, body))
   }
 
+  fn try_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
+    self.expect(Kw_Try)?;
+    let start = self.previous().span.start;
+    self.no_indent()?;
+    self.expect(Tok_Colon)?;
+    let body = self.body()?;
+
+    let mut catch = None;
+    let mut finally = None;
+
+    if self.current().is(Kw_Catch) {
+      self.indent_eq()?; // `catch` on same indentation level
+      self.bump(); // bump `catch`
+      let name = if self.no_indent().is_ok() && self.current().is(Lit_Ident) {
+        Some(self.ident()?)
+      } else {
+        None
+      };
+      self.no_indent()?;
+      self.expect(Tok_Colon)?;
+      catch = Some(ast::catch(name, self.body()?));
+    }
+    if self.current().is(Kw_Finally) {
+      self.indent_eq()?; // `finally` on same indentation level
+      self.bump(); // bump `finally`
+      self.no_indent()?;
+      self.expect(Tok_Colon)?;
+      let state = self.state.with_finally();
+      let (state, body) = self.with_state2(state, Self::body)?;
+      // yield may appear in `finally`, in which case we have to propagate it upwards here
+      self.state.current_func = state.current_func;
+      finally = Some(body);
+    }
+
+    if catch.is_none() && finally.is_none() {
+      fail!(@self.current().span, "expected `catch` or `finally`");
+    }
+
+    let end = self.previous().span.end;
+
+    Ok(ast::try_stmt(start..end, body, catch, finally))
+  }
+
+  fn match_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
+    self.expect(Kw_Match)?;
+    let start = self.previous().span.start;
+    self.no_indent()?;
+    let value = self.expr()?;
+    self.no_indent()?;
+    self.expect(Tok_Colon)?;
+
+    self.indent_gt()?;
+    let mut arms = vec![self.match_arm()?];
+    while self.indent_eq().is_ok() && !self.current().is(Tok_Eof) {
+      arms.push(self.match_arm()?);
+    }
+    self.dedent()?;
+
+    let end = self.previous().span.end;
+    Ok(ast::match_stmt(start..end, value, arms))
+  }
+
+  fn match_arm(&mut self) -> Result<ast::MatchArm<'src>, SpannedError> {
+    self.expect(Kw_Case)?;
+    self.no_indent()?;
+    let pattern = self.match_pattern(&mut HashSet::new())?;
+    let guard = if self.no_indent().is_ok() && self.bump_if(Kw_If) {
+      self.no_indent()?;
+      Some(self.expr()?)
+    } else {
+      None
+    };
+    self.no_indent()?;
+    self.expect(Tok_Colon)?;
+    let body = self.body()?;
+    Ok(ast::match_arm(pattern, guard, body))
+  }
+
+  /// Parse a `case` pattern. `bound` holds the names bound by the pattern
+  /// so far, which must be unique.
+  fn match_pattern(
+    &mut self,
+    bound: &mut HashSet<Cow<'src, str>>,
+  ) -> Result<ast::MatchPattern<'src>, SpannedError> {
+    self.check_recursion_limit(self.current().span)?;
+
+    let state = self.state.with_ignore_indent();
+    match self.current().kind {
+      Brk_SquareL => {
+        self.bump(); // bump `[`
+        let (items, _) = self.with_state(state, |p| p.match_patterns(bound, Brk_SquareR))?;
+        Ok(ast::MatchPattern::Seq(items))
+      }
+      Brk_ParenL => {
+        self.bump(); // bump `(`
+        let (mut items, trailing_comma) =
+          self.with_state(state, |p| p.match_patterns(bound, Brk_ParenR))?;
+        // `(a)` is just `a`, but `(a,)` is a tuple
+        if items.len() == 1 && !trailing_comma {
+          return Ok(items.pop().unwrap());
+        }
+        Ok(ast::MatchPattern::Seq(items))
+      }
+      Brk_CurlyL => {
+        self.bump(); // bump `{`
+        let entries = self.with_state(state, |p| p.match_table_entries(bound))?;
+        Ok(ast::MatchPattern::Table(entries))
+      }
+      Kw_Is => {
+        self.bump(); // bump `is`
+        self.no_indent()?;
+        Ok(ast::MatchPattern::Type(self.postfix_expr()?))
+      }
+      Lit_Ident => {
+        let name = self.ident()?;
+        if self.current().is(Op_Dot) && self.no_indent().is_ok() {
+          return self.match_variant(bound, name);
+        }
+        if name == "_" {
+          return Ok(ast::MatchPattern::Wildcard);
+        }
+        if !bound.insert(name.lexeme()) {
+          fail!(@name.span, "`{name}` is bound more than once in the same pattern");
+        }
+        Ok(ast::MatchPattern::Bind(name))
+      }
+      _ => {
+        let (start, start_kind) = self.match_literal()?;
+        let inclusive = match self.current().kind {
+          Op_Range => false,
+          Op_RangeInc => true,
+          _ => return Ok(ast::MatchPattern::Literal(start)),
+        };
+        self.no_indent()?; // range op must be unindented
+        self.bump(); // bump op
+        self.no_indent()?;
+        let (end, end_kind) = self.match_literal()?;
+        // values are only compared with numbers of the same type
+        if !matches!(start_kind, Lit_Int | Lit_Float) || start_kind != end_kind {
+          fail!(
+            @start.span.start..end.span.end,
+            "range bounds must both be integers or both be floats",
+          );
+        }
+        Ok(ast::MatchPattern::Range(Box::new(ast::MatchRange {
+          start,
+          end,
+          inclusive,
+        })))
+      }
+    }
+  }
+
+  /// Parse the rest of a variant pattern such as `E.A` or `E.B(a, b)`,
+  /// starting after `name`.
+  fn match_variant(
+    &mut self,
+    bound: &mut HashSet<Cow<'src, str>>,
+    name: ast::Ident<'src>,
+  ) -> Result<ast::MatchPattern<'src>, SpannedError> {
+    let start = name.span.start;
+    let mut path = ast::expr_get_var(name);
+    while self.no_indent().is_ok() && self.bump_if(Op_Dot) {
+      let field = self.ident()?;
+      path = ast::expr_get_field(start..field.span.end, path, field);
+    }
+
+    let fields = if self.current().is(Brk_ParenL) && self.no_indent().is_ok() {
+      self.bump(); // bump `(`
+      let state = self.state.with_ignore_indent();
+      let (items, _) = self.with_state(state, |p| p.match_patterns(bound, Brk_ParenR))?;
+      Some(items)
+    } else {
+      None
+    };
+
+    Ok(ast::MatchPattern::Variant(Box::new(ast::MatchVariant {
+      path,
+      fields,
+    })))
+  }
+
+  /// Parse a literal, returning it along with the kind of its token.
+  fn match_literal(&mut self) -> Result<(ast::Expr<'src>, TokenKind), SpannedError> {
+    let start = self.current().span.start;
+    let negative = self.bump_if(Op_Minus);
+    if negative {
+      self.no_indent()?;
+    }
+    let kind = self.current().kind;
+    let value = match kind {
+      Lit_Int | Lit_Float => self.primary_expr()?,
+      Lit_None | Lit_Bool | Lit_String if !negative => self.primary_expr()?,
+      _ => fail!(@self.current().span, "expected a pattern"),
+    };
+    if negative {
+      let end = value.span.end;
+      let value = ast::expr_unary(start..end, ast::UnaryOp::Minus, value);
+      return Ok((value, kind));
+    }
+    Ok((value, kind))
+  }
+
+  /// Parse a comma-separated list of patterns up to and including `end`,
+  /// returning the patterns and whether there was a trailing comma.
+  fn match_patterns(
+    &mut self,
+    bound: &mut HashSet<Cow<'src, str>>,
+    end: TokenKind,
+  ) -> Result<(Vec<ast::MatchPattern<'src>>, bool), SpannedError> {
+    let mut items = vec![];
+    if !self.current().is(end) {
+      items.push(self.match_pattern(bound)?);
+      while self.bump_if(Tok_Comma) && !self.current().is(end) {
+        items.push(self.match_pattern(bound)?);
+      }
+    }
+    let trailing_comma = self.previous().is(Tok_Comma);
+    self.expect(end)?;
+    Ok((items, trailing_comma))
+  }
+
+  /// Parse the entries of a table pattern up to and including `}`. An entry
+  /// without a pattern binds the value to a variable with the same name as
+  /// the key.
+  fn match_table_entries(
+    &mut self,
+    bound: &mut HashSet<Cow<'src, str>>,
+  ) -> Result<Vec<(ast::Ident<'src>, ast::MatchPattern<'src>)>, SpannedError> {
+    let mut entries = vec![];
+    while !self.current().is(Brk_CurlyR) {
+      let key = self.ident()?;
+      let pattern = if self.bump_if(Tok_Colon) {
+        self.match_pattern(bound)?
+      } else {
+        if !bound.insert(key.lexeme()) {
+          fail!(@key.span, "`{key}` is bound more than once in the same pattern");
+        }
+        ast::MatchPattern::Bind(key.clone())
+      };
+      entries.push((key, pattern));
+      if !self.bump_if(Tok_Comma) {
+        break;
+      }
+    }
+    self.expect(Brk_CurlyR)?;
+    Ok(entries)
+  }
+
   fn for_loop_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
     self.expect(Kw_For)?;
     let start = self.previous().span.start;
     self.no_indent()?;
-    let item = self.ident()?;
+    let item_start = self.current().span.start;
+    let item = self.pattern()?;
+    let item_span = Span::from(item_start..self.previous().span.end);
     self.no_indent()?;
     self.expect(Kw_In)?;
     self.no_indent()?;
     let iter = self.for_iter()?;
+    if matches!(iter, ast::ForIter::Range(_)) && !matches!(item, ast::Pattern::Ident(_)) {
+      fail!(@item_span, "cannot destructure a range");
+    }
     self.no_indent()?;
     self.expect(Tok_Colon)?;
     let body = self.loop_body()?;
@@ -142,7 +399,7 @@ impl<'src> Parser<'src> {
     Ok(ast::for_loop_stmt(start..end, item, iter, body))
   }
 
-  fn for_iter(&mut self) -> Result<ast::ForIter<'src>, SpannedError> {
+  pub(super) fn for_iter(&mut self) -> Result<ast::ForIter<'src>, SpannedError> {
     let start = self.expr()?;
     let inclusive = match self.current().kind {
       Op_Range => false,
@@ -215,7 +472,7 @@ impl<'src> Parser<'src> {
     Ok(ast::func(name, params, body, has_yield))
   }
 
-  fn func_params(&mut self) -> Result<ast::Params<'src>, SpannedError> {
+  pub(super) fn func_params(&mut self) -> Result<ast::Params<'src>, SpannedError> {
     self.expect(Brk_ParenL)?;
 
     let has_self = self.bump_if(Kw_Self);
@@ -248,10 +505,19 @@ impl<'src> Parser<'src> {
     params: &mut ast::Params<'src>,
     state: &mut ParamState,
   ) -> Result<(), SpannedError> {
+    if *state == ParamState::Rest {
+      fail!(@self.current().span, "rest argument must be the last argument");
+    }
+    let is_rest = self.bump_if(Op_Star);
     let name = self.ident()?;
     if params.contains(&name) {
       fail!(@name.span, "duplicate argument `{name}`");
     }
+    if is_rest {
+      *state = ParamState::Rest;
+      params.rest = Some(name);
+      return Ok(());
+    }
     let default = if self.bump_if(Op_Equal) {
       *state = ParamState::Default;
       Some(self.expr()?)
@@ -394,7 +660,80 @@ impl<'src> Parser<'src> {
     Ok(members)
   }
 
-  fn body(&mut self) -> Result<Vec<ast::Stmt<'src>>, SpannedError> {
+  fn enum_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
+    self.expect(Kw_Enum)?;
+    let start = self.previous().span.start;
+    self.no_indent()?;
+    let name = self.ident()?;
+    self.no_indent()?;
+    self.expect(Tok_Colon)?;
+
+    let mut names = HashSet::new();
+    let mut variants = vec![];
+    if self.no_indent().is_ok() {
+      // inline variants are separated by commas
+      variants.push(self.enum_variant(&mut names)?);
+      while self.bump_if(Tok_Comma) {
+        self.no_indent()?;
+        variants.push(self.enum_variant(&mut names)?);
+      }
+    } else {
+      self.indent_gt()?;
+      variants.push(self.enum_variant(&mut names)?);
+      while self.indent_eq().is_ok() && !self.current().is(Tok_Eof) {
+        variants.push(self.enum_variant(&mut names)?);
+      }
+      self.dedent()?;
+    }
+
+    let end = self.previous().span.end;
+    Ok(ast::enum_stmt(start..end, name, variants))
+  }
+
+  fn enum_variant(
+    &mut self,
+    names: &mut HashSet<ast::Ident<'src>>,
+  ) -> Result<ast::Variant<'src>, SpannedError> {
+    let name = self.ident()?;
+    if !names.insert(name.clone()) {
+      self.errors.push(SpannedError::new(
+        format!("duplicate variant `{name}`"),
+        name.span,
+      ));
+    }
+
+    let mut fields = vec![];
+    if self.current().is(Brk_ParenL) {
+      self.no_indent()?; // opening paren must be unindented
+      self.bump(); // bump opening paren
+      let state = self.state.with_ignore_indent();
+      fields = self.with_state(state, |p| {
+        let mut fields = vec![];
+        while !p.current().is(Brk_ParenR) {
+          let field = p.ident()?;
+          if fields.contains(&field) {
+            p.errors.push(SpannedError::new(
+              format!("duplicate field `{field}`"),
+              field.span,
+            ));
+          }
+          fields.push(field);
+          if !p.bump_if(Tok_Comma) {
+            break;
+          }
+        }
+        p.expect(Brk_ParenR)?;
+        Ok(fields)
+      })?;
+      if fields.is_empty() {
+        fail!(@name.span, "variant `{name}` must have at least one field, or no parentheses");
+      }
+    }
+
+    Ok(ast::Variant { name, fields })
+  }
+
+  pub(super) fn body(&mut self) -> Result<Vec<ast::Stmt<'src>>, SpannedError> {
     self.check_recursion_limit(self.current().span)?;
     if self.no_indent().is_ok() {
       let mut body = vec![self.stmt()?];
@@ -431,6 +770,7 @@ impl<'src> Parser<'src> {
       Kw_Return => self.return_stmt(),
       Kw_Continue => self.continue_stmt(),
       Kw_Break => self.break_stmt(),
+      Kw_Throw => self.throw_stmt(),
       Kw_Yield => self.yield_().map(ast::yield_stmt),
       Kw_Print => self.print_stmt(),
       _ => self.expr_stmt(),
@@ -484,6 +824,15 @@ impl<'src> Parser<'src> {
     Ok(ast::break_stmt(self.previous().span))
   }
 
+  fn throw_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
+    self.expect(Kw_Throw)?;
+    let start = self.previous().span.start;
+    self.no_indent()?;
+    let value = self.expr()?;
+    let end = self.previous().span.end;
+    Ok(ast::throw_stmt(start..end, value))
+  }
+
   fn print_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
     self.expect(Kw_Print)?;
     let start = self.previous().span;
@@ -508,6 +857,10 @@ impl<'src> Parser<'src> {
   }
 
   fn assign_stmt(&mut self) -> Result<ast::Stmt<'src>, SpannedError> {
+    if let Some(stmt) = self.destructure_stmt()? {
+      return Ok(stmt);
+    }
+
     let target = self.expr()?;
 
     'assign: {
@@ -517,7 +870,7 @@ impl<'src> Parser<'src> {
         };
         let error_span = target.span.start..self.previous().span.end;
         self.no_indent()?;
-        let value = self.expr()?;
+        let value = self.assign_value()?;
         let Some(stmt) = ast::assign(target, kind, value) else {
           let msg = match kind {
             ast::AssignKind::Decl => "invalid variable declaration",
@@ -532,6 +885,130 @@ impl<'src> Parser<'src> {
     Ok(ast::expr_stmt(target))
   }
 
+  /// Parse a destructuring assignment such as `a, b := value`.
+  ///
+  /// Patterns look like the start of an expression, so this backtracks and
+  /// returns `None` if the statement turns out not to be one.
+  fn destructure_stmt(&mut self) -> Result<Option<ast::Stmt<'src>>, SpannedError> {
+    if !matches!(
+      self.current().kind,
+      Lit_Ident | Brk_SquareL | Brk_ParenL | Brk_CurlyL
+    ) {
+      return Ok(None);
+    }
+
+    let start = self.current().span.start;
+    let lex = self.lex.clone();
+    let num_errors = self.errors.len();
+    let pattern = match self.pattern() {
+      Ok(ast::Pattern::Ident(_)) | Err(_) => None,
+      Ok(pattern) => Some(pattern),
+    };
+    let is_decl = match self.current().kind {
+      _ if self.no_indent().is_err() => None,
+      Op_ColonEqual => Some(true),
+      Op_Equal => Some(false),
+      Op_PlusEqual
+      | Op_MinusEqual
+      | Op_SlashEqual
+      | Op_StarEqual
+      | Op_PercentEqual
+      | Op_StarStarEqual
+      | Op_QuestionQuestionEqual
+        if pattern.is_some() =>
+      {
+        fail!(
+          @self.current().span,
+          "cannot use `{}` with a destructuring pattern",
+          self.current().kind.name(),
+        );
+      }
+      _ => None,
+    };
+    let (Some(pattern), Some(is_decl)) = (pattern, is_decl) else {
+      self.lex = lex;
+      self.errors.truncate(num_errors);
+      return Ok(None);
+    };
+    self.bump(); // bump operator
+
+    self.no_indent()?;
+    let value = self.assign_value()?;
+    let end = self.previous().span.end;
+    Ok(Some(ast::destructure_stmt(
+      start..end,
+      pattern,
+      value,
+      is_decl,
+    )))
+  }
+
+  /// Parse the right-hand side of an assignment.
+  ///
+  /// Tuples must be parenthesized, so `a, b = b, a` is an error rather than
+  /// a confusing failure to parse the next statement.
+  fn assign_value(&mut self) -> Result<ast::Expr<'src>, SpannedError> {
+    let value = self.expr()?;
+    if self.current().is(Tok_Comma) {
+      fail!(
+        @value.span.start..self.current().span.end,
+        "multiple values must be wrapped in parentheses, as in `(a, b)`"
+      );
+    }
+    Ok(value)
+  }
+
+  /// Parse a destructuring pattern. A single name is returned as
+  /// `Pattern::Ident`.
+  pub(super) fn pattern(&mut self) -> Result<ast::Pattern<'src>, SpannedError> {
+    let state = self.state.with_ignore_indent();
+    if self.bump_if(Brk_SquareL) {
+      let (names, _) = self.with_state(state, |p| p.pattern_names(Brk_SquareR))?;
+      return Ok(ast::Pattern::Seq(names));
+    }
+    if self.bump_if(Brk_ParenL) {
+      let (mut names, trailing_comma) = self.with_state(state, |p| p.pattern_names(Brk_ParenR))?;
+      // `(a)` is just `a`, but `(a,)` is a tuple
+      if names.len() == 1 && !trailing_comma {
+        return Ok(ast::Pattern::Ident(names.pop().unwrap()));
+      }
+      return Ok(ast::Pattern::Seq(names));
+    }
+    if self.bump_if(Brk_CurlyL) {
+      let (names, _) = self.with_state(state, |p| p.pattern_names(Brk_CurlyR))?;
+      return Ok(ast::Pattern::Table(names));
+    }
+
+    let name = self.ident()?;
+    if !self.current().is(Tok_Comma) {
+      return Ok(ast::Pattern::Ident(name));
+    }
+    let mut names = vec![name];
+    while self.no_indent().is_ok() && self.bump_if(Tok_Comma) {
+      self.no_indent()?;
+      names.push(self.ident()?);
+    }
+    Ok(ast::Pattern::Seq(names))
+  }
+
+  /// Parse a comma-separated list of names up to and including `end`,
+  /// returning the names and whether there was a trailing comma.
+  fn pattern_names(
+    &mut self,
+    end: TokenKind,
+  ) -> Result<(Vec<ast::Ident<'src>>, bool), SpannedError> {
+    let mut names = vec![];
+    if !self.current().is(end) {
+      names.push(self.ident()?);
+      while self.bump_if(Tok_Comma) && !self.current().is(end) {
+        names.push(self.ident()?);
+      }
+    }
+    let trailing_comma = self.previous().is(Tok_Comma);
+    self.expect(end)?;
+    Ok((names, trailing_comma))
+  }
+
   fn assign_kind(&mut self) -> Option<ast::AssignKind> {
     let kind = match self.current().kind {
       Op_ColonEqual => ast::AssignKind::Decl,
@@ -561,4 +1038,5 @@ fn extend_path<'src>(p: &Vec<ast::Ident<'src>>, v: ast::Ident<'src>) -> Vec<ast:
 enum ParamState {
   Positional,
   Default,
+  Rest,
 }
@@ -468,6 +468,89 @@ fn if_stmt() {
   }
 }
 
+#[test]
+fn try_stmt() {
+  check_module! {
+    r#"#!hebi
+      try: a
+      catch e: b
+      finally: c
+    "#
+  }
+
+  check_module! {
+    r#"#!hebi
+      try:
+        a
+      catch:
+        b
+    "#
+  }
+
+  check_module! {
+    r#"#!hebi
+      try:
+        a
+      finally:
+        b
+    "#
+  }
+
+  check_module! {
+    r#"#!hebi
+      loop:
+        try:
+          break
+        finally:
+          for i in 0..10:
+            continue
+    "#
+  }
+
+  check_error! {
+    r#"#!hebi
+      try:
+        a
+      b
+    "#
+  }
+
+  check_error! {
+    r#"#!hebi
+      try:
+        a
+        catch e:
+          b
+    "#
+  }
+
+  check_error! {
+    r#"#!hebi
+      loop:
+        try:
+          a
+        finally:
+          break
+    "#
+  }
+}
+
+#[test]
+fn throw_stmt() {
+  check_module! {
+    r#"#!hebi
+      throw Error("a")
+      throw a.b
+    "#
+  }
+
+  check_error! {
+    r#"#!hebi
+      throw
+    "#
+  }
+}
+
 #[test]
 fn loop_stmts() {
   check_module! {
@@ -1339,3 +1422,301 @@ check_error! {
       print y
   "#
 }
+
+check_module! {
+  fstring_expr,
+  r#"#!hebi
+    f""
+    f"plain\t{{text}}"
+    f"{a}"
+    f"a {b + 1} c {d.e(f"{g}")}"
+    f"{ {a: "}"}["a"] }"
+  "#
+}
+
+check_error! {
+  fstring_empty_expr,
+  r#"#!hebi
+    f"a {} b"
+  "#
+}
+
+check_error! {
+  fstring_single_closing_brace,
+  r#"#!hebi
+    f"a } b"
+  "#
+}
+
+check_error! {
+  fstring_invalid_expr,
+  r#"#!hebi
+    f"a {b c} d"
+  "#
+}
+
+check_module! {
+  tuple_literal,
+  r#"#!hebi
+    ()
+    (a)
+    (a,)
+    (a, b)
+    (
+      a,
+      b,
+    )
+  "#
+}
+
+check_error! {
+  tuple_missing_paren,
+  r#"#!hebi
+    (a, b
+  "#
+}
+
+check_module! {
+  destructure_stmt,
+  r#"#!hebi
+    a, b := pair
+    [first, second] := xs
+    (x,) := t
+    {name, age} := person
+    a, b = (b, a)
+    (a) = b
+    [a, b]
+    for k, v in entries:
+      print k, v
+    for {name} in people:
+      print name
+  "#
+}
+
+check_error! {
+  destructure_range,
+  r#"#!hebi
+    for a, b in 0..10:
+      pass
+  "#
+}
+
+check_error! {
+  destructure_range_comprehension,
+  r#"#!hebi
+    [a for a, b in 0..10]
+  "#
+}
+
+check_error! {
+  destructure_unparenthesized_tuple,
+  r#"#!hebi
+    a, b = b, a
+  "#
+}
+
+check_error! {
+  destructure_compound_assign,
+  r#"#!hebi
+    a, b += 1
+  "#
+}
+
+check_module! {
+  lambda,
+  r#"#!hebi
+    double := fn(x): x * 2
+    add := fn(a, b=1): a + b
+    f(fn(): none)
+    v := [fn(x): x, fn(y): y]
+    g := fn(x):
+      y := x * 2
+      return y
+    h := fn():
+      yield 1
+  "#
+}
+
+check_module! {
+  comprehension,
+  r#"#!hebi
+    a := [x * 2 for x in xs]
+    b := [x for x in xs if x > 0]
+    c := {k: v for k, v in pairs}
+    d := {[k]: v for (k, v) in pairs if v}
+    e := [a + b for [a, b] in pairs]
+    f := [i for i in 0..n]
+    g := {i: i for i in 0..=n if i}
+  "#
+}
+
+check_error! {
+  comprehension_missing_in,
+  r#"#!hebi
+    [x for x xs]
+  "#
+}
+
+check_module! {
+  match_stmt,
+  r#"#!hebi
+    match v:
+      case 0: print "zero"
+      case -1.5: pass
+      case 1..10:
+        print "small"
+      case 10..=20: pass
+      case "a": pass
+      case none: pass
+      case is Point: pass
+      case [x, _, (y, z)] if x > y:
+        print x, y, z
+      case {kind: "circle", radius}: pass
+      case {}: pass
+      case (a): pass
+      case (a,): pass
+      case _: pass
+  "#
+}
+
+check_error! {
+  match_duplicate_binding,
+  r#"#!hebi
+    match v:
+      case [a, {a}]: pass
+  "#
+}
+
+check_error! {
+  match_missing_case,
+  r#"#!hebi
+    match v:
+      print v
+  "#
+}
+
+check_error! {
+  match_invalid_literal,
+  r#"#!hebi
+    match v:
+      case -"a": pass
+  "#
+}
+
+check_error! {
+  match_invalid_range,
+  r#"#!hebi
+    match v:
+      case 0..1.5: pass
+  "#
+}
+
+check_module! {
+  variadic_params_and_spread,
+  r#"#!hebi
+    fn f(a, b = 1, *rest): pass
+    g := fn(*rest): rest
+    f(*xs)
+    f(0, *xs, *(1, 2),)
+  "#
+}
+
+check_error! {
+  rest_param_not_last,
+  r#"#!hebi
+    fn f(*rest, a): pass
+  "#
+}
+
+check_error! {
+  rest_param_duplicate,
+  r#"#!hebi
+    fn f(a, *a): pass
+  "#
+}
+
+check_module! {
+  named_args,
+  r#"#!hebi
+    f(1, b=2, c = 3)
+    f(*xs, width=1,)
+    f(a=b == 1)
+  "#
+}
+
+check_error! {
+  named_args_positional_after_named,
+  r#"#!hebi
+    f(a=1, 2)
+  "#
+}
+
+check_error! {
+  named_args_duplicate,
+  r#"#!hebi
+    f(a=1, a=2)
+  "#
+}
+
+check_module! {
+  enum_stmt,
+  r#"#!hebi
+    enum Color: Red, Green, Blue
+    enum Shape:
+      Circle(radius)
+      Rect(w, h)
+      Empty
+    match v:
+      case Shape.Circle(r): pass
+      case Shape.Rect(_, h) if h: pass
+      case m.Shape.Empty: pass
+  "#
+}
+
+check_error! {
+  enum_stmt_duplicate_variant,
+  r#"#!hebi
+    enum E: A, B, A
+  "#
+}
+
+check_error! {
+  enum_stmt_duplicate_field,
+  r#"#!hebi
+    enum E: A(x, x)
+  "#
+}
+
+check_error! {
+  enum_stmt_empty_payload,
+  r#"#!hebi
+    enum E: A()
+  "#
+}
+
+check_module! {
+  int_literals,
+  r#"#!hebi
+    1_000_000
+    2147483648
+    9_223_372_036_854_775_807
+  "#
+}
+
+check_error! {
+  int_literal_too_large,
+  r#"#!hebi
+    9_223_372_036_854_775_808
+  "#
+}
+
+check_module! {
+  bitwise_exprs,
+  r#"#!hebi
+    a | b ^ c & d << 1 + 2
+    a < b | c
+    ~a & -b
+    a // b * c
+    a >> b << c
+  "#
+}
@@ -7,7 +7,7 @@ use std::ops::Deref;
 use super::Value;
 use crate::internal::bytecode::opcode as op;
 use crate::internal::object::ptr::Ptr;
-use crate::internal::object::{ClassDescriptor, FunctionDescriptor, Str};
+use crate::internal::object::{ClassDescriptor, EnumDescriptor, FunctionDescriptor, Int, Str};
 
 #[derive(Debug, Clone)]
 pub enum Constant {
@@ -15,8 +15,10 @@ pub enum Constant {
   String(Ptr<Str>),
   Function(Ptr<FunctionDescriptor>),
   Class(Ptr<ClassDescriptor>),
+  Enum(Ptr<EnumDescriptor>),
   Offset(op::Offset),
   Float(NonNaNFloat),
+  Int(Ptr<Int>),
 }
 
 impl Constant {
@@ -28,8 +30,10 @@ impl Constant {
       Constant::String(v) => Value::object(v),
       Constant::Function(v) => Value::object(v),
       Constant::Class(v) => Value::object(v),
+      Constant::Enum(v) => Value::object(v),
       Constant::Offset(_) => panic!("cannot convert constant jump offset to value"),
       Constant::Float(v) => Value::float(v.value()),
+      Constant::Int(v) => Value::object(v),
     }
   }
 }
@@ -60,8 +64,10 @@ impl Display for Constant {
       Constant::String(v) => Display::fmt(v, f),
       Constant::Function(v) => Display::fmt(v, f),
       Constant::Class(v) => Display::fmt(v, f),
+      Constant::Enum(v) => Display::fmt(v, f),
       Constant::Offset(v) => Display::fmt(&v.0, f),
       Constant::Float(v) => Display::fmt(&v.0, f),
+      Constant::Int(v) => Display::fmt(v, f),
     }
   }
 }
@@ -140,6 +140,12 @@ impl Value {
     self.type_tag() == ty::OBJECT
   }
 
+  /// Address of the object stored in `self`, without taking a reference to it.
+  #[inline]
+  pub(crate) fn object_addr(&self) -> Option<usize> {
+    self.is_object().then(|| self.value() as usize)
+  }
+
   #[inline]
   pub fn bitwise_eq(&self, other: &Self) -> bool {
     self.bits == other.bits
@@ -64,6 +64,15 @@ impl Value {
   pub fn is_object(&self) -> bool {
     matches!(self, Self::Object(..))
   }
+
+  /// Address of the object stored in `self`, without taking a reference to it.
+  #[inline]
+  pub(crate) fn object_addr(&self) -> Option<usize> {
+    match self {
+      Self::Object(v) => Some(v.addr()),
+      _ => None,
+    }
+  }
 }
 
 impl Clone for Value {
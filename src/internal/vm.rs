@@ -7,6 +7,7 @@ pub mod thread;
 use std::fmt::Debug;
 use std::future::Future;
 use std::ptr::NonNull;
+use std::time::Duration;
 
 use global::Global;
 use module::Module;
@@ -15,7 +16,7 @@ use self::global::{Input, Output};
 use self::thread::{Stack, Thread};
 use super::error::{Error, Result};
 use super::object::function::Disassembly;
-use super::object::module::{ModuleId, ModuleLoader};
+use super::object::module::{ModuleId, ModuleLoader, MAIN_MODULE};
 use super::object::{builtin, module, Any, Function, List, Ptr, Str};
 use super::value::Value;
 use super::{codegen, syntax};
@@ -54,6 +55,12 @@ pub struct Config {
   pub module_loader: Option<Box<dyn ModuleLoader>>,
   pub input: Option<Box<dyn Input>>,
   pub output: Option<Box<dyn Output>>,
+  /// Maximum wall-clock time for a single entry into the VM.
+  pub timeout: Option<Duration>,
+  /// Initial fuel budget, or `None` for unlimited execution.
+  pub fuel: Option<u64>,
+  /// Maximum number of bytes which may be used by live objects.
+  pub memory_limit: Option<usize>,
 }
 
 impl Config {
@@ -74,6 +81,9 @@ impl Default for Config {
       module_loader: Some(Box::new(DefaultModuleLoader {})),
       input: Some(Box::new(std::io::stdin())),
       output: Some(Box::new(std::io::stdout())),
+      timeout: None,
+      fuel: None,
+      memory_limit: None,
     }
   }
 }
@@ -87,14 +97,21 @@ impl Default for Vm {
 impl Vm {
   pub fn with_config(config: Config) -> Self {
     let global = Global::new(config);
+    // builtins are part of every VM, so they are charged to the heap but may
+    // not fail because of the memory limit
+    let memory_limit = global.heap.limit();
+    global.heap.set_limit(None);
     builtin::register_builtin_functions(&global);
     let stack = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Stack::new()))) };
     let root = Thread::new(global.clone(), stack);
-    Self {
+    let mut vm = Self {
       global,
       root,
       stack,
-    }
+    };
+    builtin::register_builtin_classes(&mut vm);
+    vm.global.heap.set_limit(memory_limit);
+    vm
   }
 
   pub async fn eval(&mut self, code: &str) -> Result<Value> {
@@ -103,8 +120,16 @@ impl Vm {
   }
 
   pub fn compile(&self, code: &str) -> Result<Chunk> {
-    let ast = syntax::parse(self.global.clone(), code).map_err(Error::Syntax)?;
-    let module = codegen::emit(self.global.clone(), &ast, "__main__", true);
+    self.compile_named("<eval>", code)
+  }
+
+  /// Compile `code` in the global module, registering it as a source
+  /// called `name`.
+  pub(crate) fn compile_named(&self, name: &str, code: &str) -> Result<Chunk> {
+    let source = self.global.add_source(name, code);
+    let ast = syntax::parse(self.global.clone(), code)
+      .map_err(|e| Error::Syntax(e.with_source(source.clone())))?;
+    let module = codegen::emit(self.global.clone(), &ast, MAIN_MODULE, source.clone(), true);
     let module_id = ModuleId::global();
     let upvalues = self.global.alloc(List::new());
     let main = module.root.clone();
@@ -114,6 +139,8 @@ impl Vm {
   }
 
   pub async fn entry(&mut self, chunk: Chunk) -> Result<Value> {
+    self.global.clear_thrown();
+    self.global.start_deadline();
     self.root.entry(chunk.main).await
   }
 
@@ -122,6 +149,8 @@ impl Vm {
     callable: Ptr<Any>,
     args: &'a [Value],
   ) -> impl Future<Output = Result<Value>> + 'a {
+    self.global.clear_thrown();
+    self.global.start_deadline();
     self.root.call(callable, args)
   }
 
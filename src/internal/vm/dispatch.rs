@@ -17,6 +17,22 @@ pub fn dispatch<T: Handler>(
   pc: usize,
 ) -> Result<ControlFlow, T::Error> {
   let mut bytecode = bytecode;
+  // start of the instruction which is currently executing
+  let mut current = bytecode.as_ptr() as *mut u8;
+
+  match run(handler, &mut bytecode, pc, &mut current) {
+    Ok(flow) => Ok(flow),
+    Err(e) => Err(handler.on_error(get_pc!(current, bytecode), e)),
+  }
+}
+
+#[inline(always)]
+fn run<T: Handler>(
+  handler: &mut T,
+  bytecode: &mut NonNull<[u8]>,
+  pc: usize,
+  current: &mut *mut u8,
+) -> Result<ControlFlow, T::Error> {
   let mut pc = pc;
 
   'load_frame: loop {
@@ -29,6 +45,7 @@ pub fn dispatch<T: Handler>(
 
     loop {
       let start = ip;
+      *current = start;
       match read_opcode!(ip, end) {
         Opcode::Nop => {
           continue;
@@ -171,6 +188,11 @@ pub fn dispatch<T: Handler>(
           handler.op_make_data_class_derived(desc, parts)?;
           continue;
         }
+        Opcode::MakeEnum => {
+          let (desc,) = read_operands!(MakeEnum, ip, end, width);
+          handler.op_make_enum(desc)?;
+          continue;
+        }
         Opcode::MakeList => {
           let (start, count) = read_operands!(MakeList, ip, end, width);
           handler.op_make_list(start, count)?;
@@ -181,6 +203,16 @@ pub fn dispatch<T: Handler>(
           handler.op_make_list_empty()?;
           continue;
         }
+        Opcode::MakeTuple => {
+          let (start, count) = read_operands!(MakeTuple, ip, end, width);
+          handler.op_make_tuple(start, count)?;
+          continue;
+        }
+        Opcode::MakeTupleEmpty => {
+          let () = read_operands!(MakeTupleEmpty, ip, end, width);
+          handler.op_make_tuple_empty()?;
+          continue;
+        }
         Opcode::MakeTable => {
           let (start, count) = read_operands!(MakeTable, ip, end, width);
           handler.op_make_table(start, count)?;
@@ -191,6 +223,46 @@ pub fn dispatch<T: Handler>(
           handler.op_make_table_empty()?;
           continue;
         }
+        Opcode::Concat => {
+          let (start, count) = read_operands!(Concat, ip, end, width);
+          handler.op_concat(start, count)?;
+          continue;
+        }
+        Opcode::CheckLen => {
+          let (len,) = read_operands!(CheckLen, ip, end, width);
+          handler.op_check_len(len)?;
+          continue;
+        }
+        Opcode::Append => {
+          let (list,) = read_operands!(Append, ip, end, width);
+          handler.op_append(list)?;
+          continue;
+        }
+        Opcode::Extend => {
+          let (list,) = read_operands!(Extend, ip, end, width);
+          handler.op_extend(list)?;
+          continue;
+        }
+        Opcode::MatchSeq => {
+          let (len,) = read_operands!(MatchSeq, ip, end, width);
+          handler.op_match_seq(len)?;
+          continue;
+        }
+        Opcode::MatchPayload => {
+          let (len,) = read_operands!(MatchPayload, ip, end, width);
+          handler.op_match_payload(len)?;
+          continue;
+        }
+        Opcode::MatchTable => {
+          let () = read_operands!(MatchTable, ip, end, width);
+          handler.op_match_table()?;
+          continue;
+        }
+        Opcode::MatchKey => {
+          let (obj,) = read_operands!(MatchKey, ip, end, width);
+          handler.op_match_key(obj)?;
+          continue;
+        }
         Opcode::Jump => {
           let width_adjustment = if width.size() > 1 { 1 } else { 0 };
           #[allow(unused_assignments)] // ip is overwritten by start+offset
@@ -235,6 +307,25 @@ pub fn dispatch<T: Handler>(
           }
           continue;
         }
+        Opcode::PushHandler => {
+          let width_adjustment = if width.size() > 1 { 1 } else { 0 };
+          let pc = get_pc!(start, bytecode) - width_adjustment;
+          let (offset,) = read_operands!(PushHandler, ip, end, width);
+          handler.op_push_handler(pc, offset)?;
+          continue;
+        }
+        Opcode::PushHandlerConst => {
+          let width_adjustment = if width.size() > 1 { 1 } else { 0 };
+          let pc = get_pc!(start, bytecode) - width_adjustment;
+          let (idx,) = read_operands!(PushHandlerConst, ip, end, width);
+          handler.op_push_handler_const(pc, idx)?;
+          continue;
+        }
+        Opcode::PopHandler => {
+          let () = read_operands!(PopHandler, ip, end, width);
+          handler.op_pop_handler()?;
+          continue;
+        }
         Opcode::Add => {
           let (lhs,) = read_operands!(Add, ip, end, width);
           handler.op_add(lhs)?;
@@ -265,6 +356,36 @@ pub fn dispatch<T: Handler>(
           handler.op_pow(lhs)?;
           continue;
         }
+        Opcode::FloorDiv => {
+          let (lhs,) = read_operands!(FloorDiv, ip, end, width);
+          handler.op_floor_div(lhs)?;
+          continue;
+        }
+        Opcode::BitAnd => {
+          let (lhs,) = read_operands!(BitAnd, ip, end, width);
+          handler.op_bit_and(lhs)?;
+          continue;
+        }
+        Opcode::BitOr => {
+          let (lhs,) = read_operands!(BitOr, ip, end, width);
+          handler.op_bit_or(lhs)?;
+          continue;
+        }
+        Opcode::BitXor => {
+          let (lhs,) = read_operands!(BitXor, ip, end, width);
+          handler.op_bit_xor(lhs)?;
+          continue;
+        }
+        Opcode::Shl => {
+          let (lhs,) = read_operands!(Shl, ip, end, width);
+          handler.op_shl(lhs)?;
+          continue;
+        }
+        Opcode::Shr => {
+          let (lhs,) = read_operands!(Shr, ip, end, width);
+          handler.op_shr(lhs)?;
+          continue;
+        }
         Opcode::Inv => {
           let () = read_operands!(Inv, ip, end, width);
           handler.op_inv()?;
@@ -275,6 +396,11 @@ pub fn dispatch<T: Handler>(
           handler.op_not()?;
           continue;
         }
+        Opcode::BitNot => {
+          let () = read_operands!(BitNot, ip, end, width);
+          handler.op_bit_not()?;
+          continue;
+        }
         Opcode::CmpEq => {
           let (lhs,) = read_operands!(CmpEq, ip, end, width);
           handler.op_cmp_eq(lhs)?;
@@ -337,7 +463,7 @@ pub fn dispatch<T: Handler>(
           let return_addr = get_pc!(ip, bytecode);
           match handler.op_call(return_addr, callee, args)? {
             Call::LoadFrame(new_frame) => {
-              bytecode = new_frame.bytecode;
+              *bytecode = new_frame.bytecode;
               pc = new_frame.pc;
               continue 'load_frame;
             }
@@ -352,7 +478,37 @@ pub fn dispatch<T: Handler>(
           let return_addr = get_pc!(ip, bytecode);
           match handler.op_call0(return_addr)? {
             Call::LoadFrame(new_frame) => {
-              bytecode = new_frame.bytecode;
+              *bytecode = new_frame.bytecode;
+              pc = new_frame.pc;
+              continue 'load_frame;
+            }
+            Call::Continue => continue,
+            Call::Yield => return Ok(ControlFlow::Yield(get_pc!(ip, bytecode))),
+          }
+        }
+        Opcode::CallSpread => {
+          // frame is reloaded so neither `ip` nor `width` are read
+          #[allow(unused_assignments)]
+          let (callee,) = read_operands!(CallSpread, ip, end, width);
+          let return_addr = get_pc!(ip, bytecode);
+          match handler.op_call_spread(return_addr, callee)? {
+            Call::LoadFrame(new_frame) => {
+              *bytecode = new_frame.bytecode;
+              pc = new_frame.pc;
+              continue 'load_frame;
+            }
+            Call::Continue => continue,
+            Call::Yield => return Ok(ControlFlow::Yield(get_pc!(ip, bytecode))),
+          }
+        }
+        Opcode::CallNamed => {
+          // frame is reloaded so neither `ip` nor `width` are read
+          #[allow(unused_assignments)]
+          let (callee, named) = read_operands!(CallNamed, ip, end, width);
+          let return_addr = get_pc!(ip, bytecode);
+          match handler.op_call_named(return_addr, callee, named)? {
+            Call::LoadFrame(new_frame) => {
+              *bytecode = new_frame.bytecode;
               pc = new_frame.pc;
               continue 'load_frame;
             }
@@ -365,7 +521,7 @@ pub fn dispatch<T: Handler>(
           let return_addr = get_pc!(ip, bytecode);
           match handler.op_import(path, return_addr)? {
             Call::LoadFrame(new_frame) => {
-              bytecode = new_frame.bytecode;
+              *bytecode = new_frame.bytecode;
               pc = new_frame.pc;
               continue 'load_frame;
             }
@@ -383,7 +539,7 @@ pub fn dispatch<T: Handler>(
           let () = read_operands!(Return, ip, end, width);
           match handler.op_return()? {
             Return::LoadFrame(new_frame) => {
-              bytecode = new_frame.bytecode;
+              *bytecode = new_frame.bytecode;
               pc = new_frame.pc;
               continue 'load_frame;
             }
@@ -393,8 +549,20 @@ pub fn dispatch<T: Handler>(
         Opcode::Yield => {
           #[allow(unused_assignments)] // ip is overwritten by start+offset
           let () = read_operands!(Yield, ip, end, width);
-          handler.op_yield()?;
-          return Ok(ControlFlow::Yield(get_pc!(ip, bytecode)));
+          let resume_addr = get_pc!(ip, bytecode);
+          match handler.op_yield(resume_addr)? {
+            Return::LoadFrame(new_frame) => {
+              *bytecode = new_frame.bytecode;
+              pc = new_frame.pc;
+              continue 'load_frame;
+            }
+            Return::Yield => return Ok(ControlFlow::Return),
+          };
+        }
+        Opcode::Throw => {
+          let () = read_operands!(Throw, ip, end, width);
+          handler.op_throw()?;
+          continue;
         }
       }
     }
@@ -479,23 +647,44 @@ pub trait Handler {
     desc: op::Constant,
     parts: op::Register,
   ) -> Result<(), Self::Error>;
+  fn op_make_enum(&mut self, desc: op::Constant) -> Result<(), Self::Error>;
   fn op_make_list(&mut self, start: op::Register, count: op::Count) -> Result<(), Self::Error>;
   fn op_make_list_empty(&mut self) -> Result<(), Self::Error>;
+  fn op_make_tuple(&mut self, start: op::Register, count: op::Count) -> Result<(), Self::Error>;
+  fn op_make_tuple_empty(&mut self) -> Result<(), Self::Error>;
   fn op_make_table(&mut self, start: op::Register, count: op::Count) -> Result<(), Self::Error>;
   fn op_make_table_empty(&mut self) -> Result<(), Self::Error>;
+  fn op_concat(&mut self, start: op::Register, count: op::Count) -> Result<(), Self::Error>;
+  fn op_check_len(&mut self, len: op::Count) -> Result<(), Self::Error>;
+  fn op_append(&mut self, list: op::Register) -> Result<(), Self::Error>;
+  fn op_extend(&mut self, list: op::Register) -> Result<(), Self::Error>;
+  fn op_match_seq(&mut self, len: op::Count) -> Result<(), Self::Error>;
+  fn op_match_table(&mut self) -> Result<(), Self::Error>;
+  fn op_match_key(&mut self, obj: op::Register) -> Result<(), Self::Error>;
+  fn op_match_payload(&mut self, len: op::Count) -> Result<(), Self::Error>;
   fn op_jump(&mut self, offset: op::Offset) -> Result<op::Offset, Self::Error>;
   fn op_jump_const(&mut self, idx: op::Constant) -> Result<op::Offset, Self::Error>;
   fn op_jump_loop(&mut self, offset: op::Offset) -> Result<op::Offset, Self::Error>;
   fn op_jump_if_false(&mut self, offset: op::Offset) -> Result<Jump, Self::Error>;
   fn op_jump_if_false_const(&mut self, idx: op::Constant) -> Result<Jump, Self::Error>;
+  fn op_push_handler(&mut self, pc: usize, offset: op::Offset) -> Result<(), Self::Error>;
+  fn op_push_handler_const(&mut self, pc: usize, idx: op::Constant) -> Result<(), Self::Error>;
+  fn op_pop_handler(&mut self) -> Result<(), Self::Error>;
   fn op_add(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_sub(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_mul(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_div(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_rem(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_pow(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
+  fn op_floor_div(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
+  fn op_bit_and(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
+  fn op_bit_or(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
+  fn op_bit_xor(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
+  fn op_shl(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
+  fn op_shr(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_inv(&mut self) -> Result<(), Self::Error>;
   fn op_not(&mut self) -> Result<(), Self::Error>;
+  fn op_bit_not(&mut self) -> Result<(), Self::Error>;
   fn op_cmp_eq(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_cmp_ne(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
   fn op_cmp_gt(&mut self, lhs: op::Register) -> Result<(), Self::Error>;
@@ -514,8 +703,24 @@ pub trait Handler {
     args: op::Count,
   ) -> Result<Call, Self::Error>;
   fn op_call0(&mut self, return_addr: usize) -> Result<Call, Self::Error>;
+  fn op_call_spread(
+    &mut self,
+    return_addr: usize,
+    callee: op::Register,
+  ) -> Result<Call, Self::Error>;
+  fn op_call_named(
+    &mut self,
+    return_addr: usize,
+    callee: op::Register,
+    named: op::Register,
+  ) -> Result<Call, Self::Error>;
   fn op_import(&mut self, path: op::Constant, return_addr: usize) -> Result<Call, Self::Error>;
   fn op_finalize_module(&mut self) -> Result<(), Self::Error>;
   fn op_return(&mut self) -> Result<Return, Self::Error>;
-  fn op_yield(&mut self) -> Result<(), Self::Error>;
+  fn op_yield(&mut self, resume_addr: usize) -> Result<Return, Self::Error>;
+  fn op_throw(&mut self) -> Result<(), Self::Error>;
+
+  /// Called when an instruction fails with `error`. `pc` is the offset of
+  /// the failing instruction in the bytecode of the current frame.
+  fn on_error(&mut self, pc: usize, error: Self::Error) -> Self::Error;
 }
@@ -1,19 +1,28 @@
 use std::any::TypeId;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use indexmap::{IndexMap, IndexSet};
 
 use super::Config;
-use crate::internal::error::Result;
+use crate::internal::error::{Abort, Error, Result};
+use crate::internal::object::gc::GcStats;
 use crate::internal::object::module::{Module, ModuleId};
 use crate::internal::object::native::NativeClass;
-use crate::internal::object::{module, table, Ptr, Str, Table};
+use crate::internal::object::ptr::Heap;
+use crate::internal::object::{module, table, EnumType, Ptr, Str, Table};
 use crate::internal::value::Value;
+use crate::span::{Source, SourceId};
 use crate::Cow;
 
+/// Maximum depth of calls which run to completion on the native stack.
+const MAX_NESTED_CALLS: usize = 64;
+
 #[derive(Debug, Clone)]
 pub struct Global {
   inner: Rc<State>,
@@ -41,6 +50,7 @@ pub trait Input: std::io::Read + IoBase {}
 impl<T: std::io::Read + IoBase> Input for T {}
 
 pub struct State {
+  pub(crate) heap: Rc<Heap>,
   globals: Ptr<Table>,
   io: Io,
   module_registry: RefCell<module::Registry>,
@@ -48,11 +58,22 @@ pub struct State {
   module_visited_set: RefCell<IndexSet<ModuleId>>,
   string_table: RefCell<IndexMap<Cow<'static, str>, Ptr<Str>>>,
   type_map: RefCell<IndexMap<TypeId, Ptr<NativeClass>>>,
+  enum_map: RefCell<IndexMap<TypeId, Ptr<EnumType>>>,
+  thrown: RefCell<IndexMap<u64, Value>>,
+  next_thrown_id: Cell<u64>,
+  next_source_id: Cell<u32>,
+  interrupt: Arc<AtomicBool>,
+  timeout: Option<Duration>,
+  deadline: Cell<Option<Instant>>,
+  ticks: Cell<u32>,
+  fuel: Cell<Option<u64>>,
+  nested_calls: Cell<usize>,
 }
 
 impl Debug for State {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("State")
+      .field("heap", &self.heap)
       .field("globals", &self.globals)
       .field("io", &"<...>")
       .field("module_registry", &self.module_registry)
@@ -60,6 +81,14 @@ impl Debug for State {
       .field("module_visited_set", &self.module_visited_set)
       .field("string_table", &self.string_table)
       .field("type_map", &self.type_map)
+      .field("enum_map", &self.enum_map)
+      .field("thrown", &self.thrown)
+      .field("next_source_id", &self.next_source_id)
+      .field("interrupt", &self.interrupt)
+      .field("timeout", &self.timeout)
+      .field("deadline", &self.deadline)
+      .field("fuel", &self.fuel)
+      .field("nested_calls", &self.nested_calls)
       .finish()
   }
 }
@@ -92,6 +121,11 @@ impl Default for Global {
 
 impl Global {
   pub fn new(config: Config) -> Self {
+    let timeout = config.timeout;
+    let fuel = config.fuel;
+    let heap = Rc::new(Heap::new(config.memory_limit));
+    heap.charge(Ptr::<Table>::layout().size());
+    let globals = unsafe { Ptr::alloc_raw(heap.clone(), Table::with_capacity(0), 0) };
     let (module_loader, input, output) = config.resolve();
     let io = Io {
       input: RefCell::new(input),
@@ -100,13 +134,24 @@ impl Global {
 
     Self {
       inner: Rc::new(State {
-        globals: unsafe { Ptr::alloc_raw(Table::with_capacity(0)) },
+        heap,
+        globals,
         io,
         module_registry: RefCell::new(module::Registry::new()),
         module_loader,
         module_visited_set: RefCell::new(IndexSet::new()),
         string_table: RefCell::new(IndexMap::new()),
         type_map: RefCell::new(IndexMap::new()),
+        enum_map: RefCell::new(IndexMap::new()),
+        thrown: RefCell::new(IndexMap::new()),
+        next_thrown_id: Cell::new(0),
+        next_source_id: Cell::new(0),
+        interrupt: Arc::new(AtomicBool::new(false)),
+        timeout,
+        deadline: Cell::new(None),
+        ticks: Cell::new(0),
+        fuel: Cell::new(fuel),
+        nested_calls: Cell::new(0),
       }),
     }
   }
@@ -183,6 +228,162 @@ impl Global {
       .cloned()
   }
 
+  pub fn register_enum_raw(&self, type_id: TypeId, ty: Ptr<EnumType>) {
+    self.inner.enum_map.borrow_mut().insert(type_id, ty);
+  }
+
+  pub fn get_enum<T: 'static>(&self) -> Option<Ptr<EnumType>> {
+    self
+      .inner
+      .enum_map
+      .borrow()
+      .get(&TypeId::of::<T>())
+      .cloned()
+  }
+
+  /// Keep `value` alive until it is either caught, or the VM is entered again.
+  pub fn store_thrown(&self, value: Value) -> u64 {
+    let id = self.inner.next_thrown_id.get();
+    self.inner.next_thrown_id.set(id + 1);
+    self.inner.thrown.borrow_mut().insert(id, value);
+    id
+  }
+
+  pub fn get_thrown(&self, id: u64) -> Option<Value> {
+    self.inner.thrown.borrow().get(&id).cloned()
+  }
+
+  pub fn take_thrown(&self, id: u64) -> Option<Value> {
+    self.inner.thrown.borrow_mut().remove(&id)
+  }
+
+  pub fn clear_thrown(&self) {
+    self.inner.thrown.borrow_mut().clear();
+  }
+
+  /// Create a source string called `name` with a fresh id.
+  ///
+  /// The VM does not keep sources around: they are owned by the span tables
+  /// of the functions compiled from them, and by any errors which point into
+  /// them, so they are freed together with the code.
+  pub fn add_source(&self, name: impl ToString, text: impl ToString) -> Arc<Source> {
+    let id = self.inner.next_source_id.get();
+    self.inner.next_source_id.set(id.wrapping_add(1));
+    Arc::new(Source::new(SourceId(id), name, text))
+  }
+
+  /// The flag which is set to request that the running script is aborted.
+  pub fn interrupt_flag(&self) -> Arc<AtomicBool> {
+    self.inner.interrupt.clone()
+  }
+
+  /// Start counting down the configured timeout, if there is one.
+  pub fn start_deadline(&self) {
+    self
+      .inner
+      .deadline
+      .set(self.inner.timeout.map(|timeout| Instant::now() + timeout));
+    self.inner.ticks.set(0);
+  }
+
+  /// Account for one unit of work, aborting execution if the script ran out
+  /// of fuel, was interrupted, or has passed its deadline.
+  ///
+  /// Called on every backward jump and every call, which also makes it a
+  /// safe point to collect garbage cycles.
+  pub fn step(&self) -> Result<()> {
+    self.consume_fuel(1)?;
+    self.check_interrupt()?;
+    if self.inner.heap.should_collect() {
+      self.inner.heap.collect();
+    }
+    Ok(())
+  }
+
+  /// Record the start of a call which runs to completion on the native
+  /// stack, such as a magic method called by an operator.
+  ///
+  /// These calls can't be suspended, so their nesting depth is limited.
+  pub fn enter_nested_call(&self) -> Result<()> {
+    let depth = self.inner.nested_calls.get();
+    if depth >= MAX_NESTED_CALLS {
+      fail!("maximum depth of {MAX_NESTED_CALLS} nested calls exceeded");
+    }
+    self.inner.nested_calls.set(depth + 1);
+    Ok(())
+  }
+
+  pub fn leave_nested_call(&self) {
+    let depth = self.inner.nested_calls.get();
+    self.inner.nested_calls.set(depth.saturating_sub(1));
+  }
+
+  /// The amount of fuel left, or `None` if fuel is unlimited.
+  pub fn fuel(&self) -> Option<u64> {
+    self.inner.fuel.get()
+  }
+
+  pub fn set_fuel(&self, fuel: Option<u64>) {
+    self.inner.fuel.set(fuel);
+  }
+
+  /// Subtract `amount` from the remaining fuel.
+  ///
+  /// If there is not enough fuel left, it is drained completely and
+  /// execution is aborted.
+  pub fn consume_fuel(&self, amount: u64) -> Result<()> {
+    let Some(fuel) = self.inner.fuel.get() else {
+      return Ok(());
+    };
+    match fuel.checked_sub(amount) {
+      Some(fuel) => {
+        self.inner.fuel.set(Some(fuel));
+        Ok(())
+      }
+      None => {
+        self.inner.fuel.set(Some(0));
+        Err(Error::Aborted(Abort::OutOfFuel))
+      }
+    }
+  }
+
+  /// Abort execution if an interrupt was requested, or if the deadline
+  /// has passed.
+  ///
+  /// Reading the clock is amortized over `CLOCK_INTERVAL` checks.
+  pub fn check_interrupt(&self) -> Result<()> {
+    // must be a power of two
+    const CLOCK_INTERVAL: u32 = 1024;
+
+    if self.inner.interrupt.swap(false, Ordering::Relaxed) {
+      return Err(Error::Aborted(Abort::Interrupted));
+    }
+
+    if let Some(deadline) = self.inner.deadline.get() {
+      let ticks = self.inner.ticks.get().wrapping_add(1);
+      self.inner.ticks.set(ticks);
+      if ticks & (CLOCK_INTERVAL - 1) == 0 && Instant::now() >= deadline {
+        return Err(Error::Aborted(Abort::TimedOut));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The number of bytes used by live objects.
+  pub fn memory_usage(&self) -> usize {
+    self.inner.heap.used()
+  }
+
+  /// Free every object which is only kept alive by reference cycles.
+  pub fn collect_garbage(&self) -> GcStats {
+    self.inner.heap.collect()
+  }
+
+  pub fn gc_stats(&self) -> GcStats {
+    self.inner.heap.stats()
+  }
+
   pub fn io(&self) -> &Io {
     &self.inner.io
   }
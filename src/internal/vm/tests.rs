@@ -4,6 +4,7 @@ mod macros;
 use std::collections::HashMap;
 
 use super::*;
+use crate::internal::error::Abort;
 use crate::public::Scope;
 
 check! {
@@ -986,6 +987,22 @@ check! {
   "#
 }
 
+check! {
+  table_methods,
+  r#"#!hebi
+    t := {a: 1, b: 2}
+    keys := []
+    for k in t.keys():
+      keys.push(k)
+    values := []
+    for v in t.values():
+      values.push(v)
+    for k in t:
+      keys.push(k)
+    [t.len(), t.is_empty(), {}.is_empty(), keys, values, Table.len(t)]
+  "#
+}
+
 check! {
   table_access_keyed,
   r#"#!hebi
@@ -1881,6 +1898,186 @@ check! {
   "#
 }
 
+check! {
+  table_non_string_keys,
+  r#"#!hebi
+    t := {[1]: "int", [true]: "bool", [none]: "none", [1.5]: "float", [(1, "a")]: "tuple"}
+    t[5000000000] = "wide int"
+    t[-0.0] = "zero"
+    [t[1], t[true], t[none], t[1.5], t[(1, "a")], t[5000000000], t[0.0], ?t[1.0]]
+  "#
+}
+
+check! {
+  table_instance_keys,
+  r#"#!hebi
+    class T: pass
+    a := T()
+    b := T()
+    t := {}
+    t[a] = "a"
+    t[b] = "b"
+    [t[a], t[b], ?t[T()]]
+  "#
+}
+
+check! {
+  table_hashed_instance_keys,
+  r#"#!hebi
+    class Point:
+      x = 0
+      y = 0
+      init(self, x, y):
+        self.x = x
+        self.y = y
+      fn __hash__(self): return (self.x, self.y)
+    t := {}
+    t[Point(1, 2)] = "a"
+    t[Point(1, 2)] = "b"
+    [t[Point(1, 2)], ?t[(1, 2)]]
+  "#
+}
+
+check! {
+  bytes_methods,
+  r#"#!hebi
+    b := Bytes("héllo")
+    [b.len(), b[0], b[-1], b.get(10), b.slice(1, 3).to_hex(), b.slice(-2).to_str(), b.is_empty(), Bytes().is_empty()]
+  "#
+}
+
+check! {
+  bytes_construct_and_convert,
+  r#"#!hebi
+    b := Bytes([0, 1, 255]) + Bytes((104, 105))
+    [
+      b.to_hex(),
+      b.to_base64(),
+      Bytes.from_hex("0001ff6869") == b,
+      Bytes.from_base64("AAH/aGk=") == b,
+      Bytes("hi") == Bytes([104, 105]),
+      Bytes("a") < Bytes("b"),
+      {[Bytes("k")]: 1}[Bytes("k")],
+    ]
+  "#
+}
+
+check! {
+  bytes_iter,
+  r#"#!hebi
+    out := []
+    for v in Bytes([3, 1, 2]):
+      out.push(v)
+    out
+  "#
+}
+
+check! {
+  bytes__error_invalid_utf8,
+  r#"#!hebi
+    Bytes([255]).to_str()
+  "#
+}
+
+check! {
+  bytes__error_invalid_byte,
+  r#"#!hebi
+    Bytes([256])
+  "#
+}
+
+check! {
+  bytes__error_invalid_hex,
+  r#"#!hebi
+    Bytes.from_hex("0g")
+  "#
+}
+
+check! {
+  bytes_negative_index_out_of_bounds,
+  r#"#!hebi
+    b := Bytes([1, 2])
+    [b.get(-3), [1].get(-2), (1,).get(-2)]
+  "#
+}
+
+check! {
+  bytes__error_negative_index,
+  r#"#!hebi
+    Bytes([1, 2])[-3]
+  "#
+}
+
+check! {
+  bytes__error_negative_slice,
+  r#"#!hebi
+    Bytes([1, 2]).slice(-5)
+  "#
+}
+
+check! {
+  bytes__error_immutable,
+  r#"#!hebi
+    b := Bytes([1])
+    b[0] = 2
+  "#
+}
+
+check! {
+  set_methods,
+  r#"#!hebi
+    s := Set(1, "a", (1, 2), 1)
+    r := [s.len(), s.add(2), s.add(2), s.remove("a"), s.remove("a"), s.contains((1, 2))]
+    r.push(Set.contains(s, 3))
+    r.push(2 in s)
+    r.push("a" in s)
+    r.push(Set().is_empty())
+    r
+  "#
+}
+
+check! {
+  set_operators,
+  r#"#!hebi
+    a := Set(1, 2, 3)
+    b := Set(2, 3, 4)
+    [a | b, a & b, a - b, a ^ b, a == Set(3, 2, 1), a != b]
+  "#
+}
+
+check! {
+  set_iter,
+  r#"#!hebi
+    s := Set(3, 1, 2)
+    out := []
+    for v in s:
+      out.push(v)
+    out
+  "#
+}
+
+check! {
+  set__error_unhashable,
+  r#"#!hebi
+    Set([1])
+  "#
+}
+
+check! {
+  set__error_incompatible_operand,
+  r#"#!hebi
+    Set(1) | [1]
+  "#
+}
+
+check! {
+  table_key__error_unhashable,
+  r#"#!hebi
+    t := {}
+    t[[1, 2]] = 0
+  "#
+}
+
 // Reference test for forward and backward jumps <256 instructions
 check! {
   small_jump,
@@ -2168,3 +2365,2213 @@ check! {
       print i
   "#
 }
+
+check! {
+  try_catch,
+  r#"#!hebi
+    try:
+      parse_int("a")
+    catch e:
+      print "caught:", e.message
+    print "after"
+  "#
+}
+
+check! {
+  try_catch_no_error,
+  r#"#!hebi
+    try:
+      print "ok"
+    catch e:
+      print "unreachable"
+    print "after"
+  "#
+}
+
+check! {
+  try_catch_without_binding,
+  r#"#!hebi
+    t := {}
+    try:
+      print t["missing"]
+    catch:
+      print "missing key"
+  "#
+}
+
+check! {
+  try_catch_from_nested_call,
+  r#"#!hebi
+    fn inner():
+      return none + 1
+    fn outer():
+      v := inner()
+      print "unreachable"
+      return v
+    try:
+      outer()
+    catch e:
+      print e.message
+    print "after"
+  "#
+}
+
+check! {
+  try_finally_order,
+  r#"#!hebi
+    try:
+      print "try"
+    catch e:
+      print "catch"
+    finally:
+      print "finally"
+    try:
+      none + 1
+    catch e:
+      print "catch"
+    finally:
+      print "finally"
+  "#
+}
+
+check! {
+  try_finally_without_catch,
+  r#"#!hebi
+    try:
+      print "try"
+      none + 1
+    finally:
+      print "finally"
+    print "unreachable"
+  "#
+}
+
+check! {
+  try_catch_rethrow_runs_finally,
+  r#"#!hebi
+    try:
+      try:
+        parse_int("a")
+      catch e:
+        print "inner catch"
+        parse_int("b")
+      finally:
+        print "inner finally"
+    catch e:
+      print "outer catch:", e.message
+  "#
+}
+
+check! {
+  try_finally_return,
+  r#"#!hebi
+    fn test():
+      try:
+        return "try"
+      finally:
+        print "finally"
+      return "unreachable"
+    print test()
+  "#
+}
+
+check! {
+  try_finally_break_continue,
+  r#"#!hebi
+    for i in 0..5:
+      try:
+        if i == 1:
+          continue
+        if i == 3:
+          break
+        print i
+      finally:
+        print "finally", i
+    print "done"
+  "#
+}
+
+check! {
+  try_catch_in_loop,
+  r#"#!hebi
+    values := ["1", "a", "3"]
+    for v in values:
+      try:
+        print parse_int(v)
+      catch e:
+        print "invalid:", v
+  "#
+}
+
+check! {
+  try_catch_preserves_locals,
+  r#"#!hebi
+    fn test():
+      a := 1
+      b := 2
+      try:
+        c := a + b
+        none + c
+      catch e:
+        return a + b
+    print test()
+  "#
+}
+
+check! {
+  throw_and_catch_error,
+  r#"#!hebi
+    try:
+      throw Error("boom")
+    catch e:
+      print e.message
+  "#
+}
+
+check! {
+  throw_and_catch_custom_error,
+  r#"#!hebi
+    class MyError(Error):
+      code = 0
+      init(self, message, code):
+        super(message)
+        self.code = code
+
+    fn test():
+      throw MyError("failed", 404)
+
+    try:
+      test()
+    catch e:
+      print e.message, e.code
+  "#
+}
+
+check! {
+  throw_uncaught,
+  r#"#!hebi
+    throw Error("x")
+  "#
+}
+
+check! {
+  throw_non_error_value,
+  r#"#!hebi
+    try:
+      throw "a string"
+    catch e:
+      print e
+    throw 10
+  "#
+}
+
+check! {
+  throw_runs_finally,
+  r#"#!hebi
+    try:
+      try:
+        throw Error("inner")
+      finally:
+        print "finally"
+    catch e:
+      print "caught:", e.message
+  "#
+}
+
+check! {
+  throw_rethrow_runtime_error,
+  r#"#!hebi
+    try:
+      try:
+        none + 1
+      catch e:
+        throw e
+    catch e:
+      print e.message
+  "#
+}
+
+#[test]
+fn uncaught_throw_is_inspectable() {
+  let mut hebi = crate::public::Hebi::new();
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        class HttpError(Error):
+          status = 0
+          init(self, status):
+            super("request failed")
+            self.status = status
+        throw HttpError(500)
+      "#
+    ))
+    .unwrap_err();
+
+  let Error::Thrown(thrown) = error.inner() else {
+    panic!("expected thrown error, got {error:?}");
+  };
+  assert_eq!(thrown.message(), "request failed");
+
+  let global = hebi.global();
+  assert!(thrown.value(&global).is_some());
+  let status = thrown.field(&global, "status").unwrap();
+  assert_eq!(status.as_int(), Some(500));
+}
+
+check! {
+  backtrace_nested_calls,
+  r#"#!hebi
+    fn inner(v):
+      return v + 1
+
+    fn outer():
+      print "before"
+      return inner(none)
+
+    outer()
+  "#
+}
+
+check! {
+  backtrace_method_call,
+  r#"#!hebi
+    class T:
+      fn test(self, v):
+        return self.missing
+
+    t := T()
+    t.test(0)
+  "#
+}
+
+check! {
+  module
+  backtrace_across_modules,
+  {
+    test: r#"#!hebi
+      fn fail():
+        return none + 1
+    "#
+  },
+  r#"#!hebi
+    from test import fail
+    fn call():
+      fail()
+    call()
+  "#
+}
+
+#[test]
+fn backtrace_frames() {
+  let mut hebi = crate::public::Hebi::new();
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        fn inner():
+          throw Error("failed")
+        fn outer():
+          inner()
+        outer()
+      "#
+    ))
+    .unwrap_err();
+
+  let backtrace = error.backtrace().unwrap();
+  let frames = backtrace
+    .frames()
+    .iter()
+    .map(|frame| (frame.module(), frame.function()))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    frames,
+    [
+      ("__main__", "__main__"),
+      ("__main__", "outer"),
+      ("__main__", "inner")
+    ]
+  );
+  assert!(backtrace
+    .frames()
+    .iter()
+    .all(|frame| frame.span().is_some()));
+  assert!(backtrace
+    .frames()
+    .iter()
+    .all(|frame| frame.source().map(|source| source.name()) == Some("<eval>")));
+}
+
+#[test]
+fn syntax_error_source() {
+  let mut hebi = crate::public::Hebi::new();
+  hebi.eval("v := 0").unwrap();
+  let error = hebi.eval("fn invalid:\n  pass").unwrap_err();
+
+  let Error::Syntax(e) = &error else {
+    panic!("expected syntax error, got {error:?}");
+  };
+  assert_eq!(e.source().unwrap().text(), "fn invalid:\n  pass");
+  assert_eq!(
+    error.report(false),
+    "syntax error in `<eval>`:\nexpected `(`\n| fn invalid:\n\n"
+  );
+}
+
+#[test]
+fn interrupt_infinite_loop() {
+  let mut hebi = crate::public::Hebi::new();
+  let handle = hebi.interrupt_handle();
+  let interrupter = std::thread::spawn(move || {
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    handle.interrupt();
+  });
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        loop:
+          pass
+      "#
+    ))
+    .unwrap_err();
+  interrupter.join().unwrap();
+
+  assert!(
+    matches!(error.inner(), Error::Aborted(Abort::Interrupted)),
+    "expected interrupt, got {error:?}"
+  );
+  // the interrupt is only delivered once
+  assert_eq!(hebi.eval("1 + 1").unwrap().as_int(), Some(2));
+}
+
+#[test]
+fn timeout_in_function() {
+  let mut hebi = crate::public::Hebi::builder()
+    .timeout(std::time::Duration::from_millis(50))
+    .finish();
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        fn spin(n):
+          while true:
+            n += 1
+        spin(0)
+      "#
+    ))
+    .unwrap_err();
+
+  assert!(
+    matches!(error.inner(), Error::Aborted(Abort::TimedOut)),
+    "expected timeout, got {error:?}"
+  );
+  assert!(error.backtrace().is_some());
+  // each entry gets a fresh deadline
+  assert_eq!(hebi.eval("1 + 1").unwrap().as_int(), Some(2));
+}
+
+#[test]
+fn abort_is_not_catchable() {
+  let mut hebi = crate::public::Hebi::builder()
+    .timeout(std::time::Duration::from_millis(50))
+    .finish();
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        caught := false
+        try:
+          loop:
+            pass
+        catch e:
+          caught = true
+        finally:
+          caught = true
+        caught
+      "#
+    ))
+    .unwrap_err();
+
+  assert!(
+    matches!(error.inner(), Error::Aborted(Abort::TimedOut)),
+    "expected timeout, got {error:?}"
+  );
+  assert_eq!(hebi.global().get("caught").unwrap().as_bool(), Some(false));
+}
+
+#[test]
+fn fuel_runs_out() {
+  let mut hebi = crate::public::Hebi::builder().fuel(1000).finish();
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        loop:
+          pass
+      "#
+    ))
+    .unwrap_err();
+
+  assert!(
+    matches!(error.inner(), Error::Aborted(Abort::OutOfFuel)),
+    "expected out of fuel, got {error:?}"
+  );
+  assert_eq!(hebi.fuel(), Some(0));
+
+  hebi.set_fuel(Some(1000));
+  assert_eq!(hebi.eval("1 + 1").unwrap().as_int(), Some(2));
+}
+
+#[test]
+fn fuel_is_deterministic() {
+  let source = indoc::indoc!(
+    r#"#!hebi
+      fn add(a, b):
+        return a + b
+      v := 0
+      for i in 0..100:
+        v = add(v, i)
+      v
+    "#
+  );
+
+  let mut hebi = crate::public::Hebi::builder().fuel(10_000).finish();
+  hebi.eval(source).unwrap();
+  let first = 10_000 - hebi.fuel().unwrap();
+  hebi.set_fuel(Some(10_000));
+  hebi.eval(source).unwrap();
+  let second = 10_000 - hebi.fuel().unwrap();
+
+  assert!(
+    first >= 200,
+    "expected at least one unit per iteration and call"
+  );
+  assert_eq!(first, second);
+}
+
+#[test]
+fn native_fn_consume_fuel() {
+  fn expensive(scope: Scope<'_>) -> Result<()> {
+    scope.consume_fuel(100)
+  }
+
+  let mut hebi = crate::public::Hebi::builder().fuel(250).finish();
+  hebi.register(
+    &NativeModule::builder("test")
+      .function("expensive", expensive)
+      .finish(),
+  );
+
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from test import expensive
+        expensive()
+        expensive()
+        expensive()
+      "#
+    ))
+    .unwrap_err();
+  assert!(
+    matches!(error.inner(), Error::Aborted(Abort::OutOfFuel)),
+    "expected out of fuel, got {error:?}"
+  );
+}
+
+#[test]
+fn memory_limit_is_catchable() {
+  let mut hebi = crate::public::Hebi::builder()
+    .memory_limit(1024 * 1024)
+    .finish();
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        x := []
+        try:
+          loop:
+            x.push(x)
+        catch e:
+          e.message
+      "#
+    ))
+    .unwrap();
+
+  assert!(
+    value.to_string().starts_with("out of memory"),
+    "unexpected value {value}"
+  );
+  assert!(hebi.memory_usage() <= 1024 * 1024);
+}
+
+#[test]
+fn memory_limit_applies_to_shl() {
+  let mut hebi = crate::public::Hebi::builder()
+    .memory_limit(1024 * 1024)
+    .finish();
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        try:
+          1 << 20000000
+        catch e:
+          e.message
+      "#
+    ))
+    .unwrap();
+
+  assert!(
+    value.to_string().starts_with("out of memory"),
+    "unexpected value {value}"
+  );
+}
+
+#[test]
+fn memory_limit_applies_to_bigint_arithmetic() {
+  let mut hebi = crate::public::Hebi::builder()
+    .memory_limit(1024 * 1024)
+    .finish();
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        x := 3
+        try:
+          for i in 0..26:
+            x = x * x
+        catch e:
+          e.message
+      "#
+    ))
+    .unwrap();
+
+  assert!(
+    value.to_string().starts_with("out of memory"),
+    "unexpected value {value}"
+  );
+  assert!(hebi.memory_usage() <= 1024 * 1024);
+}
+
+#[test]
+fn tiny_memory_limit() {
+  let mut hebi = crate::public::Hebi::builder().memory_limit(4000).finish();
+  assert!(hebi.memory_usage() > 4000);
+  let error = hebi.eval("[1, 2, 3]").unwrap_err();
+  assert!(
+    error.to_string().contains("out of memory"),
+    "unexpected error {error}"
+  );
+}
+
+#[test]
+fn memory_is_released() {
+  let mut hebi = crate::public::Hebi::builder()
+    .memory_limit(1024 * 1024)
+    .finish();
+  let before = hebi.memory_usage();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        for i in 0..100000:
+          v := [i, i, i]
+      "#
+    ))
+    .unwrap();
+  // only the compiled chunk and its source should remain
+  assert!(hebi.memory_usage() - before < 4096);
+}
+
+#[test]
+fn memory_is_released_on_removal() {
+  let mut hebi = crate::public::Hebi::builder()
+    .memory_limit(200_000)
+    .finish();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        x := []
+        s := Set()
+        for i in 0..100000:
+          x.push(i)
+          x.pop()
+          s.add(i)
+          s.remove(i)
+      "#
+    ))
+    .unwrap();
+}
+
+#[test]
+fn collect_cycles() {
+  let mut hebi = crate::public::Hebi::new();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        class Node:
+          next = none
+
+        fn make_cycles():
+          list := []
+          list.push(list)
+
+          node := Node()
+          node.next = node
+
+          fn recurse(n):
+            if n > 0:
+              return recurse(n - 1)
+            return n
+          recurse(1)
+
+        make_cycles()
+      "#
+    ))
+    .unwrap();
+
+  let before = hebi.memory_usage();
+  let stats = hebi.collect_garbage();
+  assert!(stats.objects >= 3, "{stats:?}");
+  assert!(stats.bytes > 0, "{stats:?}");
+  assert_eq!(hebi.memory_usage(), before - stats.bytes);
+  assert_eq!(hebi.gc_stats().collections, 1);
+
+  // nothing left to collect
+  assert_eq!(hebi.collect_garbage().objects, 0);
+}
+
+#[test]
+fn collect_keeps_reachable_cycles() {
+  let mut hebi = crate::public::Hebi::new();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        fn make_cycle():
+          a := []
+          b := [a]
+          a.push(b)
+          return a
+
+        v := make_cycle()
+      "#
+    ))
+    .unwrap();
+
+  assert_eq!(hebi.collect_garbage().objects, 0);
+  let value = hebi.eval("v[0][0][0][0].len()").unwrap();
+  assert_eq!(value.as_int(), Some(1));
+
+  hebi.eval("v = none").unwrap();
+  assert_eq!(hebi.collect_garbage().objects, 2);
+}
+
+#[test]
+fn collect_cycles_through_native_instances() {
+  use crate::public::module::NativeModule;
+
+  struct Handle;
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("handle")
+      .class::<Handle>("Handle", |class| class.init(|_| Ok(Handle)).finish())
+      .finish(),
+  );
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from handle import Handle
+
+        fn make_cycle():
+          a := [Handle()]
+          a.push(a)
+
+        make_cycle()
+      "#
+    ))
+    .unwrap();
+
+  // the list, and the instance it holds
+  assert_eq!(hebi.collect_garbage().objects, 2);
+}
+
+#[test]
+fn collect_under_memory_pressure() {
+  let mut hebi = crate::public::Hebi::builder()
+    .memory_limit(4 * 1024 * 1024)
+    .finish();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        fn cycle():
+          v := []
+          v.push(v)
+
+        for i in 0..100000:
+          cycle()
+      "#
+    ))
+    .unwrap();
+
+  assert!(hebi.gc_stats().collections > 0);
+  assert!(hebi.gc_stats().objects >= 10000);
+}
+
+check! {
+  generator_for_loop,
+  r#"#!hebi
+    fn count(n):
+      i := 0
+      while i < n:
+        yield i
+        i += 1
+
+    v := []
+    for x in count(3):
+      v.push(x)
+    for x in count(0):
+      v.push(x)
+    v
+  "#
+}
+
+check! {
+  generator_collect,
+  r#"#!hebi
+    fn gen(a, b):
+      yield a
+      yield b
+      yield a + b
+      return 100
+
+    g := gen(1, 2)
+    [collect(g), collect(g), g.done(), g.next()]
+  "#
+}
+
+check! {
+  generator_done_then_next,
+  r#"#!hebi
+    fn gen():
+      yield none
+      yield 1
+
+    g := gen()
+    [g.done(), g.done(), g.next(), g.next(), g.done(), g.next()]
+  "#
+}
+
+check! {
+  generator_closure,
+  r#"#!hebi
+    fn outer():
+      n := 10
+      fn gen():
+        for i in 0..3:
+          yield n + i
+      return gen
+
+    collect(outer()())
+  "#
+}
+
+check! {
+  generator_method,
+  r#"#!hebi
+    class Range:
+      start = 0
+      end = 0
+      init(self, start, end):
+        self.start = start
+        self.end = end
+      fn values(self):
+        i := self.start
+        while i < self.end:
+          yield i
+          i += 1
+
+    collect(Range(2, 5).values())
+  "#
+}
+
+check! {
+  generator_try_across_yield,
+  r#"#!hebi
+    fn gen():
+      try:
+        yield 1
+        throw "oops"
+      catch e:
+        yield e
+
+    collect(gen())
+  "#
+}
+
+check! {
+  generator_error_ends_generator,
+  r#"#!hebi
+    fn gen():
+      yield 1
+      throw "oops"
+
+    g := gen()
+    v := []
+    try:
+      for x in g:
+        v.push(x)
+    catch e:
+      v.push(e)
+    v.push(g.done())
+    v
+  "#
+}
+
+check! {
+  generator_already_running,
+  r#"#!hebi
+    fn gen():
+      yield g.next()
+
+    g := gen()
+    g.next()
+  "#
+}
+
+#[test]
+fn generator_from_rust() {
+  let mut hebi = crate::public::Hebi::new();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        fn gen():
+          yield 1
+          yield 2
+          yield 3
+
+        g := gen()
+      "#
+    ))
+    .unwrap();
+
+  let global = hebi.global();
+  let g = global
+    .get("g")
+    .unwrap()
+    .as_object::<crate::public::Generator>(global.clone())
+    .unwrap();
+  assert_eq!(g.resume(global.clone()).unwrap().unwrap().as_int(), Some(1));
+  let rest = g
+    .iter(global.clone())
+    .map(|v| v.unwrap().as_int().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(rest, [2, 3]);
+  assert!(g.is_done());
+  assert!(g.resume(global).unwrap().is_none());
+}
+
+check! {
+  fstring_interpolation,
+  r#"#!hebi
+    name := "world"
+    count := 2
+    fn items():
+      return [1, "two", none]
+    [
+      f"",
+      f"hello",
+      f"hello {name}, you have {count + 1} items",
+      f"{items()} {{escaped}} {{{count}}}",
+      f"\t{ {a: "}"}["a"] }\n",
+      f"{f"{name}"}",
+    ]
+  "#
+}
+
+check! {
+  tuple_literal,
+  r#"#!hebi
+    t := (1, "two", (3,))
+    [
+      (),
+      t,
+      t[0],
+      t[-1][0],
+      t.len(),
+      (1, 2) == (1, 2),
+      (1, 2) == (1, 3),
+      (1, 2) == (1, 2, 3),
+      ((1, "a"),) == ((1, "a"),),
+      collect(t),
+    ]
+  "#
+}
+
+check! {
+  tuple_is_immutable,
+  r#"#!hebi
+    t := (1, 2)
+    t[0] = 3
+  "#
+}
+
+check! {
+  tuple_index_out_of_bounds,
+  r#"#!hebi
+    (1, 2)[2]
+  "#
+}
+
+#[test]
+fn tuple_from_rust() {
+  use crate::public::{FromValue, IntoValue};
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        pair := (1, "a")
+        triple := (1, 2, 3)
+      "#
+    ))
+    .unwrap();
+
+  let global = hebi.global();
+  let pair = global.get("pair").unwrap();
+  let (a, b) = <(i32, String)>::from_value(pair, global.clone()).unwrap();
+  assert_eq!((a, b.as_str()), (1, "a"));
+
+  let triple = global.get("triple").unwrap();
+  let e = <(i32, i32)>::from_value(triple, global.clone()).unwrap_err();
+  assert_eq!(e.to_string(), "expected a tuple of length 2, got 3");
+
+  let value = (true, 2.5).into_value(global.clone()).unwrap();
+  let tuple = value
+    .as_object::<crate::public::Tuple>(global.clone())
+    .unwrap();
+  assert_eq!(tuple.len(), 2);
+  assert_eq!(tuple.get(0).unwrap().as_bool(), Some(true));
+  assert_eq!(tuple.get(1).unwrap().as_float(), Some(2.5));
+}
+
+check! {
+  destructure_assign,
+  r#"#!hebi
+    a, b := (1, 2)
+    [c, d] := ["c", "d"]
+    {name, age} := {name: "Ana", age: 30}
+    a, b = (b, a)
+    fn f(pair):
+      x, y := pair
+      return x + y
+    [a, b, c, d, name, age, f([3, 4])]
+  "#
+}
+
+check! {
+  destructure_for_loop,
+  r##"#!hebi
+    out := []
+    for k, v in [("a", 1), ("b", 2)]:
+      out.push(f"{k}={v}")
+    for {name} in [{name: "x"}, {name: "y"}]:
+      out.push(name)
+    fn g():
+      total := 0
+      for a, b in [[1, 2], [3, 4]]:
+        total += a * b
+      return total
+    out.push(g())
+    out
+  "##
+}
+
+check! {
+  destructure_table_entries,
+  r##"#!hebi
+    table := {a: 1, b: 2, [3]: "c"}
+    out := []
+    for k, v in table.entries():
+      out.push(f"{k}={v}")
+    out
+  "##
+}
+
+check! {
+  destructure_too_few_values,
+  r#"#!hebi
+    a, b, c := (1, 2)
+  "#
+}
+
+check! {
+  destructure_too_many_values,
+  r#"#!hebi
+    a, b := [1, 2, 3]
+  "#
+}
+
+check! {
+  destructure_not_a_sequence,
+  r#"#!hebi
+    a, b := 10
+  "#
+}
+
+check! {
+  destructure_missing_key,
+  r#"#!hebi
+    {name, age} := {name: "Ana"}
+  "#
+}
+
+check! {
+  lambda,
+  r##"#!hebi
+    double := fn(x): x * 2
+    fn apply(f, v):
+      return f(v)
+    fn make_counter():
+      count := 0
+      return fn():
+        count += 1
+        return count
+    counter := make_counter()
+    counter()
+    fn sum(xs):
+      total := 0
+      for x in xs:
+        total += x
+      return total
+    [double(21), apply(fn(x): x + 1, 1), counter(), sum([1, 2, 3])]
+  "##
+}
+
+check! {
+  lambda_generator,
+  r##"#!hebi
+    gen := fn():
+      yield 1
+      yield 2
+    out := []
+    for v in gen():
+      out.push(v)
+    out
+  "##
+}
+
+check! {
+  list_comprehension,
+  r##"#!hebi
+    xs := [-1, 2, -3, 4]
+    fn scale(xs, n):
+      return [x * n for x in xs]
+    [[x * 2 for x in xs if x > 0], scale(xs, 10), [a + b for a, b in [(1, 2), (3, 4)]]]
+  "##
+}
+
+check! {
+  table_comprehension,
+  r##"#!hebi
+    t := {k: v * v for k, v in [("a", 1), ("b", 2), ("c", 3)] if v != 2}
+    [t["a"], t["c"]]
+  "##
+}
+
+check! {
+  range_comprehension,
+  r##"#!hebi
+    n := 3
+    [[i for i in 0..5], [i * i for i in 1..=n if i != 2], {i: i * 2 for i in 0..n}[2]]
+  "##
+}
+
+check! {
+  comprehension_variable_scope,
+  r##"#!hebi
+    x := "outer"
+    ys := [x for x in [1, 2]]
+    [x, ys]
+  "##
+}
+
+check! {
+  match_stmt,
+  r##"#!hebi
+    class Point:
+      x = 0
+      y = 0
+    fn describe(v):
+      match v:
+        case 0: return "zero"
+        case -1: return "minus one"
+        case 1..10: return "small"
+        case 10..=20: return "medium"
+        case "hello": return "greeting"
+        case none: return "nothing"
+        case is Point: return f"point at {v.x}"
+        case [x, y] if x == y: return f"pair of {x}"
+        case [x, [y, _]]: return f"nested {x} {y}"
+        case (x, y): return f"pair {x} {y}"
+        case {kind: "circle", radius}: return f"circle {radius}"
+        case {name}: return f"named {name}"
+        case _: return "other"
+    [
+      describe(0),
+      describe(-1),
+      describe(5),
+      describe(20),
+      describe("hello"),
+      describe(none),
+      describe(Point()),
+      describe([2, 2]),
+      describe([1, [2, 3]]),
+      describe((1, 2)),
+      describe({kind: "circle", radius: 4}),
+      describe({name: "x", age: 1}),
+      describe({kind: "square"}),
+      describe([1, 2, 3]),
+      describe(1.5),
+    ]
+  "##
+}
+
+check! {
+  match_no_arm_matches,
+  r##"#!hebi
+    out := "unchanged"
+    match 100:
+      case 0: out = "zero"
+      case [a]: out = "list"
+    out
+  "##
+}
+
+check! {
+  match_bindings_are_scoped,
+  r##"#!hebi
+    x := "outer"
+    match [1, 2]:
+      case [x, y]: print x, y
+    x
+  "##
+}
+
+check! {
+  instance_of_class,
+  r##"#!hebi
+    class A: pass
+    class B(A): pass
+    class C: pass
+    b := B()
+    [b is B, b is A, b is C, A() is B, 1 is A]
+  "##
+}
+
+check! {
+  match_type_of_functions_and_classes,
+  r##"#!hebi
+    class A: pass
+    fn f(): pass
+    fn describe(v):
+      match v:
+        case is A:
+          return "A"
+        case is f:
+          return "function"
+        case _:
+          return "other"
+    [describe(A()), describe(A), describe(f), describe(fn(): 1), describe(to_str), describe(1)]
+  "##
+}
+
+check! {
+  variadic_params,
+  r##"#!hebi
+    fn f(a, b = 2, *rest):
+      return [a, b, rest]
+    class T:
+      fn m(self, *rest):
+        return rest
+    g := fn(*rest): rest
+    fn gen(*rest):
+      for v in rest:
+        yield v
+    [f(1), f(1, 3), f(1, 3, 4, 5), T().m(), T().m(1, 2), g(1), [v for v in gen(1, 2)]]
+  "##
+}
+
+check! {
+  variadic_params__error_not_enough_args,
+  r##"#!hebi
+    fn f(a, *rest):
+      return rest
+    f()
+  "##
+}
+
+check! {
+  spread_args,
+  r##"#!hebi
+    fn f(a, b, c):
+      return [a, b, c]
+    fn g(*rest):
+      return rest
+    xs := [1, 2]
+    l := []
+    l.push(*[3])
+    [f(*[1, 2, 3]), f(0, *xs), f(*(4, 5), 6), g(*xs, *xs), g(*[]), l]
+  "##
+}
+
+check! {
+  spread_args__error_too_many_args,
+  r##"#!hebi
+    fn f(a):
+      return a
+    f(*[1, 2])
+  "##
+}
+
+check! {
+  spread_args__error_not_a_list,
+  r##"#!hebi
+    fn f(a):
+      return a
+    f(*1)
+  "##
+}
+
+check! {
+  spread_args_in_loop,
+  r##"#!hebi
+    fn add(a, b):
+      return a + b
+    fn run():
+      v := 0
+      for i in 0..100:
+        v = add(*[v, i])
+      return v
+    run()
+  "##
+}
+
+#[test]
+fn native_fn_rest_params() {
+  fn sum(scope: Scope<'_>) -> Result<i32> {
+    let first = scope.param::<i32>(0)?;
+    let rest = scope.rest_params::<i32>(1)?;
+    Ok(first + rest.into_iter().sum::<i32>())
+  }
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(&NativeModule::builder("test").function("sum", sum).finish());
+
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from test import sum
+        xs := [2, 3]
+        f"{sum(1)} {sum(1, *xs)} {sum(*xs, *xs)}"
+      "#
+    ))
+    .unwrap();
+  assert_eq!(value.to_string(), "1 6 10");
+}
+
+check! {
+  named_args,
+  r##"#!hebi
+    fn draw(x, y, color = "red", width = 1):
+      return [x, y, color, width]
+    class Point:
+      x = 0
+      y = 0
+      init(self, x = 0, y = 0):
+        self.x = x
+        self.y = y
+      fn moved(self, dx = 0, dy = 0):
+        return [self.x + dx, self.y + dy]
+    p := Point(y=2)
+    [
+      draw(1, 2, width=3),
+      draw(1, y=2),
+      draw(x=1, y=2, color="blue"),
+      draw(*[1, 2], width=4),
+      [p.x, p.y],
+      p.moved(dy=1),
+      (fn(a, b = 1): [a, b])(b=2, a=1),
+    ]
+  "##
+}
+
+check! {
+  named_args__error_unknown_param,
+  r##"#!hebi
+    fn f(a):
+      return a
+    f(1, b=2)
+  "##
+}
+
+check! {
+  named_args__error_passed_twice,
+  r##"#!hebi
+    fn f(a, b = 1):
+      return a
+    f(1, a=2)
+  "##
+}
+
+check! {
+  named_args__error_missing_required,
+  r##"#!hebi
+    fn f(a, b, c = 1):
+      return a
+    f(1, c=2)
+  "##
+}
+
+#[test]
+fn native_fn_named_params() {
+  fn greet(scope: Scope<'_>) -> Result<String> {
+    let name = scope.param::<String>(0)?;
+    let greeting = scope.named_param::<String>("greeting")?;
+    let greeting = greeting.as_deref().unwrap_or("hello");
+    Ok(format!("{greeting}, {name}"))
+  }
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("test")
+      .function("greet", greet)
+      .finish(),
+  );
+
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from test import greet
+        f"{greet("a")}; {greet("b", greeting="hi")}"
+      "#
+    ))
+    .unwrap();
+  assert_eq!(value.to_string(), "hello, a; hi, b");
+}
+
+check! {
+  enum_values,
+  r##"#!hebi
+    enum Shape:
+      Circle(radius)
+      Rect(w, h)
+      Empty
+    c := Shape.Circle(2)
+    r := Shape.Rect(h=3, w=1)
+    [
+      c,
+      r,
+      Shape.Empty,
+      c.radius,
+      r[0],
+      r[-1],
+      c is Shape,
+      c is Shape.Circle,
+      c is Shape.Rect,
+      Shape.Empty is Shape.Empty,
+      c == Shape.Circle(2),
+      c == Shape.Circle(3),
+      Shape.Empty == Shape.Empty,
+    ]
+  "##
+}
+
+check! {
+  enum_values_as_keys,
+  r##"#!hebi
+    enum E:
+      A
+      B(x)
+    t := {}
+    t[E.A] = 1
+    t[E.B(2)] = 2
+    s := Set(E.A, E.B((1, 2)))
+    [
+      t[E.A],
+      t[E.B(2)],
+      E.A in s,
+      E.B((1, 2)) in s,
+      E.B(1) in s,
+      s.len(),
+    ]
+  "##
+}
+
+check! {
+  enum_values_as_keys__error_unhashable,
+  r##"#!hebi
+    enum E: A(x)
+    Set(E.A([]))
+  "##
+}
+
+check! {
+  enum_match,
+  r##"#!hebi
+    enum Shape:
+      Circle(radius)
+      Rect(w, h)
+      Empty
+    fn area(shape):
+      match shape:
+        case Shape.Circle(r): return 3 * r * r
+        case Shape.Rect(w, h) if w == h: return f"square {w * h}"
+        case Shape.Rect(w, h): return w * h
+        case Shape.Empty: return 0
+    [area(Shape.Circle(2)), area(Shape.Rect(2, 2)), area(Shape.Rect(2, 3)), area(Shape.Empty)]
+  "##
+}
+
+check! {
+  enum_match__payload_len_mismatch,
+  r##"#!hebi
+    enum E: A(x, y), B
+    match E.A(1, 2):
+      case E.A(x): "one"
+      case E.A: "any"
+  "##
+}
+
+check! {
+  enum_values__error_too_many_args,
+  r##"#!hebi
+    enum Shape: Circle(radius)
+    Shape.Circle(1, 2)
+  "##
+}
+
+check! {
+  enum_values__error_missing_arg,
+  r##"#!hebi
+    enum Shape: Rect(w, h)
+    Shape.Rect(w=1)
+  "##
+}
+
+check! {
+  enum_values__error_unknown_variant,
+  r##"#!hebi
+    enum Shape: Circle(radius)
+    Shape.Square
+  "##
+}
+
+check! {
+  enum_values__error_immutable,
+  r##"#!hebi
+    enum Shape: Circle(radius)
+    c := Shape.Circle(1)
+    c.radius = 2
+  "##
+}
+
+check! {
+  class_magic_methods,
+  r##"#!hebi
+    class Vector:
+      x = 0
+      y = 0
+      init(self, x, y):
+        self.x = x
+        self.y = y
+      fn __add__(self, other): return Vector(self.x + other.x, self.y + other.y)
+      fn __mul__(self, k): return Vector(self.x * k, self.y * k)
+      fn __neg__(self): return Vector(-self.x, -self.y)
+      fn __eq__(self, other):
+        return other is Vector && self.x == other.x && self.y == other.y
+      fn __lt__(self, other): return self.x < other.x
+      fn __index__(self, i):
+        if i == 0: return self.x
+        return self.y
+      fn __contains__(self, v): return self.x == v || self.y == v
+      fn __call__(self, k): return self.x * k
+      fn __str__(self): return f"({self.x}, {self.y})"
+    a := Vector(1, 2)
+    b := Vector(3, 4)
+    print a + b, a * 2
+    print -a
+    [
+      a + b == Vector(4, 6),
+      a != b,
+      a == 1,
+      a < b,
+      b < a,
+      a[0],
+      a[1],
+      2 in a,
+      5 in a,
+      a(10),
+      to_str(b),
+      f"{a}",
+    ]
+  "##
+}
+
+check! {
+  class_magic_str_in_print_n,
+  r##"#!hebi
+    fn deep(n):
+      if n == 0: return "s"
+      return deep(n - 1)
+    class S:
+      fn __str__(self): return deep(500)
+    x := 1
+    s := S()
+    print x, s, x, s
+  "##
+}
+
+check! {
+  class_magic_eq_mutates_container,
+  r##"#!hebi
+    class M:
+      fn __eq__(self, other):
+        a.push(1)
+        t["x"] = 1
+        return true
+    a := [M()]
+    b := [M()]
+    t := {m: M()}
+    u := {m: M()}
+    [a == b, t == u, a.len(), t.len()]
+  "##
+}
+
+check! {
+  class_magic_methods_inherited,
+  r##"#!hebi
+    class Base:
+      fn __add__(self, other): return "added"
+      fn __str__(self): return "base"
+    class Derived(Base):
+      fn __str__(self): return "derived"
+    d := Derived()
+    print d, Base()
+    d + 1
+  "##
+}
+
+check! {
+  class_magic_methods__set_index,
+  r##"#!hebi
+    class Store:
+      values = none
+      init(self): self.values = {}
+      fn __index__(self, key): return self.values[key]
+      fn __setindex__(self, key, value): self.values[key] = value
+    s := Store()
+    s["a"] = 1
+    s["a"] += 1
+    s["a"]
+  "##
+}
+
+check! {
+  class_magic_methods__error_unsupported,
+  r##"#!hebi
+    class T: pass
+    T() + T()
+  "##
+}
+
+check! {
+  class_magic_methods__error_not_callable,
+  r##"#!hebi
+    class T: pass
+    T()()
+  "##
+}
+
+check! {
+  class_magic_methods__error_in_method,
+  r##"#!hebi
+    class T:
+      fn __add__(self, other): throw "nope"
+    T() + 1
+  "##
+}
+
+check! {
+  class_magic_methods__catch_error_in_method,
+  r##"#!hebi
+    class T:
+      fn __lt__(self, other): throw "nope"
+    try:
+      T() < 1
+    catch e:
+      e
+  "##
+}
+
+check! {
+  class_magic_methods__error_recursion,
+  r##"#!hebi
+    class T:
+      fn __add__(self, other): return self + other
+    T() + 1
+  "##
+}
+
+check! {
+  class_magic_methods__error_str_type,
+  r##"#!hebi
+    class T:
+      fn __str__(self): return 1
+    print T()
+  "##
+}
+
+#[test]
+fn native_enum() {
+  use crate::public::IntoValue;
+
+  enum Shape {
+    Circle(i32),
+    Empty,
+  }
+
+  fn make(scope: Scope<'_>) -> Result<crate::public::EnumValue<'_>> {
+    let radius = scope.param::<Option<i32>>(0)?;
+    let shape = match radius {
+      Some(radius) => Shape::Circle(radius),
+      None => Shape::Empty,
+    };
+    match shape {
+      Shape::Circle(radius) => {
+        let radius = radius.into_value(scope.global())?;
+        scope.new_enum_value::<Shape>("Circle", [radius])
+      }
+      Shape::Empty => scope.new_enum_value::<Shape>("Empty", []),
+    }
+  }
+
+  fn describe(scope: Scope<'_>) -> Result<String> {
+    let shape = scope.param::<crate::public::EnumValue>(0)?;
+    assert!(shape.is::<Shape>(scope.global()));
+    let radius = shape.field("radius").map(|v| v.to_string());
+    Ok(format!(
+      "{}.{} {}",
+      shape.enum_name(),
+      shape.variant(),
+      radius.unwrap_or_default()
+    ))
+  }
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("shapes")
+      .enum_::<Shape>("Shape", |e| {
+        e.variant("Circle", ["radius"]).unit("Empty").finish()
+      })
+      .function("make", make)
+      .function("describe", describe)
+      .finish(),
+  );
+
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from shapes import Shape, make, describe
+        fn radius(shape):
+          match shape:
+            case Shape.Circle(r): return r
+            case _: return -1
+        c := make(2)
+        e := make(none)
+        f"{radius(c)} {c is Shape.Circle} {e == Shape.Empty} {c == Shape.Circle(2)} {describe(c)}; {describe(Shape.Empty)}"
+      "#
+    ))
+    .unwrap();
+  assert_eq!(
+    value.to_string(),
+    "2 true true true Shape.Circle 2; Shape.Empty "
+  );
+}
+
+#[test]
+fn native_class_operators() {
+  use std::cmp::Ordering;
+
+  use crate::public::module::NativeModule;
+  use crate::public::{IntoValue, This};
+
+  #[derive(Clone, Copy, PartialEq)]
+  struct Vec3(f64, f64, f64);
+
+  impl Vec3 {
+    fn get(&self, i: i32) -> Result<f64> {
+      match i {
+        0 => Ok(self.0),
+        1 => Ok(self.1),
+        2 => Ok(self.2),
+        _ => fail!("index `{i}` out of bounds"),
+      }
+    }
+
+    fn len(&self) -> f64 {
+      (self.0 * self.0 + self.1 * self.1 + self.2 * self.2).sqrt()
+    }
+  }
+
+  struct Vec3Class(std::cell::Cell<Vec3>);
+
+  fn new<'cx>(scope: &Scope<'cx>, v: Vec3) -> Result<crate::public::Value<'cx>> {
+    scope.new_instance(Vec3Class(std::cell::Cell::new(v)))
+  }
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("math")
+      .class::<Vec3Class>("Vec3", |class| {
+        class
+          .init(|scope| {
+            let (x, y, z) = scope.params::<(f64, f64, f64)>()?;
+            Ok(Vec3Class(std::cell::Cell::new(Vec3(x, y, z))))
+          })
+          .op_add(|scope, this, other: This<Vec3Class>| {
+            let (a, b) = (this.0.get(), other.0.get());
+            new(&scope, Vec3(a.0 + b.0, a.1 + b.1, a.2 + b.2))
+          })
+          .op_mul(|scope, this, k: f64| {
+            let a = this.0.get();
+            new(&scope, Vec3(a.0 * k, a.1 * k, a.2 * k))
+          })
+          .op_neg(|scope, this| {
+            let a = this.0.get();
+            new(&scope, Vec3(-a.0, -a.1, -a.2))
+          })
+          .op_eq(|_, this, other: This<Vec3Class>| Ok(this.0.get() == other.0.get()))
+          .op_cmp(|_, this, other: This<Vec3Class>| {
+            Ok(
+              this
+                .0
+                .get()
+                .len()
+                .partial_cmp(&other.0.get().len())
+                .unwrap_or(Ordering::Equal),
+            )
+          })
+          .op_contains(|_, this, v: f64| {
+            let a = this.0.get();
+            a.0 == v || a.1 == v || a.2 == v
+          })
+          .index(|_, this, i: i32| this.0.get().get(i))
+          .index_mut(|_, this, i: i32, v: f64| {
+            let mut a = this.0.get();
+            match i {
+              0 => a.0 = v,
+              1 => a.1 = v,
+              2 => a.2 = v,
+              _ => fail!("index `{i}` out of bounds"),
+            }
+            this.0.set(a);
+            Ok(())
+          })
+          .display(|_, this| {
+            let a = this.0.get();
+            format!("Vec3({}, {}, {})", a.0, a.1, a.2)
+          })
+          .iter(|scope, this| {
+            let a = this.0.get();
+            let list = scope.new_list(3);
+            for v in [a.0, a.1, a.2] {
+              list.push(v.into_value(scope.global())?);
+            }
+            Ok(list)
+          })
+          .finish()
+      })
+      .finish(),
+  );
+
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from math import Vec3
+        a := Vec3(1.0, 2.0, 3.0)
+        b := Vec3(1.0, 1.0, 1.0)
+        a[2] = 4.0
+        sum := 0.0
+        for v in a: sum += v
+        f"{a + b} {a * 2.0} {to_str(-b)} {a + b == Vec3(2.0, 3.0, 5.0)} {a == b} {a == 1} {b < a} {a <= b} {2.0 in a} {3.0 in a} {a[1]} {sum}"
+      "#
+    ))
+    .unwrap();
+  assert_eq!(
+    value.to_string(),
+    "Vec3(2, 3, 5) Vec3(2, 4, 8) Vec3(-1, -1, -1) true false false true false true false 2 7"
+  );
+
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from math import Vec3
+        Vec3(1.0, 2.0, 3.0) - Vec3(1.0, 2.0, 3.0)
+      "#
+    ))
+    .unwrap_err();
+  assert!(
+    error.to_string().contains("`Vec3` does not support `-`"),
+    "{error}"
+  );
+}
+
+check! {
+  int_overflow_promotion,
+  r#"#!hebi
+    a := 2147483647 + 1
+    b := a * a
+    c := b * b
+    d := c - c + 1
+    [a, b, c, d, -2147483648 - 1, -a, type_of(a), type_of(c), type_of(d)]
+  "#
+}
+
+check! {
+  int_wide_literals,
+  r#"#!hebi
+    a := 5_000_000_000
+    b := -9_223_372_036_854_775_807 - 1
+    [a, b, b - 1, -b, a == 5000000000, a != 4999999999, a > 2147483647, b < a, a / 2, a + 0.5, 0 is a]
+  "#
+}
+
+check! {
+  int_wide_to_from_str,
+  r#"#!hebi
+    a := parse_int("123456789012345678901234567890")
+    b := to_int(1e20)
+    [a, b, a - 1, to_float(a), to_str(a * a), type_of(a), type_of(b)]
+  "#
+}
+
+#[test]
+fn wide_ints_from_rust() {
+  use crate::public::{FromValue, IntoValue};
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        big := 9223372036854775807 * 4
+      "#
+    ))
+    .unwrap();
+
+  let global = hebi.global();
+
+  let value = i64::MAX.into_value(global.clone()).unwrap();
+  assert_eq!(value.as_i64(), Some(i64::MAX));
+  assert!(value.is_int());
+  assert_eq!(i64::from_value(value, global.clone()).unwrap(), i64::MAX);
+
+  let value = u64::MAX.into_value(global.clone()).unwrap();
+  assert_eq!(value.as_i64(), None);
+  assert_eq!(value.as_i128(), Some(u64::MAX as i128));
+  assert_eq!(
+    u64::from_value(value.clone(), global.clone()).unwrap(),
+    u64::MAX
+  );
+  let e = i64::from_value(value, global.clone()).unwrap_err();
+  assert_eq!(
+    e.to_string(),
+    "`18446744073709551615` does not fit into `i64`"
+  );
+
+  let value = 7usize.into_value(global.clone()).unwrap();
+  assert_eq!(value.as_int(), Some(7));
+  assert_eq!(usize::from_value(value, global.clone()).unwrap(), 7);
+
+  let value = (-1i32).into_value(global.clone()).unwrap();
+  let e = usize::from_value(value, global.clone()).unwrap_err();
+  assert_eq!(e.to_string(), "`-1` does not fit into `usize`");
+
+  let value = i128::MIN.into_value(global.clone()).unwrap();
+  assert_eq!(i128::from_value(value, global.clone()).unwrap(), i128::MIN);
+
+  let big = global.get("big").unwrap();
+  assert_eq!(
+    i128::from_value(big.clone(), global.clone()).unwrap(),
+    i64::MAX as i128 * 4
+  );
+  assert!(u64::from_value(big, global.clone()).is_err());
+
+  let e = i64::from_value(2.0.into_value(global.clone()).unwrap(), global.clone()).unwrap_err();
+  assert_eq!(e.to_string(), "value is not an int");
+}
+
+check! {
+  bitwise_ops,
+  r#"#!hebi
+    [
+      12 & 10,
+      12 | 10,
+      12 ^ 10,
+      ~5,
+      ~-1,
+      1 << 4,
+      -16 >> 2,
+      1 << 40,
+      (1 << 100) >> 99,
+      (1 << 70) | 1,
+      ~(1 << 70),
+      -1 >> 100,
+      1 + 2 & 6 == 2,
+    ]
+  "#
+}
+
+check! {
+  floor_div,
+  r#"#!hebi
+    [
+      7 // 2,
+      -7 // 2,
+      7 // -2,
+      -7 // -2,
+      6 // 3,
+      7.5 // 2,
+      -7 // 2.0,
+      (1 << 80) // 3,
+      (-9223372036854775807 - 1) // -1,
+    ]
+  "#
+}
+
+check! {
+  rem_int,
+  r#"#!hebi
+    [
+      7 % 3,
+      -7 % 3,
+      7 % -3,
+      7.5 % 2,
+      9007199254740993 % 2,
+      9007199254740993 % 10,
+      (1 << 80) % 7,
+      (-9223372036854775807 - 1) % -1,
+    ]
+  "#
+}
+
+check! {
+  rem__error_zero,
+  r#"#!hebi
+    1 % 0
+  "#
+}
+
+check! {
+  floor_div__error_zero,
+  r#"#!hebi
+    1 // 0
+  "#
+}
+
+check! {
+  bitwise__error_float,
+  r#"#!hebi
+    1 & 2.0
+  "#
+}
+
+check! {
+  bitwise__error_negative_shift,
+  r#"#!hebi
+    1 << -1
+  "#
+}
+
+check! {
+  bitwise__error_shift_too_large,
+  r#"#!hebi
+    try:
+      1 << 100000000000
+    catch e:
+      print e
+    0 << 100000000000
+  "#
+}
+
+check! {
+  bitwise__error_bit_not_str,
+  r#"#!hebi
+    ~"a"
+  "#
+}
+
+check! {
+  class_bitwise_magic_methods,
+  r##"#!hebi
+    class Flags:
+      bits = 0
+      init(self, bits):
+        self.bits = bits
+      fn __and__(self, other): return Flags(self.bits & other.bits)
+      fn __or__(self, other): return Flags(self.bits | other.bits)
+      fn __xor__(self, other): return Flags(self.bits ^ other.bits)
+      fn __lshift__(self, n): return Flags(self.bits << n)
+      fn __rshift__(self, n): return Flags(self.bits >> n)
+      fn __floordiv__(self, n): return Flags(self.bits // n)
+      fn __invert__(self): return Flags(~self.bits & 7)
+    a := Flags(5)
+    b := Flags(3)
+    [(a & b).bits, (a | b).bits, (a ^ b).bits, (a << 2).bits, (a >> 1).bits, (a // 2).bits, (~a).bits]
+  "##
+}
+
+#[test]
+fn native_class_bitwise_operators() {
+  use crate::public::module::NativeModule;
+  use crate::public::This;
+
+  struct Flags(u32);
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("flags")
+      .class::<Flags>("Flags", |class| {
+        class
+          .init(|scope| Ok(Flags(scope.param::<i32>(0)? as u32)))
+          .op_bit_and(|scope, this, other: This<Flags>| scope.new_instance(Flags(this.0 & other.0)))
+          .op_bit_or(|scope, this, other: This<Flags>| scope.new_instance(Flags(this.0 | other.0)))
+          .op_bit_xor(|scope, this, other: This<Flags>| scope.new_instance(Flags(this.0 ^ other.0)))
+          .op_shl(|scope, this, n: i32| scope.new_instance(Flags(this.0 << n)))
+          .op_shr(|scope, this, n: i32| scope.new_instance(Flags(this.0 >> n)))
+          .op_floor_div(|scope, this, n: i32| scope.new_instance(Flags(this.0 / n as u32)))
+          .op_bit_not(|scope, this| scope.new_instance(Flags(!this.0 & 0xf)))
+          .display(|_, this| format!("{:04b}", this.0))
+          .finish()
+      })
+      .finish(),
+  );
+
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from flags import Flags
+        a := Flags(5)
+        b := Flags(3)
+        f"{a & b} {a | b} {a ^ b} {a << 1} {a >> 2} {a // 2} {~a}"
+      "#
+    ))
+    .unwrap();
+  assert_eq!(value.to_string(), "0001 0111 0110 1010 0001 0010 1010");
+
+  let error = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from flags import Flags
+        Flags(1) + Flags(2)
+      "#
+    ))
+    .unwrap_err();
+  assert!(
+    error.to_string().contains("`Flags` does not support `+`"),
+    "{error}"
+  );
+}
+
+#[test]
+fn bytes_from_rust() {
+  use crate::public::module::NativeModule;
+  use crate::public::{Bytes, FromValue, IntoValue};
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("io")
+      .function("checksum", |scope| {
+        let data = scope.param::<Bytes>(0)?;
+        Ok(data.as_bytes().iter().map(|b| *b as i32).sum::<i32>())
+      })
+      .function("payload", |scope| {
+        let data: &[u8] = &[0xde, 0xad];
+        data.into_value(scope.global())
+      })
+      .finish(),
+  );
+
+  let global = hebi.global();
+  let value = vec![1u8, 2, 3].into_value(global.clone()).unwrap();
+  global.set(global.new_string("data"), value);
+
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from io import checksum, payload
+        r := (checksum(data), payload() + data)
+      "#
+    ))
+    .unwrap();
+
+  let global = hebi.global();
+  let value = global.get("r").unwrap();
+  let (sum, bytes) = <(i32, Vec<u8>)>::from_value(value, global).unwrap();
+  assert_eq!(sum, 6);
+  assert_eq!(bytes, vec![0xde, 0xad, 1, 2, 3]);
+}
+
+#[test]
+fn set_from_rust() {
+  use std::collections::{BTreeSet, HashSet};
+
+  use crate::public::{FromValue, IntoValue, Set};
+
+  let mut hebi = crate::public::Hebi::new();
+  let global = hebi.global();
+  let value = BTreeSet::from([1, 2, 3])
+    .into_value(global.clone())
+    .unwrap();
+  global.set(global.new_string("s"), value);
+
+  hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        r := s | Set(4)
+      "#
+    ))
+    .unwrap();
+
+  let global = hebi.global();
+  let value = global.get("r").unwrap();
+  let set = value.as_object::<Set>(global.clone()).unwrap();
+  assert_eq!(set.len(), 4);
+  assert!(set.contains(4.into_value(global.clone()).unwrap()).unwrap());
+  let set = HashSet::<i32>::from_value(value, global).unwrap();
+  assert_eq!(set, HashSet::from([1, 2, 3, 4]));
+}
+
+#[test]
+fn native_class_hash() {
+  use crate::public::module::NativeModule;
+
+  struct Id(i32);
+
+  let mut hebi = crate::public::Hebi::new();
+  hebi.register(
+    &NativeModule::builder("id")
+      .class::<Id>("Id", |class| {
+        class
+          .init(|scope| Ok(Id(scope.param::<i32>(0)?)))
+          .hash(|_, this| this.0)
+          .finish()
+      })
+      .finish(),
+  );
+
+  let value = hebi
+    .eval(indoc::indoc!(
+      r#"#!hebi
+        from id import Id
+        t := {}
+        t[Id(1)] = "a"
+        t[Id(1)] = "b"
+        t[Id(2)] = "c"
+        f"{t[Id(1)]} {t[Id(2)]} {?t[1]}"
+      "#
+    ))
+    .unwrap();
+  assert_eq!(value.to_string(), "b c none");
+}
@@ -12,12 +12,12 @@ macro_rules! check {
       let mut hebi = crate::public::Hebi::builder().output(Vec::<u8>::new()).finish();
       let chunk = match hebi.compile(&source) {
         Ok(chunk) => chunk,
-        Err(e) => panic!("Failed to compile:\n{}", e.report(&source, false)),
+        Err(e) => panic!("Failed to compile:\n{}", e.report(false)),
       };
       eprintln!("{}", chunk.disassemble());
       let result = match hebi.run_async(chunk).await {
         Ok(value) => format!("{value:#?}"),
-        Err(e) => e.report(&source, false),
+        Err(e) => e.report(false),
       };
       let output = String::from_utf8(
         hebi
@@ -53,7 +53,7 @@ macro_rules! check {
         .finish();
       let result = match hebi.eval_async(&source).await {
         Ok(value) => format!("{value:#?}"),
-        Err(e) => e.report(&source, false),
+        Err(e) => e.report(false),
       };
       let output = String::from_utf8(
         hebi
@@ -5,29 +5,35 @@ pub mod util;
 
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
+use std::future::Future;
 use std::mem::take;
+use std::pin::pin;
 use std::ptr::NonNull;
+use std::task::{Context, Poll};
 
+use futures_util::task::noop_waker_ref;
 use indexmap::IndexMap;
 
 use self::util::*;
 use super::dispatch::{dispatch, Call, ControlFlow, Handler, LoadFrame, Return};
 use super::global::Global;
 use crate::internal::bytecode::opcode as op;
-use crate::internal::error::{Error, Result};
+use crate::internal::error::{Backtrace, BacktraceFrame, Error, Result, Thrown};
 use crate::internal::object::class::{ClassInstance, ClassProxy};
-use crate::internal::object::function::Params;
-use crate::internal::object::module::{ModuleId, ModuleKind};
+use crate::internal::object::function::{Generator, Params, Resume};
+use crate::internal::object::int;
+use crate::internal::object::module::{ModuleId, ModuleKind, MAIN_MODULE};
 use crate::internal::object::native::LocalBoxFuture;
+use crate::internal::object::native::NativeClassInstance;
+use crate::internal::object::table::Key;
 use crate::internal::object::{
-  function, Any, ClassDescriptor, ClassType, Function, FunctionDescriptor, List, Module, Object,
-  Ptr, Str, Table, Type,
+  function, Any, ClassDescriptor, ClassType, EnumDescriptor, EnumType, EnumValue, Function,
+  FunctionDescriptor, List, Module, Object, Ptr, RuntimeError, Str, Table, Tuple, Type,
 };
 use crate::internal::value::constant::Constant;
 use crate::internal::value::Value;
 use crate::internal::{codegen, syntax};
 use crate::public::Scope;
-use crate::util::JoinIter;
 
 pub struct Thread {
   pub(crate) global: Global,
@@ -53,6 +59,7 @@ impl Clone for Thread {
 pub struct Stack {
   pub(crate) frames: Vec<Frame>,
   pub(crate) regs: Vec<Value>,
+  pub(crate) handlers: Vec<TryFrame>,
 }
 
 impl Stack {
@@ -60,6 +67,34 @@ impl Stack {
     Self {
       frames: Vec::with_capacity(8),
       regs: Vec::with_capacity(64),
+      handlers: Vec::new(),
+    }
+  }
+
+  /// Remove any handlers which belong to frames that are no longer on the
+  /// call stack.
+  fn truncate_handlers(&mut self) {
+    let frames = self.frames.len();
+    while matches!(self.handlers.last(), Some(handler) if handler.frame_index >= frames) {
+      self.handlers.pop();
+    }
+  }
+}
+
+/// An active `try` block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TryFrame {
+  /// Index of the call frame which pushed this handler.
+  frame_index: usize,
+  /// Absolute address of the `catch` block in that frame's bytecode.
+  catch_addr: usize,
+}
+
+impl TryFrame {
+  pub(crate) fn new(frame_index: usize, catch_addr: usize) -> Self {
+    Self {
+      frame_index,
+      catch_addr,
     }
   }
 }
@@ -83,12 +118,97 @@ impl Thread {
     for frame in stack.frames.drain(start..).rev() {
       stack.regs.truncate(frame.stack_base);
     }
+    stack.truncate_handlers();
+  }
+
+  /// Attempt to resume execution at the innermost `catch` block.
+  ///
+  /// Only handlers pushed by frames at or above `floor` are considered.
+  /// If there are none, `error` is returned back to the caller.
+  fn catch(&mut self, error: Error, floor: usize) -> Result<()> {
+    // aborting must not be preventable by the script
+    if let Error::Aborted(_) = error.inner() {
+      return Err(error);
+    }
+
+    let stack = unsafe { self.stack.as_mut() };
+    let handler = match stack.handlers.last() {
+      Some(handler) if handler.frame_index >= floor => *handler,
+      _ => return Err(error),
+    };
+    stack.handlers.pop();
+
+    // discard every frame above the one which pushed the handler
+    for frame in stack.frames.drain(handler.frame_index + 1..).rev() {
+      stack.regs.truncate(frame.stack_base);
+    }
+    let frame = &stack.frames[handler.frame_index];
+    stack.regs.truncate(frame.stack_base + frame.frame_size);
+
+    self.acc = match error.inner() {
+      Error::Thrown(thrown) => self.global.take_thrown(thrown.id()).unwrap_or_default(),
+      _ => Value::object(self.global.alloc(RuntimeError::new(&self.global, error))),
+    };
+    self.pc = handler.catch_addr;
+    Ok(())
+  }
+
+  /// Attach a backtrace of the current call stack to `error`, where `pc` is
+  /// the offset of the failing instruction in the innermost frame.
+  ///
+  /// Errors which already have a backtrace are returned as-is, because it
+  /// was captured closer to where they were originally raised.
+  fn trace(&self, error: Error, pc: usize) -> Error {
+    if let Error::Traced(..) = error {
+      return error;
+    }
+
+    let stack = unsafe { self.stack.as_ref() };
+    let mut frames = Vec::with_capacity(stack.frames.len());
+    let mut pc = Some(pc);
+    for frame in stack.frames.iter().rev() {
+      let module = if frame.module_id == ModuleId::global() {
+        MAIN_MODULE.to_string()
+      } else {
+        match self.global.get_module_by_id(frame.module_id) {
+          Some(module) => module.name.to_string(),
+          None => "<unknown>".to_string(),
+        }
+      };
+      let spans = &frame.descriptor.spans;
+      frames.push(BacktraceFrame::new(
+        frame.descriptor.name.to_string(),
+        module,
+        spans.source().cloned(),
+        pc.and_then(|pc| spans.get(pc)),
+      ));
+      // the caller is suspended at the call instruction which
+      // ends right before the return address
+      pc = frame.return_addr.map(|addr| addr - 1);
+    }
+    frames.reverse();
+
+    // point errors raised without a span at the failing instruction
+    let error = match error {
+      Error::Vm(mut e) if e.span.is_empty() => {
+        if let Some(span) = frames.last().and_then(|frame| frame.span()) {
+          e.span = span;
+        }
+        Error::Vm(e)
+      }
+      error => error,
+    };
+
+    Error::Traced(Box::new(error), Backtrace::new(frames))
   }
 
   pub async fn entry(&mut self, main: Ptr<Function>) -> Result<Value> {
     Function::prepare_call_empty_unchecked(main.clone(), self, None);
     loop {
       if let Err(e) = self.run() {
+        let Err(e) = self.catch(e, 0) else {
+          continue;
+        };
         self.unwind_stack(None);
         if !unsafe { self.stack.as_ref().regs.is_empty() } {
           eprintln!("{self:?}");
@@ -105,6 +225,10 @@ impl Thread {
             continue;
           }
           Err(e) => {
+            let e = self.trace(e, self.pc - 1);
+            let Err(e) = self.catch(e, 0) else {
+              continue;
+            };
             self.unwind_stack(None);
             if !unsafe { self.stack.as_ref().regs.is_empty() } {
               eprintln!("{self:?}");
@@ -125,6 +249,27 @@ impl Thread {
   }
 
   pub async fn call(&mut self, callable: Ptr<Any>, args: &[Value]) -> Result<Value> {
+    self.call_inner(callable, args, false).await
+  }
+
+  /// Call `callable` to completion without suspending, for callers which
+  /// can't `await`, such as operators dispatched to magic methods.
+  ///
+  /// Async native functions called along the way must resolve immediately.
+  pub fn call_sync(&mut self, callable: Ptr<Any>, args: &[Value]) -> Result<Value> {
+    self.global.enter_nested_call()?;
+    let result = {
+      let fut = pin!(self.call_inner(callable, args, true));
+      match fut.poll(&mut Context::from_waker(noop_waker_ref())) {
+        Poll::Ready(result) => result,
+        Poll::Pending => unreachable!("synchronous call was suspended"),
+      }
+    };
+    self.global.leave_nested_call();
+    result
+  }
+
+  async fn call_inner(&mut self, callable: Ptr<Any>, args: &[Value], sync: bool) -> Result<Value> {
     let current_frame_index = unsafe { self.stack.as_ref().frames.len() };
 
     let args = self.push_args(args);
@@ -134,24 +279,31 @@ impl Thread {
         CallResult::Poll(frame) => {
           // `args` is strictly below `frame.args`,
           // so we don't have to pop them here
-          frame.fut.await
+          resolve(frame.fut, sync).await
         }
         CallResult::Dispatch => {
           // the call pushed a frame onto the call stack,
           // so all we have to do is enter the interpreter
+          self.pc = self.entry_pc();
           loop {
             if let Err(e) = self.run() {
-              break Err(e);
+              match self.catch(e, current_frame_index) {
+                Ok(()) => continue,
+                Err(e) => break Err(e),
+              }
             }
             if let Some(frame) = self.poll.take() {
-              let result = frame.fut.await;
+              let result = resolve(frame.fut, sync).await;
               self.truncate_stack(frame.stack_base);
               match result {
                 Ok(value) => {
                   self.acc = value;
                   continue;
                 }
-                Err(e) => break Err(e),
+                Err(e) => match self.catch(self.trace(e, self.pc - 1), current_frame_index) {
+                  Ok(()) => continue,
+                  Err(e) => break Err(e),
+                },
               };
             } else {
               break Ok(take(&mut self.acc));
@@ -205,16 +357,28 @@ impl Thread {
     stack_mut!(self).truncate(to)
   }
 
-  fn do_call(&mut self, function: Ptr<Any>, args: Args, return_addr: usize) -> Result<Call> {
-    if function.is::<Function>() {
-      let function = unsafe { function.cast_unchecked::<Function>() };
-      match Function::prepare_call(function, self, args, Some(return_addr)) {
-        Ok(frame) => return Ok(Call::LoadFrame(frame)),
-        Err(e) => return Err(e),
-      };
-    }
+  fn do_call(
+    &mut self,
+    function: Ptr<Any>,
+    args: Args,
+    named: Option<Ptr<Table>>,
+    return_addr: usize,
+  ) -> Result<Call> {
+    self.global.step()?;
+
+    // calls to generator functions don't push a frame
+    let function = match function.cast::<Function>() {
+      Ok(function) if !function.descriptor.is_generator => {
+        let frame = Function::prepare_call(function, self, args, Some(return_addr))?;
+        return Ok(Call::LoadFrame(frame));
+      }
+      Ok(function) => function.into_any(),
+      Err(function) => function,
+    };
 
-    match function.call(self.get_scope(args), Some(return_addr)) {
+    let mut scope = self.get_scope(args);
+    scope.named = named;
+    match function.call(scope, Some(return_addr)) {
       Ok(call) => match call {
         CallResult::Return(value) => {
           self.acc = value;
@@ -226,7 +390,7 @@ impl Thread {
         }
         CallResult::Dispatch => {
           let bytecode = current_call_frame!(self).instructions;
-          let pc = 0;
+          let pc = self.entry_pc();
           Ok(Call::LoadFrame(LoadFrame { bytecode, pc }))
         }
       },
@@ -234,7 +398,42 @@ impl Thread {
     }
   }
 
-  fn make_fn(&mut self, desc: Ptr<FunctionDescriptor>) -> Ptr<Function> {
+  /// Call `function` with arguments which are not stored in the registers
+  /// of the current frame.
+  fn call_with_args(
+    &mut self,
+    function: Ptr<Any>,
+    args: Vec<Value>,
+    named: Option<Ptr<Table>>,
+    return_addr: usize,
+  ) -> Result<Call> {
+    // the arguments are pushed past the end of the current frame, and
+    // removed again once the callee no longer needs them
+    let num_frames = unsafe { self.stack.as_ref() }.frames.len();
+    let args = self.push_args(&args);
+    let call = self.do_call(function, args, named, return_addr);
+
+    let stack = unsafe { self.stack.as_mut() };
+    if let Ok(Call::Yield) = &call {
+      // async calls use the arguments until they resolve,
+      // so they are popped along with the async frame
+      if let Some(frame) = self.poll.as_mut() {
+        frame.stack_base = args.start;
+      }
+    } else if stack.frames.len() > num_frames {
+      // the callee's frame was pushed above the arguments
+      // and has already copied them into its registers
+      let frame = unsafe { stack.frames.last_mut().unwrap_unchecked() };
+      stack.regs.drain(args.start..args.start + args.count);
+      frame.stack_base -= args.count;
+    } else {
+      self.pop_args(args);
+    }
+
+    call
+  }
+
+  fn make_fn(&mut self, desc: Ptr<FunctionDescriptor>) -> Result<Ptr<Function>> {
     let num_upvalues = desc.upvalues.borrow().len();
     let mut upvalues = Vec::with_capacity(num_upvalues);
     upvalues.resize_with(num_upvalues, Value::none);
@@ -250,13 +449,13 @@ impl Thread {
       let slot = unsafe { upvalues.get_unchecked_mut(i) };
       *slot = value;
     }
-    let upvalues = self.global.alloc(List::from(upvalues));
+    let extra = num_upvalues * List::ELEMENT_SIZE;
+    let upvalues = self.global.try_alloc(List::from(upvalues), extra)?;
 
-    self.global.alloc(Function::new(
-      desc,
-      upvalues,
-      current_call_frame!(self).module_id,
-    ))
+    self.global.try_alloc(
+      Function::new(desc, upvalues, current_call_frame!(self).module_id),
+      0,
+    )
   }
 
   fn make_class(
@@ -264,9 +463,15 @@ impl Thread {
     desc: Ptr<ClassDescriptor>,
     fields: Option<Ptr<Table>>,
     parent: Option<Ptr<ClassType>>,
-  ) -> Ptr<ClassType> {
-    let mut init = desc.init.as_ref().map(|init| self.make_fn(init.clone()));
-    let fields = fields.unwrap_or_else(|| self.global.alloc(Table::new()));
+  ) -> Result<Ptr<ClassType>> {
+    let mut init = match desc.init.as_ref() {
+      Some(init) => Some(self.make_fn(init.clone())?),
+      None => None,
+    };
+    let fields = match fields {
+      Some(fields) => fields,
+      None => self.global.try_alloc(Table::new(), 0)?,
+    };
     let mut methods = IndexMap::with_capacity(desc.methods.len());
 
     // inherit `init` and methods
@@ -281,16 +486,13 @@ impl Thread {
     }
 
     for (key, desc) in desc.methods.iter() {
-      methods.insert(key.clone(), self.make_fn(desc.clone()));
+      methods.insert(key.clone(), self.make_fn(desc.clone())?);
     }
 
-    self.global.alloc(ClassType::new(
-      desc.name.clone(),
-      init,
-      fields,
-      methods,
-      parent,
-    ))
+    self.global.try_alloc(
+      ClassType::new(desc.name.clone(), init, fields, methods, parent),
+      0,
+    )
   }
 
   fn load_module(&mut self, path: Ptr<Str>, return_addr: usize) -> Result<Call> {
@@ -305,9 +507,17 @@ impl Thread {
 
     // module is not in cache, actually load it
     let module_id = self.global.next_module_id();
-    let module = self.global.load_module(path.as_str())?.to_string();
-    let module = syntax::parse(self.global.clone(), &module).map_err(Error::Syntax)?;
-    let module = codegen::emit(self.global.clone(), &module, path.as_str(), false);
+    let module = self.global.load_module(path.as_str())?;
+    let source = self.global.add_source(path.as_str(), module);
+    let module = syntax::parse(self.global.clone(), source.text())
+      .map_err(|e| Error::Syntax(e.with_source(source.clone())))?;
+    let module = codegen::emit(
+      self.global.clone(),
+      &module,
+      path.as_str(),
+      source.clone(),
+      false,
+    );
     let main = self.global.alloc(Function::new(
       module.root.clone(),
       self.global.alloc(List::new()),
@@ -371,14 +581,71 @@ impl Thread {
     stack_mut!(self).truncate(scope.args.start);
   }
 
+  /// Offset of the first instruction to execute in a frame which was just
+  /// pushed onto the call stack.
+  fn entry_pc(&self) -> usize {
+    match &current_call_frame!(self).generator {
+      Some((generator, _)) => generator.pc(),
+      None => 0,
+    }
+  }
+
+  /// Continue execution in the caller of `frame`, which was just popped.
+  fn return_to_caller(&mut self, frame: &Frame) -> Return {
+    let stack = unsafe { self.stack.as_ref() };
+    if let Some(current_frame) = stack.frames.last() {
+      if let Some(return_addr) = frame.return_addr {
+        self.pc = return_addr;
+        return Return::LoadFrame(LoadFrame {
+          bytecode: current_frame.instructions,
+          pc: self.pc,
+        });
+      }
+    }
+
+    Return::Yield
+  }
+
+  /// Operators whose left operand is a class instance are dispatched to its
+  /// magic methods or operator hooks, whatever the type of the right operand.
+  fn class_operator(
+    &self,
+    lhs: &Value,
+    rhs: &Value,
+    name: &str,
+    op: &str,
+  ) -> Result<Option<Value>> {
+    let scope = self.get_empty_scope();
+    let args = std::slice::from_ref(rhs);
+    if let Some(this) = lhs.clone().to_object::<ClassInstance>() {
+      ClassInstance::call_operator(scope, this, name, op, args).map(Some)
+    } else if let Some(this) = lhs.clone().to_object::<NativeClassInstance>() {
+      NativeClassInstance::call_operator(scope, this, name, op, args).map(Some)
+    } else {
+      Ok(None)
+    }
+  }
+
   fn stack_base(&self) -> usize {
     current_call_frame!(self).stack_base
   }
 
   // idk what should be the public API for this
   pub(crate) fn check_equality(scope: Scope<'_>, lhs: Value, rhs: Value) -> Result<bool> {
+    let args = std::slice::from_ref(&rhs);
+    let result = if let Some(this) = lhs.clone().to_object::<ClassInstance>() {
+      ClassInstance::call_method(scope.clone(), this, "__eq__", args)?
+    } else if let Some(this) = lhs.clone().to_object::<NativeClassInstance>() {
+      NativeClassInstance::call_hook(scope.clone(), this, "__eq__", args)?
+    } else {
+      None
+    };
+    if let Some(result) = result {
+      return Ok(is_truthy(result));
+    }
+
     Ok(binary!(lhs, rhs {
-      i32 => lhs == rhs,
+      int => int::cmp(&lhs, &rhs).is_eq(),
       f64 => lhs == rhs,
       any => lhs.eq(scope, rhs)?,
       bool => lhs == rhs,
@@ -388,6 +655,20 @@ impl Thread {
   }
 }
 
+/// Await `fut`, or poll it exactly once if the caller can't be suspended.
+async fn resolve(mut fut: LocalBoxFuture<'static, Result<Value>>, sync: bool) -> Result<Value> {
+  if !sync {
+    return fut.await;
+  }
+  match fut
+    .as_mut()
+    .poll(&mut Context::from_waker(noop_waker_ref()))
+  {
+    Poll::Ready(result) => result,
+    Poll::Pending => fail!("cannot call an async function from a magic method"),
+  }
+}
+
 pub enum CallResult {
   Return(Value),
   Poll(AsyncFrame),
@@ -465,6 +746,7 @@ impl Debug for AsyncFrame {
 }
 
 pub(crate) struct Frame {
+  descriptor: Ptr<FunctionDescriptor>,
   instructions: NonNull<[u8]>,
   constants: NonNull<[Constant]>,
   upvalues: Ptr<List>,
@@ -472,11 +754,15 @@ pub(crate) struct Frame {
   frame_size: usize,
   return_addr: Option<usize>,
   module_id: ModuleId,
+  /// The generator which this frame belongs to, if it is a resumed
+  /// generator, and the method which resumed it.
+  generator: Option<(Ptr<Generator>, Resume)>,
 }
 
 impl Debug for Frame {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("Frame")
+      .field("name", &self.descriptor.name)
       .field("instructions", &unsafe { self.instructions.as_ref() })
       .field("constants", &unsafe { self.constants.as_ref() })
       .field("upvalues", &self.upvalues)
@@ -484,6 +770,7 @@ impl Debug for Frame {
       .field("frame_size", &self.frame_size)
       .field("return_addr", &self.return_addr)
       .field("module_id", &self.module_id)
+      .field("generator", &self.generator)
       .finish()
   }
 }
@@ -493,6 +780,7 @@ impl Frame {
     let desc = f.descriptor.as_ref();
 
     Self {
+      descriptor: f.descriptor.clone(),
       instructions: desc.instructions,
       constants: desc.constants,
       upvalues: f.upvalues.clone(),
@@ -500,11 +788,50 @@ impl Frame {
       frame_size: desc.frame_size,
       return_addr,
       module_id: f.module_id,
+      generator: None,
+    }
+  }
+
+  pub(crate) fn resume(
+    g: &Ptr<Generator>,
+    resume: Resume,
+    stack_base: usize,
+    return_addr: Option<usize>,
+  ) -> Self {
+    let desc = g.descriptor.as_ref();
+
+    Self {
+      descriptor: g.descriptor.clone(),
+      instructions: desc.instructions,
+      constants: desc.constants,
+      upvalues: g.upvalues.clone(),
+      stack_base,
+      frame_size: desc.frame_size,
+      return_addr,
+      module_id: g.module,
+      generator: Some((g.clone(), resume)),
+    }
+  }
+}
+
+impl Drop for Frame {
+  fn drop(&mut self) {
+    if let Some((generator, _)) = &self.generator {
+      generator.abandon();
     }
   }
 }
 
 impl Thread {
+  fn push_handler(&mut self, catch_addr: usize) {
+    let stack = unsafe { self.stack.as_mut() };
+    let frame_index = stack.frames.len() - 1;
+    stack.handlers.push(TryFrame {
+      frame_index,
+      catch_addr,
+    });
+  }
+
   fn get_constant(&self, idx: op::Constant) -> Constant {
     clone_from_raw_slice(current_call_frame!(self).constants.as_ptr(), idx.index())
   }
@@ -854,7 +1181,7 @@ impl Handler for Thread {
       fail!("{this} is not a class");
     };
 
-    self.acc = Value::object(self.global.alloc(proxy));
+    self.acc = Value::object(self.global.try_alloc(proxy, 0)?);
 
     Ok(())
   }
@@ -902,7 +1229,7 @@ impl Handler for Thread {
     let desc = self.get_constant_object::<FunctionDescriptor>(desc);
 
     // fetch upvalues
-    let f = self.make_fn(desc);
+    let f = self.make_fn(desc)?;
 
     self.acc = Value::object(f);
 
@@ -915,7 +1242,7 @@ impl Handler for Thread {
 
     let desc = self.get_constant_object::<ClassDescriptor>(desc);
 
-    let class = self.make_class(desc, None, None);
+    let class = self.make_class(desc, None, None)?;
 
     self.acc = Value::object(class);
 
@@ -932,8 +1259,10 @@ impl Handler for Thread {
     let Some(parent) = parent.clone().to_object::<ClassType>() else {
       fail!("{parent} is not a class");
     };
-    let fields = self.global.alloc(parent.fields.copy());
-    let class = self.make_class(desc, Some(fields), Some(parent));
+    let fields = parent.fields.copy();
+    let extra = fields.len() * Table::ENTRY_SIZE;
+    let fields = self.global.try_alloc(fields, extra)?;
+    let class = self.make_class(desc, Some(fields), Some(parent))?;
 
     self.acc = Value::object(class);
 
@@ -946,12 +1275,15 @@ impl Handler for Thread {
 
     let desc = self.get_constant_object::<ClassDescriptor>(desc);
 
-    let fields = self.global.alloc(Table::with_capacity(desc.fields.len()));
+    let extra = desc.fields.len() * Table::ENTRY_SIZE;
+    let fields = self
+      .global
+      .try_alloc(Table::with_capacity(desc.fields.len()), extra)?;
     for (offset, key) in desc.fields.keys().enumerate() {
       let value = self.get_register(parts.offset(offset));
-      fields.insert(key, value);
+      fields.insert_key(key, value);
     }
-    let class = self.make_class(desc, Some(fields), None);
+    let class = self.make_class(desc, Some(fields), None)?;
 
     self.acc = Value::object(class);
 
@@ -969,18 +1301,31 @@ impl Handler for Thread {
       fail!("{parent} is not a class");
     };
 
-    let fields = self.global.alloc(parent.fields.copy());
+    let fields = parent.fields.copy();
+    let extra = fields.len() * Table::ENTRY_SIZE;
+    let fields = self.global.try_alloc(fields, extra)?;
     for (offset, key) in desc.fields.keys().enumerate() {
       let value = self.get_register(parts.offset(1 + offset));
-      fields.insert(key, value);
+      fields.insert_key(key, value);
     }
-    let class = self.make_class(desc, Some(fields), Some(parent));
+    let class = self.make_class(desc, Some(fields), Some(parent))?;
 
     self.acc = Value::object(class);
 
     Ok(())
   }
 
+  fn op_make_enum(&mut self, desc: op::Constant) -> Result<()> {
+    self.print_stack();
+    vprintln!("make_enum {desc}");
+
+    let desc = self.get_constant_object::<EnumDescriptor>(desc);
+
+    self.acc = Value::object(self.global.try_alloc(EnumType::new(desc), 0)?);
+
+    Ok(())
+  }
+
   fn op_make_list(&mut self, start: op::Register, count: op::Count) -> Result<()> {
     self.print_stack();
     vprintln!("make_list {start}, {count}");
@@ -989,7 +1334,8 @@ impl Handler for Thread {
     for reg in start.iter(count, 1) {
       list.push(self.get_register(reg));
     }
-    self.acc = Value::object(self.global.alloc(list));
+    let extra = count.value() * List::ELEMENT_SIZE;
+    self.acc = Value::object(self.global.try_alloc(list, extra)?);
     Ok(())
   }
 
@@ -997,7 +1343,28 @@ impl Handler for Thread {
     self.print_stack();
     vprintln!("make_list_empty");
 
-    self.acc = Value::object(self.global.alloc(List::new()));
+    self.acc = Value::object(self.global.try_alloc(List::new(), 0)?);
+    Ok(())
+  }
+
+  fn op_make_tuple(&mut self, start: op::Register, count: op::Count) -> Result<()> {
+    self.print_stack();
+    vprintln!("make_tuple {start}, {count}");
+
+    let items = start
+      .iter(count, 1)
+      .map(|reg| self.get_register(reg))
+      .collect::<Vec<_>>();
+    let extra = count.value() * Tuple::ELEMENT_SIZE;
+    self.acc = Value::object(self.global.try_alloc(Tuple::from(items), extra)?);
+    Ok(())
+  }
+
+  fn op_make_tuple_empty(&mut self) -> Result<()> {
+    self.print_stack();
+    vprintln!("make_tuple_empty");
+
+    self.acc = Value::object(self.global.try_alloc(Tuple::new(), 0)?);
     Ok(())
   }
 
@@ -1010,13 +1377,11 @@ impl Handler for Thread {
       let key = self.get_register(reg);
       let value = self.get_register(reg.offset(1));
 
-      let Some(key) = key.clone().to_any().and_then(|v| v.cast::<Str>().ok()) else {
-        fail!("`{key}` is not a string");
-      };
-
-      table.insert(key, value);
+      let key = Key::new(self.get_empty_scope(), key)?;
+      table.insert_key(key, value);
     }
-    self.acc = Value::object(self.global.alloc(table));
+    let extra = count.value() * Table::ENTRY_SIZE;
+    self.acc = Value::object(self.global.try_alloc(table, extra)?);
     Ok(())
   }
 
@@ -1024,7 +1389,127 @@ impl Handler for Thread {
     self.print_stack();
     vprintln!("make_table_empty");
 
-    self.acc = Value::object(self.global.alloc(Table::new()));
+    self.acc = Value::object(self.global.try_alloc(Table::new(), 0)?);
+    Ok(())
+  }
+
+  fn op_concat(&mut self, start: op::Register, count: op::Count) -> Result<()> {
+    self.print_stack();
+    vprintln!("concat {start}, {count}");
+
+    use std::fmt::Write;
+    let mut str = String::new();
+    for reg in start.iter(count, 1) {
+      let value = stringify(self.get_empty_scope(), self.get_register(reg))?;
+      write!(&mut str, "{value}").map_err(Error::user)?;
+    }
+    let extra = str.len();
+    self.acc = Value::object(self.global.try_alloc(Str::owned(str), extra)?);
+    Ok(())
+  }
+
+  fn op_check_len(&mut self, len: op::Count) -> Result<()> {
+    self.print_stack();
+    vprintln!("check_len {len}");
+
+    let value = self.acc.clone();
+    let actual = if let Some(list) = value.clone().to_object::<List>() {
+      list.len()
+    } else if let Some(tuple) = value.clone().to_object::<Tuple>() {
+      tuple.len()
+    } else {
+      fail!("cannot destructure `{value}`, expected a list or tuple");
+    };
+    let expected = len.value();
+    if actual != expected {
+      fail!("expected {expected} values to destructure, got {actual}");
+    }
+    Ok(())
+  }
+
+  fn op_append(&mut self, list: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("append {list}");
+
+    let list = self.get_register(list);
+    let value = take(&mut self.acc);
+
+    let Some(list) = list.clone().to_object::<List>() else {
+      fail!("cannot append to `{list}`, expected a list");
+    };
+    list.reserve(List::ELEMENT_SIZE)?;
+    list.push(value);
+    Ok(())
+  }
+
+  fn op_extend(&mut self, list: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("extend {list}");
+
+    let list = self.get_register(list);
+    let value = take(&mut self.acc);
+
+    let Some(list) = list.clone().to_object::<List>() else {
+      fail!("cannot extend `{list}`, expected a list");
+    };
+    let items = if let Some(other) = value.clone().to_object::<List>() {
+      other.iter().collect::<Vec<_>>()
+    } else if let Some(other) = value.clone().to_object::<Tuple>() {
+      other.iter().collect::<Vec<_>>()
+    } else {
+      fail!("cannot spread `{value}`, expected a list or tuple");
+    };
+    list.reserve(items.len() * List::ELEMENT_SIZE)?;
+    for item in items {
+      list.push(item);
+    }
+    Ok(())
+  }
+
+  fn op_match_seq(&mut self, len: op::Count) -> Result<()> {
+    self.print_stack();
+    vprintln!("match_seq {len}");
+
+    let value = take(&mut self.acc);
+    let actual = if let Some(list) = value.clone().to_object::<List>() {
+      Some(list.len())
+    } else {
+      value.to_object::<Tuple>().map(|tuple| tuple.len())
+    };
+    self.acc = Value::bool(actual == Some(len.value()));
+    Ok(())
+  }
+
+  fn op_match_table(&mut self) -> Result<()> {
+    self.print_stack();
+    vprintln!("match_table");
+
+    let value = take(&mut self.acc);
+    self.acc = Value::bool(value.to_object::<Table>().is_some());
+    Ok(())
+  }
+
+  fn op_match_key(&mut self, obj: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("match_key {obj}");
+
+    let object = self.get_register(obj);
+    let key = take(&mut self.acc);
+    let has_key = match (object.to_object::<Table>(), key.to_object::<Str>()) {
+      (Some(table), Some(key)) => table.get(key.as_str()).is_some(),
+      _ => false,
+    };
+    self.acc = Value::bool(has_key);
+    Ok(())
+  }
+
+  fn op_match_payload(&mut self, len: op::Count) -> Result<()> {
+    self.print_stack();
+    vprintln!("match_payload {len}");
+
+    let value = take(&mut self.acc);
+    let actual = value.to_object::<EnumValue>().map(|value| value.len());
+    self.acc = Value::bool(actual == Some(len.value()));
     Ok(())
   }
 
@@ -1049,6 +1534,8 @@ impl Handler for Thread {
     self.print_stack();
     vprintln!("jump_loop {offset}");
 
+    self.global.step()?;
+
     Ok(offset)
   }
 
@@ -1076,14 +1563,50 @@ impl Handler for Thread {
     }
   }
 
+  fn op_push_handler(&mut self, pc: usize, offset: op::Offset) -> Result<()> {
+    self.print_stack();
+    vprintln!("push_handler {offset}");
+
+    self.push_handler(pc + offset.value());
+    Ok(())
+  }
+
+  fn op_push_handler_const(&mut self, pc: usize, idx: op::Constant) -> Result<()> {
+    self.print_stack();
+    vprintln!("push_handler_const {idx}");
+
+    let offset = self.get_constant(idx).as_offset().cloned();
+    debug_assert!(offset.is_some());
+    let offset = unsafe { offset.unwrap_unchecked() };
+    self.push_handler(pc + offset.value());
+    Ok(())
+  }
+
+  fn op_pop_handler(&mut self) -> Result<()> {
+    self.print_stack();
+    vprintln!("pop_handler");
+
+    let stack = unsafe { self.stack.as_mut() };
+    debug_assert!(matches!(
+      stack.handlers.last(),
+      Some(handler) if handler.frame_index + 1 == stack.frames.len()
+    ));
+    stack.handlers.pop();
+    Ok(())
+  }
+
   fn op_add(&mut self, lhs: op::Register) -> Result<()> {
     self.print_stack();
     vprintln!("add {lhs}");
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__add__", "`+`")? {
+      self.acc = value;
+      return Ok(());
+    }
     let value = binary!(lhs + rhs {
-      i32 => Value::int(lhs + rhs),
+      int => int::add(&self.global, &lhs, &rhs)?,
       f64 => Value::float(lhs + rhs),
       any => lhs.add(self.get_empty_scope(), rhs)?,
     });
@@ -1097,8 +1620,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__sub__", "`-`")? {
+      self.acc = value;
+      return Ok(());
+    }
     let value = binary!(lhs - rhs {
-      i32 => Value::int(lhs - rhs),
+      int => int::sub(&self.global, &lhs, &rhs)?,
       f64 => Value::float(lhs - rhs),
       any => lhs.subtract(self.get_empty_scope(), rhs)?,
     });
@@ -1112,8 +1639,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__mul__", "`*`")? {
+      self.acc = value;
+      return Ok(());
+    }
     let value = binary!(lhs * rhs {
-      i32 => Value::int(lhs * rhs),
+      int => int::mul(&self.global, &lhs, &rhs)?,
       f64 => Value::float(lhs * rhs),
       any => lhs.multiply(self.get_empty_scope(), rhs)?,
     });
@@ -1127,10 +1658,14 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__div__", "`/`")? {
+      self.acc = value;
+      return Ok(());
+    }
     let value = binary!(lhs / rhs {
-      i32 => {
-        if rhs != 0 {
-          Value::float(lhs as f64 / rhs as f64)
+      int => {
+        if !int::is_zero(&rhs) {
+          Value::float(int::to_f64(&lhs).unwrap() / int::to_f64(&rhs).unwrap())
         } else {
           fail!("cannot divide int by zero")
         }
@@ -1148,10 +1683,14 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__rem__", "`%`")? {
+      self.acc = value;
+      return Ok(());
+    }
     let value = binary!(lhs % rhs {
-      i32 => {
-        if rhs != 0 {
-          Value::float(lhs as f64 % rhs as f64)
+      int => {
+        if !int::is_zero(&rhs) {
+          int::rem(&self.global, &lhs, &rhs)?
         } else {
           fail!("cannot divide int by zero")
         }
@@ -1169,8 +1708,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__pow__", "`**`")? {
+      self.acc = value;
+      return Ok(());
+    }
     let value = binary!(lhs ** rhs {
-      i32 => Value::float((lhs as f64).powf(rhs as f64)),
+      int => Value::float(int::to_f64(&lhs).unwrap().powf(int::to_f64(&rhs).unwrap())),
       f64 => Value::float(lhs.powf(rhs)),
       any => lhs.pow(self.get_empty_scope(), rhs)?,
     });
@@ -1178,14 +1721,136 @@ impl Handler for Thread {
     Ok(())
   }
 
+  fn op_floor_div(&mut self, lhs: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("floor_div {lhs}");
+
+    let lhs = self.get_register(lhs);
+    let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__floordiv__", "`//`")? {
+      self.acc = value;
+      return Ok(());
+    }
+    let value = binary!(lhs, rhs {
+      int => {
+        if !int::is_zero(&rhs) {
+          int::floor_div(&self.global, &lhs, &rhs)?
+        } else {
+          fail!("cannot divide int by zero")
+        }
+      },
+      f64 => Value::float((lhs / rhs).floor()),
+      any => lhs.floor_divide(self.get_empty_scope(), rhs)?,
+      bool => fail!("cannot `//` `bool`"),
+      none => fail!("cannot `//` `none`"),
+      incompatible_types => fail!("operands must have the same type: `{}`, `{}`", lhs, rhs),
+    });
+    self.acc = value;
+    Ok(())
+  }
+
+  fn op_bit_and(&mut self, lhs: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("bit_and {lhs}");
+
+    let lhs = self.get_register(lhs);
+    let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__and__", "`&`")? {
+      self.acc = value;
+      return Ok(());
+    }
+    let value = binary!(lhs & rhs {
+      int => int::bit_and(&self.global, &lhs, &rhs)?,
+      f64 => fail!("cannot `&` `float`"),
+      any => lhs.bit_and(self.get_empty_scope(), rhs)?,
+    });
+    self.acc = value;
+    Ok(())
+  }
+
+  fn op_bit_or(&mut self, lhs: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("bit_or {lhs}");
+
+    let lhs = self.get_register(lhs);
+    let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__or__", "`|`")? {
+      self.acc = value;
+      return Ok(());
+    }
+    let value = binary!(lhs | rhs {
+      int => int::bit_or(&self.global, &lhs, &rhs)?,
+      f64 => fail!("cannot `|` `float`"),
+      any => lhs.bit_or(self.get_empty_scope(), rhs)?,
+    });
+    self.acc = value;
+    Ok(())
+  }
+
+  fn op_bit_xor(&mut self, lhs: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("bit_xor {lhs}");
+
+    let lhs = self.get_register(lhs);
+    let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__xor__", "`^`")? {
+      self.acc = value;
+      return Ok(());
+    }
+    let value = binary!(lhs ^ rhs {
+      int => int::bit_xor(&self.global, &lhs, &rhs)?,
+      f64 => fail!("cannot `^` `float`"),
+      any => lhs.bit_xor(self.get_empty_scope(), rhs)?,
+    });
+    self.acc = value;
+    Ok(())
+  }
+
+  fn op_shl(&mut self, lhs: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("shl {lhs}");
+
+    let lhs = self.get_register(lhs);
+    let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__lshift__", "`<<`")? {
+      self.acc = value;
+      return Ok(());
+    }
+    let value = binary!(lhs << rhs {
+      int => int::shl(&self.global, &lhs, &rhs)?,
+      f64 => fail!("cannot `<<` `float`"),
+      any => lhs.shift_left(self.get_empty_scope(), rhs)?,
+    });
+    self.acc = value;
+    Ok(())
+  }
+
+  fn op_shr(&mut self, lhs: op::Register) -> Result<()> {
+    self.print_stack();
+    vprintln!("shr {lhs}");
+
+    let lhs = self.get_register(lhs);
+    let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__rshift__", "`>>`")? {
+      self.acc = value;
+      return Ok(());
+    }
+    let value = binary!(lhs >> rhs {
+      int => int::shr(&self.global, &lhs, &rhs)?,
+      f64 => fail!("cannot `>>` `float`"),
+      any => lhs.shift_right(self.get_empty_scope(), rhs)?,
+    });
+    self.acc = value;
+    Ok(())
+  }
+
   fn op_inv(&mut self) -> Result<()> {
     self.print_stack();
     vprintln!("inv");
 
     let value = take(&mut self.acc);
     let value = if value.is_int() {
-      let value = unsafe { value.to_int_unchecked() };
-      Value::int(-value)
+      int::neg(&self.global, &value)?
     } else if value.is_float() {
       let value = unsafe { value.to_float_unchecked() };
       Value::float(-value)
@@ -1212,6 +1877,29 @@ impl Handler for Thread {
     Ok(())
   }
 
+  fn op_bit_not(&mut self) -> Result<()> {
+    self.print_stack();
+    vprintln!("bit_not");
+
+    let value = take(&mut self.acc);
+    let value = if int::is_int(&value) {
+      int::bit_not(&self.global, &value)?
+    } else if value.is_float() {
+      fail!("cannot `~` `float`")
+    } else if value.is_bool() {
+      fail!("cannot `~` `bool`")
+    } else if value.is_none() {
+      fail!("cannot `~` `none`")
+    } else if value.is_object() {
+      let value = unsafe { value.to_any_unchecked() };
+      value.bit_not(self.get_empty_scope())?
+    } else {
+      unreachable!()
+    };
+    self.acc = value;
+    Ok(())
+  }
+
   fn op_cmp_eq(&mut self, lhs: op::Register) -> Result<()> {
     self.print_stack();
     vprintln!("cmp_eq {lhs}");
@@ -1240,8 +1928,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__gt__", "`>`")? {
+      self.acc = Value::bool(is_truthy(value));
+      return Ok(());
+    }
     let value = binary!(lhs > rhs {
-      i32 => Value::bool(lhs > rhs),
+      int => Value::bool(int::cmp(&lhs, &rhs).is_gt()),
       f64 => Value::bool(lhs > rhs),
       any => Value::bool(matches!(lhs.cmp(self.get_empty_scope(), rhs)?, Ordering::Greater)),
     });
@@ -1255,8 +1947,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__ge__", "`>=`")? {
+      self.acc = Value::bool(is_truthy(value));
+      return Ok(());
+    }
     let value = binary!(lhs >= rhs {
-      i32 => Value::bool(lhs >= rhs),
+      int => Value::bool(int::cmp(&lhs, &rhs).is_ge()),
       f64 => Value::bool(lhs >= rhs),
       any => Value::bool(matches!(lhs.cmp(self.get_empty_scope(), rhs)?, Ordering::Greater | Ordering::Equal)),
     });
@@ -1270,8 +1966,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__lt__", "`<`")? {
+      self.acc = Value::bool(is_truthy(value));
+      return Ok(());
+    }
     let value = binary!(lhs < rhs {
-      i32 => Value::bool(lhs < rhs),
+      int => Value::bool(int::cmp(&lhs, &rhs).is_lt()),
       f64 => Value::bool(lhs < rhs),
       any => Value::bool(matches!(lhs.cmp(self.get_empty_scope(), rhs)?, Ordering::Less)),
     });
@@ -1285,8 +1985,12 @@ impl Handler for Thread {
 
     let lhs = self.get_register(lhs);
     let rhs = take(&mut self.acc);
+    if let Some(value) = self.class_operator(&lhs, &rhs, "__le__", "`<=`")? {
+      self.acc = Value::bool(is_truthy(value));
+      return Ok(());
+    }
     let value = binary!(lhs <= rhs {
-      i32 => Value::bool(lhs <= rhs),
+      int => Value::bool(int::cmp(&lhs, &rhs).is_le()),
       f64 => Value::bool(lhs <= rhs),
       any => Value::bool(matches!(lhs.cmp(self.get_empty_scope(), rhs)?, Ordering::Less | Ordering::Equal)),
     });
@@ -1306,7 +2010,7 @@ impl Handler for Thread {
 
       lhs.instance_of(rhs)?
     } else {
-      (lhs.is_int() && rhs.is_int())
+      (int::is_int(&lhs) && int::is_int(&rhs))
         || (lhs.is_float() && rhs.is_float())
         || (lhs.is_bool() && rhs.is_bool())
         || (lhs.is_none() && rhs.is_none())
@@ -1346,8 +2050,10 @@ impl Handler for Thread {
     self.print_stack();
     vprintln!("print");
 
+    let value = take(&mut self.acc);
+    let value = stringify(self.get_empty_scope(), value)?;
     let mut output = self.global.io().output.borrow_mut();
-    writeln!(&mut output, "{}", take(&mut self.acc)).map_err(Error::user)?;
+    writeln!(&mut output, "{value}").map_err(Error::user)?;
     Ok(())
   }
 
@@ -1357,8 +2063,13 @@ impl Handler for Thread {
 
     debug_assert!(self.stack_base() + start.index() + count.value() <= stack!(self).len());
 
+    // `stringify` may call `__str__`, which can grow the stack, so each
+    // register is read separately instead of through a borrowed slice
+    let values = start
+      .iter(count, 1)
+      .map(|reg| stringify(self.get_empty_scope(), self.get_register(reg)))
+      .collect::<Result<Vec<_>>>()?;
     let mut output = self.global.io().output.borrow_mut();
-    let values = stack!(self)[start.index()..start.index() + count.value()].iter();
     writeln!(&mut output, "{}", values.join(" ")).map_err(Error::user)?;
 
     Ok(())
@@ -1378,7 +2089,7 @@ impl Handler for Thread {
       fail!("`{function}` is not callable");
     };
 
-    self.do_call(function, args, return_addr)
+    self.do_call(function, args, None, return_addr)
   }
 
   fn op_call0(&mut self, return_addr: usize) -> Result<Call> {
@@ -1395,7 +2106,61 @@ impl Handler for Thread {
       fail!("`{function}` is not callable");
     };
 
-    self.do_call(function, args, return_addr)
+    self.do_call(function, args, None, return_addr)
+  }
+
+  fn op_call_spread(&mut self, return_addr: usize, callee: op::Register) -> Result<Call> {
+    self.print_stack();
+    vprintln!("call_spread {callee} (ret={return_addr})");
+
+    let function = self.get_register(callee);
+    let args = take(&mut self.acc);
+
+    let Some(function) = function.clone().to_any() else {
+      fail!("`{function}` is not callable");
+    };
+    let Some(args) = args.clone().to_object::<List>() else {
+      fail!("cannot spread `{args}`, expected a list");
+    };
+
+    self.call_with_args(function, args.iter().collect(), None, return_addr)
+  }
+
+  fn op_call_named(
+    &mut self,
+    return_addr: usize,
+    callee: op::Register,
+    named: op::Register,
+  ) -> Result<Call> {
+    self.print_stack();
+    vprintln!("call_named {callee}, {named} (ret={return_addr})");
+
+    let function = self.get_register(callee);
+    let named = self.get_register(named);
+    let args = take(&mut self.acc);
+
+    let Some(function) = function.clone().to_any() else {
+      fail!("`{function}` is not callable");
+    };
+    let Some(args) = args.clone().to_object::<List>() else {
+      fail!("cannot spread `{args}`, expected a list");
+    };
+    let Some(named) = named.clone().to_object::<Table>() else {
+      fail!("expected named arguments to be a table, got `{named}`");
+    };
+
+    // script functions receive named arguments in the position of the
+    // param with the same name, natives retrieve them from their scope
+    let mut args = args.iter().collect::<Vec<_>>();
+    let named = match callee_descriptor(&function) {
+      Some((descriptor, has_implicit_receiver)) => {
+        bind_named_args(&descriptor, has_implicit_receiver, &mut args, &named)?;
+        None
+      }
+      None => Some(named),
+    };
+
+    self.call_with_args(function, args, named, return_addr)
   }
 
   fn op_import(&mut self, path: op::Constant, return_addr: usize) -> Result<Call> {
@@ -1433,24 +2198,78 @@ impl Handler for Thread {
 
     // truncate stack
     stack.regs.truncate(frame.stack_base);
+    stack.truncate_handlers();
 
-    if let Some(current_frame) = stack.frames.last() {
-      if let Some(return_addr) = frame.return_addr {
-        self.pc = return_addr;
-        return Ok(Return::LoadFrame(LoadFrame {
-          bytecode: current_frame.instructions,
-          pc: self.pc,
-        }));
-      }
+    // the return value of a generator is discarded
+    if let Some((generator, resume)) = &frame.generator {
+      self.acc = generator.finish(*resume);
     }
 
-    Ok(Return::Yield)
+    Ok(self.return_to_caller(&frame))
   }
 
-  fn op_yield(&mut self) -> Result<()> {
+  fn op_yield(&mut self, resume_addr: usize) -> Result<Return> {
     self.print_stack();
     vprintln!("yield");
 
-    todo!()
+    // yielded value is in the accumulator
+
+    let Some((generator, resume)) = current_call_frame!(self).generator.clone() else {
+      fail!("`yield` outside of a generator");
+    };
+
+    let stack = unsafe { self.stack.as_mut() };
+
+    // pop frame, moving its registers and handlers into the generator
+    let frame = unsafe { stack.frames.pop().unwrap_unchecked() };
+    let regs = stack.regs.split_off(frame.stack_base);
+    let frame_index = stack.frames.len();
+    let start = stack
+      .handlers
+      .iter()
+      .rposition(|handler| handler.frame_index < frame_index)
+      .map(|index| index + 1)
+      .unwrap_or(0);
+    let handlers = stack
+      .handlers
+      .drain(start..)
+      .map(|handler| handler.catch_addr)
+      .collect();
+
+    let value = take(&mut self.acc);
+    self.acc = generator.suspend(resume, regs, resume_addr, handlers, value);
+
+    Ok(self.return_to_caller(&frame))
+  }
+
+  fn op_throw(&mut self) -> Result<()> {
+    self.print_stack();
+    vprintln!("throw");
+
+    let value = take(&mut self.acc);
+    if let Some(error) = value.clone().to_object::<RuntimeError>() {
+      // re-raise the original error if it hasn't been re-raised yet
+      if let Some(error) = error.take() {
+        return Err(error);
+      }
+    }
+
+    let message = match value.clone().to_any() {
+      Some(object) => object
+        .named_field_opt(self.get_empty_scope(), self.global.intern("message"))
+        .ok()
+        .flatten(),
+      None => None,
+    };
+    let message = match message {
+      Some(message) if !message.is_none() => message.to_string(),
+      _ => value.to_string(),
+    };
+    let id = self.global.store_thrown(value);
+    Err(Error::Thrown(Thrown::new(id, message)))
+  }
+
+  fn on_error(&mut self, pc: usize, error: Error) -> Error {
+    self.trace(error, pc)
   }
 }
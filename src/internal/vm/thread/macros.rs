@@ -52,12 +52,12 @@ macro_rules! current_call_frame_mut {
 
 macro_rules! binary {
   ($lhs:ident ** $rhs:ident {
-    i32 => $i32_expr:expr,
+    int => $int_expr:expr,
     f64 => $f64_expr:expr,
     any => $any_expr:expr,
   }) => {{
     binary!($lhs, $rhs {
-      i32 => $i32_expr,
+      int => $int_expr,
       f64 => $f64_expr,
       any => $any_expr,
       bool => fail!("cannot `**` `bool`"),
@@ -66,12 +66,12 @@ macro_rules! binary {
     })
   }};
   ($lhs:ident $op:tt $rhs:ident {
-    i32 => $i32_expr:expr,
+    int => $int_expr:expr,
     f64 => $f64_expr:expr,
     any => $any_expr:expr,
   }) => {{
     binary!($lhs, $rhs {
-      i32 => $i32_expr,
+      int => $int_expr,
       f64 => $f64_expr,
       any => $any_expr,
       bool => fail!("cannot `{}` `bool`", stringify!($op)),
@@ -80,27 +80,32 @@ macro_rules! binary {
     })
   }};
   ($lhs:ident, $rhs:ident {
-    i32 => $i32_expr:expr,
+    int => $int_expr:expr,
     f64 => $f64_expr:expr,
     any => $any_expr:expr,
     bool => $bool_expr:expr,
     none => $none_expr:expr,
     incompatible_types => $on_different_object_types_expr:expr,
   }) => {{
-    if $lhs.is_int() && $rhs.is_int() {
-      let $lhs = unsafe { $lhs.to_int_unchecked() };
-      let $rhs = unsafe { $rhs.to_int_unchecked() };
-      $i32_expr
+    use $crate::internal::object::int;
+    if int::is_int(&$lhs) && int::is_int(&$rhs) {
+      $int_expr
     } else if $lhs.is_float() && $rhs.is_float() {
+      #[allow(unused_variables)]
       let $lhs = unsafe { $lhs.to_float_unchecked() };
+      #[allow(unused_variables)]
       let $rhs = unsafe { $rhs.to_float_unchecked() };
       $f64_expr
-    } else if $lhs.is_float() && $rhs.is_int() {
+    } else if $lhs.is_float() && int::is_int(&$rhs) {
+      #[allow(unused_variables)]
       let $lhs = unsafe { $lhs.to_float_unchecked() };
-      let $rhs = unsafe { $rhs.to_int_unchecked() } as f64;
+      #[allow(unused_variables)]
+      let $rhs = unsafe { int::to_f64(&$rhs).unwrap_unchecked() };
       $f64_expr
-    } else if $lhs.is_int() && $rhs.is_float() {
-      let $lhs = unsafe { $lhs.to_int_unchecked() } as f64;
+    } else if int::is_int(&$lhs) && $rhs.is_float() {
+      #[allow(unused_variables)]
+      let $lhs = unsafe { int::to_f64(&$lhs).unwrap_unchecked() };
+      #[allow(unused_variables)]
       let $rhs = unsafe { $rhs.to_float_unchecked() };
       $f64_expr
     } else if $lhs.is_bool() && $rhs.is_bool() {
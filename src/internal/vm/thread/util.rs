@@ -1,4 +1,6 @@
 use super::*;
+use crate::internal::object::native::NativeClassInstance;
+use crate::internal::object::BoundFunction;
 
 pub fn is_truthy(value: Value) -> bool {
   if value.is_bool() {
@@ -22,6 +24,25 @@ pub fn is_truthy(value: Value) -> bool {
   true
 }
 
+/// Format `value` for display, using the `__str__` method of class instances
+/// and the display hook of native class instances if they have one.
+pub fn stringify(scope: Scope<'_>, value: Value) -> Result<String> {
+  let str = if let Some(this) = value.clone().to_object::<ClassInstance>() {
+    ClassInstance::call_method(scope, this, "__str__", &[])?
+  } else if let Some(this) = value.clone().to_object::<NativeClassInstance>() {
+    NativeClassInstance::call_hook(scope, this, "__str__", &[])?
+  } else {
+    None
+  };
+  match str {
+    Some(str) => match str.clone().to_object::<Str>() {
+      Some(str) => Ok(str.as_str().to_string()),
+      None => fail!("`__str__` must return a string, got `{str}`"),
+    },
+    None => Ok(value.to_string()),
+  }
+}
+
 pub fn clone_from_raw_slice<T: Clone>(ptr: *mut [T], index: usize) -> T {
   #[allow(dead_code)]
   struct Components<T> {
@@ -42,6 +63,14 @@ pub fn check_args(params: &Params, has_implicit_receiver: bool, num_args: usize)
   let min = params.min as usize + has_explicit_self_param as usize;
   let max = params.max as usize + has_explicit_self_param as usize;
 
+  if params.has_rest {
+    if num_args < min {
+      let plural = if min != 1 { "s" } else { "" };
+      fail!("expected at least {min} arg{plural}, got {num_args}")
+    }
+    return Ok(());
+  }
+
   if min > num_args || num_args > max {
     if min == max {
       let plural = if min != 1 { "s" } else { "" };
@@ -57,3 +86,100 @@ pub fn check_args(params: &Params, has_implicit_receiver: bool, num_args: usize)
 
   Ok(())
 }
+
+/// Split off the arguments which do not fit into the positional params, and
+/// collect them into a list for the rest param.
+///
+/// If the function has no rest param, `args` is returned unchanged.
+pub fn split_rest_args(
+  thread: &Thread,
+  params: &Params,
+  has_implicit_receiver: bool,
+  args: Args,
+) -> Result<(Args, Option<Value>)> {
+  if !params.has_rest {
+    return Ok((args, None));
+  }
+
+  let has_explicit_self_param = params.has_self && !has_implicit_receiver;
+  let count = args
+    .count
+    .min(params.max as usize + has_explicit_self_param as usize);
+
+  let stack = unsafe { thread.stack.as_ref() };
+  let rest = stack.regs[args.start + count..args.start + args.count].to_vec();
+  let extra = rest.len() * List::ELEMENT_SIZE;
+  let rest = thread.global.try_alloc(List::from(rest), extra)?;
+
+  Ok((
+    Args {
+      start: args.start,
+      count,
+    },
+    Some(Value::object(rest)),
+  ))
+}
+
+/// Find the script function which runs when `callee` is called, and whether
+/// its receiver is passed implicitly.
+pub fn callee_descriptor(callee: &Ptr<Any>) -> Option<(Ptr<FunctionDescriptor>, bool)> {
+  if let Some(function) = callee.clone_cast::<Function>() {
+    return Some((function.descriptor.clone(), false));
+  }
+  if let Some(function) = callee.clone_cast::<BoundFunction>() {
+    return Some((function.function().descriptor.clone(), true));
+  }
+  let init = if let Some(class) = callee.clone_cast::<ClassType>() {
+    class.init.clone()
+  } else if let Some(proxy) = callee.clone_cast::<ClassProxy>() {
+    proxy.class.init.clone()
+  } else {
+    None
+  };
+  init.map(|init| (init.descriptor.clone(), true))
+}
+
+/// Move named arguments into the positions of the params with the same
+/// names. Params between the positional and named arguments are passed
+/// `none`, so that their default value is used.
+pub fn bind_named_args(
+  descriptor: &FunctionDescriptor,
+  has_implicit_receiver: bool,
+  args: &mut Vec<Value>,
+  named: &Table,
+) -> Result<()> {
+  let offset = (descriptor.params.has_self && !has_implicit_receiver) as usize;
+  let num_positional = args.len();
+
+  let mut is_named = vec![false; descriptor.param_names.len()];
+  for (key, value) in named.entries() {
+    let Some(index) = descriptor
+      .param_names
+      .iter()
+      .position(|name| key.as_str() == Some(name.as_str()))
+    else {
+      fail!("`{}` has no param named `{key}`", descriptor.name);
+    };
+    if offset + index < num_positional {
+      fail!("param `{key}` was passed both by position and by name");
+    }
+    if args.len() <= offset + index {
+      args.resize_with(offset + index + 1, Value::none);
+    }
+    args[offset + index] = value;
+    is_named[index] = true;
+  }
+
+  for (index, name) in descriptor
+    .param_names
+    .iter()
+    .enumerate()
+    .take(descriptor.params.min as usize)
+  {
+    if offset + index >= num_positional && !is_named[index] {
+      fail!("missing argument `{name}`");
+    }
+  }
+
+  Ok(())
+}
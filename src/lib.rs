@@ -34,5 +34,5 @@ pub mod prelude {
   pub use super::serde::ValueDeserializer;
 }
 
-pub use internal::error::{Error, Result};
+pub use internal::error::{Abort, Backtrace, BacktraceFrame, Error, Result, Thrown};
 pub use public::*;
@@ -9,6 +9,9 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use futures_util::TryFutureExt;
 
@@ -30,12 +33,18 @@ pub mod object;
 pub mod value;
 
 pub use crate::fail;
+pub use crate::internal::object::gc::GcStats;
 pub use crate::internal::object::module::ModuleLoader;
 pub use crate::internal::object::native::LocalBoxFuture;
 pub use crate::public::module::NativeModule;
+pub use crate::public::object::bytes::Bytes;
+pub use crate::public::object::enum_::EnumValue;
+pub use crate::public::object::generator::Generator;
 pub use crate::public::object::list::List;
+pub use crate::public::object::set::Set;
 pub use crate::public::object::string::Str;
 pub use crate::public::object::table::Table;
+pub use crate::public::object::tuple::Tuple;
 pub use crate::public::object::Any;
 pub use crate::public::value::{FromValue, IntoValue, Value};
 
@@ -89,6 +98,9 @@ pub struct HebiBuilder<M, I, O> {
   module_loader: Option<Box<dyn crate::internal::object::module::ModuleLoader>>,
   input: Option<Box<dyn crate::internal::vm::global::Input>>,
   output: Option<Box<dyn crate::internal::vm::global::Output>>,
+  timeout: Option<Duration>,
+  fuel: Option<u64>,
+  memory_limit: Option<usize>,
   __: PhantomData<(M, I, O)>,
 }
 
@@ -104,6 +116,9 @@ impl<I, O> HebiBuilder<(), I, O> {
       module_loader: Some(Box::new(module_loader)),
       input: self.input,
       output: self.output,
+      timeout: self.timeout,
+      fuel: self.fuel,
+      memory_limit: self.memory_limit,
       __: PhantomData,
     }
   }
@@ -118,6 +133,9 @@ impl<M, O> HebiBuilder<M, (), O> {
       module_loader: self.module_loader,
       input: Some(Box::new(input)),
       output: self.output,
+      timeout: self.timeout,
+      fuel: self.fuel,
+      memory_limit: self.memory_limit,
       __: PhantomData,
     }
   }
@@ -132,18 +150,64 @@ impl<M, I> HebiBuilder<M, I, ()> {
       module_loader: self.module_loader,
       input: self.input,
       output: Some(Box::new(output)),
+      timeout: self.timeout,
+      fuel: self.fuel,
+      memory_limit: self.memory_limit,
       __: PhantomData,
     }
   }
 }
 
 impl<M, I, O> HebiBuilder<M, I, O> {
+  /// Abort any single call to `eval`, `run`, or a script function which
+  /// takes longer than `timeout` to finish.
+  ///
+  /// The deadline is only checked on loop iterations and calls, so time
+  /// spent inside a native function is not interrupted.
+  pub fn timeout(self, timeout: Duration) -> Self {
+    HebiBuilder {
+      timeout: Some(timeout),
+      ..self
+    }
+  }
+
+  /// Limit the amount of work scripts may perform.
+  ///
+  /// Every loop iteration and call consumes one unit of fuel, and native
+  /// functions may consume more using [`Scope::consume_fuel`]. Once the
+  /// fuel runs out, execution is aborted with [`Abort::OutOfFuel`]. The
+  /// budget is shared by all scripts run by the instance, and may be
+  /// refilled using [`Hebi::set_fuel`].
+  ///
+  /// [`Abort::OutOfFuel`]: crate::Abort::OutOfFuel
+  pub fn fuel(self, fuel: u64) -> Self {
+    HebiBuilder {
+      fuel: Some(fuel),
+      ..self
+    }
+  }
+
+  /// Limit the number of bytes which may be used by objects allocated by
+  /// scripts.
+  ///
+  /// An allocation which would exceed the limit fails with a runtime error,
+  /// which the script may catch.
+  pub fn memory_limit(self, bytes: usize) -> Self {
+    HebiBuilder {
+      memory_limit: Some(bytes),
+      ..self
+    }
+  }
+
   pub fn finish(self) -> Hebi {
     Hebi {
       vm: Vm::with_config(Config {
         module_loader: self.module_loader,
         input: self.input,
         output: self.output,
+        timeout: self.timeout,
+        fuel: self.fuel,
+        memory_limit: self.memory_limit,
       }),
     }
   }
@@ -159,6 +223,9 @@ impl Hebi {
       module_loader: None,
       input: None,
       output: None,
+      timeout: None,
+      fuel: None,
+      memory_limit: None,
       __: PhantomData,
     }
   }
@@ -210,6 +277,58 @@ impl Hebi {
   pub fn register(&mut self, module: &NativeModule) {
     self.vm.register(module)
   }
+
+  /// The amount of fuel left, or `None` if fuel is unlimited.
+  pub fn fuel(&self) -> Option<u64> {
+    self.vm.global.fuel()
+  }
+
+  /// Refill the fuel budget, or remove it by passing `None`.
+  pub fn set_fuel(&mut self, fuel: Option<u64>) {
+    self.vm.global.set_fuel(fuel)
+  }
+
+  /// The number of bytes used by live objects.
+  pub fn memory_usage(&self) -> usize {
+    self.vm.global.memory_usage()
+  }
+
+  /// Free every object which is only kept alive by reference cycles, and
+  /// return statistics about this collection.
+  ///
+  /// Collections also happen automatically while scripts are running, once
+  /// enough memory has been allocated since the previous one.
+  pub fn collect_garbage(&mut self) -> GcStats {
+    self.vm.global.collect_garbage()
+  }
+
+  /// Statistics about every collection performed so far.
+  pub fn gc_stats(&self) -> GcStats {
+    self.vm.global.gc_stats()
+  }
+
+  /// Obtain a handle which may be used to abort the running script from
+  /// another thread.
+  pub fn interrupt_handle(&self) -> InterruptHandle {
+    InterruptHandle {
+      flag: self.vm.global.interrupt_flag(),
+    }
+  }
+}
+
+/// A handle used to abort execution of a [`Hebi`] instance.
+///
+/// The script stops with [`Error::Aborted`] at the next loop iteration or
+/// call. If no script is running, the interrupt is delivered to the next one.
+#[derive(Clone, Debug)]
+pub struct InterruptHandle {
+  flag: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+  pub fn interrupt(&self) {
+    self.flag.store(true, Ordering::Relaxed);
+  }
 }
 
 impl Debug for Hebi {
@@ -283,10 +402,10 @@ impl<'a, 'cx> Iterator for GlobalEntries<'a, 'cx> {
   type Item = (Str<'cx>, Value<'cx>);
 
   fn next(&mut self) -> Option<Self::Item> {
-    self
-      .entries
-      .next()
-      .map(|(key, value)| unsafe { (key.bind_raw::<'cx>(), value.bind_raw::<'cx>()) })
+    // globals are always keyed by name
+    let (key, value) = self.entries.next()?;
+    let key = key.to_str().expect("global key is not a string");
+    Some(unsafe { (key.bind_raw::<'cx>(), value.bind_raw::<'cx>()) })
   }
 }
 
@@ -295,6 +414,8 @@ pub struct Scope<'cx> {
   pub(crate) thread: Thread,
   pub(crate) stack_base: usize,
   pub(crate) args: Args,
+  /// Arguments passed by name, if there were any.
+  pub(crate) named: Option<Ptr<table::Table>>,
   pub(crate) lifetime: PhantomData<&'cx ()>,
 }
 
@@ -306,6 +427,7 @@ impl<'cx> Scope<'cx> {
       thread,
       stack_base,
       args,
+      named: None,
       lifetime: PhantomData,
     }
   }
@@ -314,6 +436,12 @@ impl<'cx> Scope<'cx> {
     self.thread.global.alloc(v)
   }
 
+  /// Allocate `v` along with `extra` bytes of out-of-line storage, failing if
+  /// that would exceed the memory limit.
+  pub(crate) fn try_alloc<T: Type>(&self, v: T, extra: usize) -> Result<Ptr<T>> {
+    self.thread.global.try_alloc(v, extra)
+  }
+
   pub(crate) fn intern(
     &self,
     s: impl Into<Cow<'static, str>>,
@@ -328,6 +456,15 @@ impl<'cx> Scope<'cx> {
     }
   }
 
+  /// Charge the running script `amount` units of fuel, for example to
+  /// account for expensive work done by a native function.
+  ///
+  /// Fails with [`Abort::OutOfFuel`][crate::Abort::OutOfFuel] if there is
+  /// not enough fuel left.
+  pub fn consume_fuel(&self, amount: u64) -> Result<()> {
+    self.thread.global.consume_fuel(amount)
+  }
+
   pub fn num_args(&self) -> usize {
     self.args.count
   }
@@ -351,6 +488,22 @@ impl<'cx> Scope<'cx> {
     T::from_value(value, self.global())
   }
 
+  /// Retrieve the argument passed as `name = value`, or `None` if there is
+  /// no such argument.
+  pub fn named_param<T: FromValue<'cx>>(&self, name: &str) -> Result<Option<T>> {
+    let Some(value) = self.named.as_ref().and_then(|named| named.get(name)) else {
+      return Ok(None);
+    };
+    let value = unsafe { value.bind_raw::<'cx>() };
+    T::from_value(value, self.global()).map(Some)
+  }
+
+  /// Retrieve every argument starting at `n`, for functions which accept
+  /// any number of arguments.
+  pub fn rest_params<T: FromValue<'cx>>(&self, n: usize) -> Result<Vec<T>> {
+    (n..self.args.count).map(|i| self.param(i)).collect()
+  }
+
   // TODO: does this also need to be force-Send?
   pub async fn call<'a>(
     &'a mut self,
@@ -375,9 +528,11 @@ impl<'cx> Scope<'cx> {
     args: Args,
     frame_size: Option<usize>,
   ) -> Scope<'cx> {
-    self
+    let mut scope = self
       .thread
-      .enter_nested_scope(self.stack_base, slot0, args, frame_size)
+      .enter_nested_scope(self.stack_base, slot0, args, frame_size);
+    scope.named = self.named.clone();
+    scope
   }
 
   pub(crate) fn leave(mut self) {
@@ -436,6 +591,21 @@ impl<'cx, T: Send + 'static> This<'cx, T> {
   }
 }
 
+/// Allows native functions to accept instances of native classes as
+/// arguments, for example as the right operand of an operator.
+impl<'cx, T: Send + 'static> FromValue<'cx> for This<'cx, T> {
+  fn from_value(value: Value<'cx>, _: Global<'cx>) -> Result<Self> {
+    let this = value
+      .unbind()
+      .to_object::<NativeClassInstance>()
+      .and_then(This::new);
+    match this {
+      Some(this) => Ok(this),
+      None => fail!("value is not an instance of {}", std::any::type_name::<T>()),
+    }
+  }
+}
+
 impl<'cx, T: Send + 'static> Deref for This<'cx, T> {
   type Target = T;
 
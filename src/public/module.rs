@@ -1,4 +1,5 @@
 use std::any::TypeId;
+use std::cmp::Ordering;
 use std::future;
 use std::future::Future;
 use std::marker::PhantomData;
@@ -11,8 +12,8 @@ use indexmap::IndexMap;
 
 use crate::internal::error::Result;
 use crate::internal::object::native::{
-  AsyncCallback, NativeClassDescriptor, NativeClassInstance, NativeFieldDescriptor,
-  NativeMethodDescriptor, SyncCallback,
+  AsyncCallback, NativeClassDescriptor, NativeClassInstance, NativeEnumDescriptor,
+  NativeFieldDescriptor, NativeMethodDescriptor, SyncCallback,
 };
 use crate::internal::value::Value as OwnedValue;
 use crate::internal::vm::thread::Args;
@@ -31,6 +32,7 @@ impl NativeModule {
         fns: IndexMap::new(),
         async_fns: IndexMap::new(),
         classes: IndexMap::new(),
+        enums: IndexMap::new(),
       },
     }
   }
@@ -41,6 +43,7 @@ pub(crate) struct NativeModuleData {
   pub(crate) fns: IndexMap<StdString, SyncCallback>,
   pub(crate) async_fns: IndexMap<StdString, AsyncCallback>,
   pub(crate) classes: IndexMap<StdString, NativeClassDescriptor>,
+  pub(crate) enums: IndexMap<StdString, NativeEnumDescriptor>,
 }
 
 pub struct NativeModuleBuilder {
@@ -89,6 +92,22 @@ impl NativeModuleBuilder {
     self
   }
 
+  /// Expose the Rust type `T` as an enum. Values of the enum are created
+  /// using [`Global::new_enum_value`][crate::public::Global::new_enum_value],
+  /// and are the same kind of value as those of enums declared in scripts.
+  pub fn enum_<T: 'static>(
+    mut self,
+    name: impl ToString,
+    f: impl Fn(NativeEnumBuilder<T>) -> NativeEnumDescriptor + Send + Sync + 'static,
+  ) -> Self {
+    let name = name.to_string();
+    self
+      .data
+      .enums
+      .insert(name.clone(), f(NativeEnumBuilder::new(name)));
+    self
+  }
+
   pub fn finish(self) -> NativeModule {
     NativeModule {
       data: Arc::new(self.data),
@@ -127,6 +146,7 @@ impl<const HAS_INIT: bool, T: Send + 'static> NativeClassBuilder<HAS_INIT, T> {
         fields: IndexMap::new(),
         methods: IndexMap::new(),
         static_methods: IndexMap::new(),
+        operators: IndexMap::new(),
       },
       ty: PhantomData,
     }
@@ -212,6 +232,346 @@ impl<const HAS_INIT: bool, T: Send + 'static> NativeClassBuilder<HAS_INIT, T> {
       .insert(name.to_string(), NativeMethodDescriptor::Sync(wrap_fn(f)));
     self
   }
+
+  /// Implement `a + b`, where `a` is an instance of this class.
+  pub fn op_add<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__add__", wrap_operator(f))
+  }
+
+  /// Implement `a - b`, where `a` is an instance of this class.
+  pub fn op_sub<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__sub__", wrap_operator(f))
+  }
+
+  /// Implement `a * b`, where `a` is an instance of this class.
+  pub fn op_mul<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__mul__", wrap_operator(f))
+  }
+
+  /// Implement `a / b`, where `a` is an instance of this class.
+  pub fn op_div<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__div__", wrap_operator(f))
+  }
+
+  /// Implement `a % b`, where `a` is an instance of this class.
+  pub fn op_rem<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__rem__", wrap_operator(f))
+  }
+
+  /// Implement `a ** b`, where `a` is an instance of this class.
+  pub fn op_pow<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__pow__", wrap_operator(f))
+  }
+
+  /// Implement `a // b`, where `a` is an instance of this class.
+  pub fn op_floor_div<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__floordiv__", wrap_operator(f))
+  }
+
+  /// Implement `a & b`, where `a` is an instance of this class.
+  pub fn op_bit_and<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__and__", wrap_operator(f))
+  }
+
+  /// Implement `a | b`, where `a` is an instance of this class.
+  pub fn op_bit_or<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__or__", wrap_operator(f))
+  }
+
+  /// Implement `a ^ b`, where `a` is an instance of this class.
+  pub fn op_bit_xor<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__xor__", wrap_operator(f))
+  }
+
+  /// Implement `a << b`, where `a` is an instance of this class.
+  pub fn op_shl<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__lshift__", wrap_operator(f))
+  }
+
+  /// Implement `a >> b`, where `a` is an instance of this class.
+  pub fn op_shr<'cx, V, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__rshift__", wrap_operator(f))
+  }
+
+  /// Implement unary `-a`.
+  pub fn op_neg<'cx, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    R: IntoValue<'cx>,
+  {
+    self.operator("__neg__", wrap_method(f))
+  }
+
+  /// Implement unary `~a`.
+  pub fn op_bit_not<'cx, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    R: IntoValue<'cx>,
+  {
+    self.operator("__invert__", wrap_method(f))
+  }
+
+  /// Implement `a == b` and `a != b`.
+  ///
+  /// If `b` can't be converted to `V`, the values are not equal. Without
+  /// this, instances are only equal to themselves.
+  pub fn op_eq<'cx, V>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> Result<bool> + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+  {
+    self.operator(
+      "__eq__",
+      wrap_operator(move |scope: Scope<'cx>, this, other: Value<'cx>| {
+        match V::from_value(other, scope.global()) {
+          Ok(other) => f(scope, this, other),
+          Err(_) => Ok(false),
+        }
+      }),
+    )
+  }
+
+  /// Implement `<`, `<=`, `>` and `>=`.
+  pub fn op_cmp<'cx, V>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> Result<Ordering> + Send + Sync + 'static,
+  ) -> Self
+  where
+    V: FromValue<'cx>,
+  {
+    self.operator(
+      "__cmp__",
+      wrap_operator(move |scope, this, other| f(scope, this, other).map(|o| o as i32)),
+    )
+  }
+
+  /// Implement `key in a`.
+  pub fn op_contains<'cx, K, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, K) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    K: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__contains__", wrap_operator(f))
+  }
+
+  /// Implement `a[key]`.
+  pub fn index<'cx, K, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, K) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    K: FromValue<'cx>,
+    R: IntoValue<'cx>,
+  {
+    self.operator("__index__", wrap_operator(f))
+  }
+
+  /// Implement `a[key] = value`.
+  pub fn index_mut<'cx, K, V>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>, K, V) -> Result<()> + Send + Sync + 'static,
+  ) -> Self
+  where
+    K: FromValue<'cx>,
+    V: FromValue<'cx>,
+  {
+    self.operator(
+      "__setindex__",
+      wrap_operator(move |scope: Scope<'cx>, this, key| {
+        let value = scope.param::<V>(1)?;
+        f(scope, this, key, value)
+      }),
+    )
+  }
+
+  /// Format instances when they are printed, interpolated into strings,
+  /// or passed to `to_str`.
+  pub fn display<'cx>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>) -> StdString + Send + Sync + 'static,
+  ) -> Self {
+    self.operator("__str__", wrap_method(f))
+  }
+
+  /// Allow instances to be used as table keys which are compared by value.
+  ///
+  /// Two instances of this class are the same key if the values returned
+  /// by `f` are the same key. Without this, instances are compared by
+  /// identity.
+  pub fn hash<'cx, R>(
+    self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    R: IntoValue<'cx>,
+  {
+    self.operator("__hash__", wrap_method(f))
+  }
+
+  /// Make instances iterable, for example in `for` loops. The value returned
+  /// by `f` may be anything which is itself iterable, such as a list.
+  pub fn iter<'cx, R>(
+    mut self,
+    f: impl Fn(Scope<'cx>, This<'cx, T>) -> R + Send + Sync + 'static,
+  ) -> Self
+  where
+    R: IntoValue<'cx>,
+  {
+    let f = wrap_method(f);
+    let iter: SyncCallback = Arc::new(move |scope| {
+      let mut thread = scope.thread.clone();
+      let iterable = f(scope.clone())?;
+      let Some(object) = iterable.clone().to_any() else {
+        fail!("`{iterable}` is not iterable");
+      };
+      let iter = object.named_field(scope.clone(), scope.intern("iter"))?;
+      let Some(iter) = iter.clone().to_any() else {
+        fail!("`{iter}` is not callable");
+      };
+      thread.call_sync(iter, &[])
+    });
+    self
+      .descriptor
+      .methods
+      .insert("iter".to_string(), NativeMethodDescriptor::Sync(iter));
+    self
+  }
+
+  fn operator(mut self, name: &'static str, f: SyncCallback) -> Self {
+    self.descriptor.operators.insert(name, f);
+    self
+  }
+}
+
+pub struct NativeEnumBuilder<T> {
+  descriptor: NativeEnumDescriptor,
+  ty: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> NativeEnumBuilder<T> {
+  pub fn new(name: StdString) -> Self {
+    Self {
+      descriptor: NativeEnumDescriptor {
+        name,
+        type_id: TypeId::of::<T>(),
+        variants: IndexMap::new(),
+      },
+      ty: PhantomData,
+    }
+  }
+
+  pub fn finish(self) -> NativeEnumDescriptor {
+    self.descriptor
+  }
+
+  /// Add a variant without a payload.
+  pub fn unit(self, name: impl ToString) -> Self {
+    self.variant(name, Vec::<StdString>::new())
+  }
+
+  /// Add a variant which carries a value for each of `fields`.
+  pub fn variant(
+    mut self,
+    name: impl ToString,
+    fields: impl IntoIterator<Item = impl ToString>,
+  ) -> Self {
+    let fields = fields.into_iter().map(|field| field.to_string()).collect();
+    self.descriptor.variants.insert(name.to_string(), fields);
+    self
+  }
 }
 
 fn wrap_fn<'cx, R>(f: impl Fn(Scope<'cx>) -> R + Send + Sync + 'static) -> SyncCallback
@@ -267,6 +627,7 @@ fn extract_this<T: Send + 'static>(scope: Scope<'_>) -> Result<(Scope<'_>, This<
       count: scope.args.count - 1,
     },
     stack_base: scope.stack_base,
+    named: scope.named,
     lifetime: PhantomData,
   };
   Ok((scope, this))
@@ -312,6 +673,26 @@ where
   })
 }
 
+/// Wrap a method which takes one argument besides the receiver.
+fn wrap_operator<'cx, T: Send + 'static, V, R>(
+  f: impl Fn(Scope<'cx>, This<'cx, T>, V) -> R + Send + Sync + 'static,
+) -> SyncCallback
+where
+  V: FromValue<'cx>,
+  R: IntoValue<'cx>,
+{
+  Arc::new(move |scope| {
+    let (scope, this) = extract_this::<T>(scope)?;
+    let (scope, this) =
+      unsafe { transmute::<(Scope<'_>, This<'_, T>), (Scope<'cx>, This<'cx, T>)>((scope, this)) };
+    let other = scope.param::<V>(0)?;
+    let global = scope.global();
+    f(scope, this, other)
+      .into_value(global)
+      .map(|value| value.unbind())
+  })
+}
+
 fn wrap_getter<'cx, T: Send + 'static, R>(
   f: impl Fn(Scope<'cx>, This<'cx, T>) -> R + Send + Sync + 'static,
 ) -> SyncCallback
@@ -1,7 +1,12 @@
+pub mod bytes;
+pub mod enum_;
 pub mod function;
+pub mod generator;
 pub mod list;
+pub mod set;
 pub mod string;
 pub mod table;
+pub mod tuple;
 
 use std::fmt::{Debug, Display};
 
@@ -0,0 +1,44 @@
+use super::*;
+use crate::internal::object::{Bytes as OwnedBytes, Ptr};
+use crate::public::{Hebi, Scope};
+
+decl_ref! {
+  struct Bytes(Ptr<OwnedBytes>)
+}
+
+impl_object_ref!(Bytes, OwnedBytes);
+
+impl<'cx> Bytes<'cx> {
+  pub fn len(&self) -> usize {
+    self.inner.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.inner.is_empty()
+  }
+
+  pub fn as_bytes(&self) -> &[u8] {
+    self.inner.as_slice()
+  }
+}
+
+impl<'cx> Global<'cx> {
+  pub fn new_bytes(&self, v: impl Into<Vec<u8>>) -> Bytes<'cx> {
+    self
+      .inner
+      .alloc(OwnedBytes::from(v.into()))
+      .bind(self.clone())
+  }
+}
+
+impl<'cx> Scope<'cx> {
+  pub fn new_bytes(&self, v: impl Into<Vec<u8>>) -> Bytes<'cx> {
+    self.global().new_bytes(v)
+  }
+}
+
+impl Hebi {
+  pub fn new_bytes(&self, v: impl Into<Vec<u8>>) -> Bytes<'_> {
+    self.global().new_bytes(v)
+  }
+}
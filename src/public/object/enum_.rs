@@ -0,0 +1,102 @@
+use super::*;
+use crate::internal::error::Result;
+use crate::internal::object::{EnumValue as OwnedEnumValue, Ptr};
+use crate::public::{Hebi, Scope, Unbind, Value};
+
+decl_ref! {
+  struct EnumValue(Ptr<OwnedEnumValue>)
+}
+
+impl_object_ref!(EnumValue, OwnedEnumValue);
+
+impl<'cx> EnumValue<'cx> {
+  /// Name of the enum which this value belongs to.
+  pub fn enum_name(&self) -> &str {
+    self.inner.ty.name().as_str()
+  }
+
+  /// Name of the variant.
+  pub fn variant(&self) -> &str {
+    self.inner.variant_name().as_str()
+  }
+
+  /// Whether this is a value of the enum registered for `T`.
+  pub fn is<T: 'static>(&self, global: Global<'cx>) -> bool {
+    match global.inner.get_enum::<T>() {
+      Some(ty) => self.inner.ty.ptr_eq(&ty),
+      None => false,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.inner.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.inner.is_empty()
+  }
+
+  /// Retrieve the value at `index` in the variant's payload.
+  pub fn get(&self, index: usize) -> Option<Value<'cx>> {
+    self
+      .inner
+      .get(index)
+      .map(|value| unsafe { value.bind_raw::<'cx>() })
+  }
+
+  /// Retrieve the payload field called `name`.
+  pub fn field(&self, name: &str) -> Option<Value<'cx>> {
+    self
+      .inner
+      .field(name)
+      .map(|value| unsafe { value.bind_raw::<'cx>() })
+  }
+}
+
+impl<'cx> Global<'cx> {
+  /// Create a value of the enum registered for `T` through
+  /// [`NativeModuleBuilder::enum_`][crate::public::module::NativeModuleBuilder::enum_].
+  pub fn new_enum_value<T: 'static>(
+    &self,
+    variant: &str,
+    values: impl IntoIterator<Item = Value<'cx>>,
+  ) -> Result<EnumValue<'cx>> {
+    let Some(ty) = self.inner.get_enum::<T>() else {
+      fail!("`{}` is not a registered enum", std::any::type_name::<T>());
+    };
+    let Some(index) = ty.variant_index(variant) else {
+      fail!("enum `{}` has no variant `{variant}`", ty.name());
+    };
+    let values = values.into_iter().map(|v| v.unbind()).collect::<Vec<_>>();
+    let expected = ty.variant_fields(index).len();
+    if values.len() != expected {
+      fail!(
+        "`{}.{variant}` expected {expected} values, got {}",
+        ty.name(),
+        values.len()
+      );
+    }
+    let value = self.inner.alloc(OwnedEnumValue::new(ty, index, values));
+    Ok(value.bind(self.clone()))
+  }
+}
+
+impl<'cx> Scope<'cx> {
+  pub fn new_enum_value<T: 'static>(
+    &self,
+    variant: &str,
+    values: impl IntoIterator<Item = Value<'cx>>,
+  ) -> Result<EnumValue<'cx>> {
+    self.global().new_enum_value::<T>(variant, values)
+  }
+}
+
+impl Hebi {
+  pub fn new_enum_value<'cx, T: 'static>(
+    &'cx self,
+    variant: &str,
+    values: impl IntoIterator<Item = Value<'cx>>,
+  ) -> Result<EnumValue<'cx>> {
+    self.global().new_enum_value::<T>(variant, values)
+  }
+}
@@ -0,0 +1,58 @@
+use std::ptr::NonNull;
+
+use super::*;
+use crate::internal::error::Result;
+use crate::internal::object::function::Generator as OwnedGenerator;
+use crate::internal::object::Ptr;
+use crate::internal::vm::thread::{Stack, Thread};
+use crate::public::Value;
+
+decl_ref! {
+  struct Generator(Ptr<OwnedGenerator>)
+}
+
+impl_object_ref!(Generator, OwnedGenerator);
+
+impl<'cx> Generator<'cx> {
+  /// Whether the generator has returned, or was stopped by an error.
+  pub fn is_done(&self) -> bool {
+    self.inner.is_done()
+  }
+
+  /// Run the generator until its next `yield`, returning the yielded value,
+  /// or `None` if it returned instead.
+  ///
+  /// Like [`Hebi::eval`][crate::Hebi::eval], every call to `resume` gets the
+  /// full configured timeout.
+  pub fn resume(&self, global: Global<'cx>) -> Result<Option<Value<'cx>>> {
+    global.inner.start_deadline();
+    // the generator's frame is self-contained, so it can run on its own stack
+    let mut stack = Stack::new();
+    let mut thread = Thread::new(global.inner.clone(), NonNull::from(&mut stack));
+    let value = pollster::block_on(OwnedGenerator::next(self.inner.clone(), &mut thread))?;
+    Ok(value.map(|value| unsafe { value.bind_raw::<'cx>() }))
+  }
+
+  /// Iterate over the values yielded by the generator.
+  ///
+  /// The iterator stops after the generator returns or fails.
+  pub fn iter(&self, global: Global<'cx>) -> Iter<'cx> {
+    Iter {
+      generator: self.clone(),
+      global,
+    }
+  }
+}
+
+pub struct Iter<'cx> {
+  generator: Generator<'cx>,
+  global: Global<'cx>,
+}
+
+impl<'cx> Iterator for Iter<'cx> {
+  type Item = Result<Value<'cx>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.generator.resume(self.global.clone()).transpose()
+  }
+}
@@ -20,14 +20,14 @@ impl<'cx> List<'cx> {
   }
 
   pub fn push(&self, value: Value<'cx>) {
+    self.inner.charge(OwnedList::ELEMENT_SIZE);
     self.inner.push(value.unbind());
   }
 
   pub fn pop(&self) -> Option<Value<'cx>> {
-    self
-      .inner
-      .pop()
-      .map(|value| unsafe { value.bind_raw::<'cx>() })
+    let value = self.inner.pop()?;
+    self.inner.release(OwnedList::ELEMENT_SIZE);
+    Some(unsafe { value.bind_raw::<'cx>() })
   }
 
   pub fn get(&self, index: usize) -> Option<Value<'cx>> {
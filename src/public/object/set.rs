@@ -0,0 +1,89 @@
+use std::marker::PhantomData;
+
+use super::*;
+use crate::internal::error::Result;
+use crate::internal::object::table::Key;
+use crate::internal::object::{set, Ptr, Set as OwnedSet};
+use crate::public::{Hebi, Scope, Unbind, Value};
+
+decl_ref! {
+  struct Set(Ptr<OwnedSet>)
+}
+
+impl_object_ref!(Set, OwnedSet);
+
+impl<'cx> Set<'cx> {
+  pub fn len(&self) -> usize {
+    self.inner.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.inner.is_empty()
+  }
+
+  /// Returns `false` if the set already contained `value`.
+  ///
+  /// Fails if `value` is not hashable. Instances of classes are compared by
+  /// identity, because their `__hash__` hooks are not called here.
+  pub fn insert(&self, value: Value<'cx>) -> Result<bool> {
+    let inserted = self.inner.insert(Key::plain(value.unbind())?);
+    if inserted {
+      self.inner.charge(OwnedSet::ELEMENT_SIZE);
+    }
+    Ok(inserted)
+  }
+
+  /// Returns `false` if the set did not contain `value`.
+  pub fn remove(&self, value: Value<'cx>) -> Result<bool> {
+    let removed = self.inner.remove(&Key::plain(value.unbind())?);
+    if removed {
+      self.inner.release(OwnedSet::ELEMENT_SIZE);
+    }
+    Ok(removed)
+  }
+
+  pub fn contains(&self, value: Value<'cx>) -> Result<bool> {
+    Ok(self.inner.contains_key(&Key::plain(value.unbind())?))
+  }
+
+  pub fn iter<'a>(&'a self) -> Iter<'a, 'cx> {
+    Iter {
+      inner: self.inner.iter(),
+      lifetime: PhantomData,
+    }
+  }
+}
+
+pub struct Iter<'a, 'cx> {
+  inner: set::Iter<'a>,
+  lifetime: PhantomData<&'cx ()>,
+}
+
+impl<'a, 'cx> Iterator for Iter<'a, 'cx> {
+  type Item = Value<'cx>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|v| unsafe { v.bind_raw::<'cx>() })
+  }
+}
+
+impl<'cx> Global<'cx> {
+  pub fn new_set(&self, capacity: usize) -> Set<'cx> {
+    self
+      .inner
+      .alloc(OwnedSet::with_capacity(capacity))
+      .bind(self.clone())
+  }
+}
+
+impl<'cx> Scope<'cx> {
+  pub fn new_set(&self, capacity: usize) -> Set<'cx> {
+    self.global().new_set(capacity)
+  }
+}
+
+impl Hebi {
+  pub fn new_set(&self, capacity: usize) -> Set<'_> {
+    self.global().new_set(capacity)
+  }
+}
@@ -20,10 +20,11 @@ impl<'cx> Table<'cx> {
   }
 
   pub fn insert(&self, key: Str<'cx>, value: Value<'cx>) -> Option<Value<'cx>> {
-    self
-      .inner
-      .insert(key.unbind(), value.unbind())
-      .map(|v| unsafe { v.bind_raw::<'cx>() })
+    let old = self.inner.insert(key.unbind(), value.unbind());
+    if old.is_none() {
+      self.inner.charge(OwnedTable::ENTRY_SIZE);
+    }
+    old.map(|v| unsafe { v.bind_raw::<'cx>() })
   }
 
   pub fn get(&self, key: &str) -> Option<Value<'cx>> {
@@ -58,10 +59,13 @@ pub struct Keys<'a, 'cx> {
 }
 
 impl<'a, 'cx> Iterator for Keys<'a, 'cx> {
-  type Item = Str<'cx>;
+  type Item = Value<'cx>;
 
   fn next(&mut self) -> Option<Self::Item> {
-    self.inner.next().map(|v| unsafe { v.bind_raw::<'cx>() })
+    self
+      .inner
+      .next()
+      .map(|v| unsafe { v.into_value().bind_raw::<'cx>() })
   }
 }
 
@@ -84,13 +88,13 @@ pub struct Entries<'a, 'cx> {
 }
 
 impl<'a, 'cx> Iterator for Entries<'a, 'cx> {
-  type Item = (Str<'cx>, Value<'cx>);
+  type Item = (Value<'cx>, Value<'cx>);
 
   fn next(&mut self) -> Option<Self::Item> {
     self
       .inner
       .next()
-      .map(|(key, value)| unsafe { (key.bind_raw::<'cx>(), value.bind_raw::<'cx>()) })
+      .map(|(key, value)| unsafe { (key.into_value().bind_raw::<'cx>(), value.bind_raw::<'cx>()) })
   }
 }
 
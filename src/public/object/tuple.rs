@@ -0,0 +1,67 @@
+use std::marker::PhantomData;
+
+use super::*;
+use crate::internal::object::{tuple, Ptr, Tuple as OwnedTuple};
+use crate::public::{Hebi, Scope, Unbind, Value};
+
+decl_ref! {
+  struct Tuple(Ptr<OwnedTuple>)
+}
+
+impl_object_ref!(Tuple, OwnedTuple);
+
+impl<'cx> Tuple<'cx> {
+  pub fn len(&self) -> usize {
+    self.inner.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.inner.is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<Value<'cx>> {
+    self
+      .inner
+      .get(index)
+      .map(|value| unsafe { value.bind_raw::<'cx>() })
+  }
+
+  pub fn iter<'a>(&'a self) -> Iter<'a, 'cx> {
+    Iter {
+      inner: self.inner.iter(),
+      lifetime: PhantomData,
+    }
+  }
+}
+
+pub struct Iter<'a, 'cx> {
+  inner: tuple::Iter<'a>,
+  lifetime: PhantomData<&'cx ()>,
+}
+
+impl<'a, 'cx> Iterator for Iter<'a, 'cx> {
+  type Item = Value<'cx>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|v| unsafe { v.bind_raw::<'cx>() })
+  }
+}
+
+impl<'cx> Global<'cx> {
+  pub fn new_tuple(&self, items: impl IntoIterator<Item = Value<'cx>>) -> Tuple<'cx> {
+    let items = items.into_iter().map(|v| v.unbind()).collect::<Vec<_>>();
+    self.inner.alloc(OwnedTuple::from(items)).bind(self.clone())
+  }
+}
+
+impl<'cx> Scope<'cx> {
+  pub fn new_tuple(&self, items: impl IntoIterator<Item = Value<'cx>>) -> Tuple<'cx> {
+    self.global().new_tuple(items)
+  }
+}
+
+impl Hebi {
+  pub fn new_tuple<'cx>(&'cx self, items: impl IntoIterator<Item = Value<'cx>>) -> Tuple<'cx> {
+    self.global().new_tuple(items)
+  }
+}
@@ -1,3 +1,8 @@
+use std::collections::{BTreeSet, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+use super::object::set::Set;
+use super::object::tuple::Tuple;
 use super::object::{Any, ObjectRef};
 use crate::internal::error::Result;
 use crate::internal::{object, value};
@@ -20,8 +25,20 @@ impl<'cx> Value<'cx> {
     self.inner.clone().to_int()
   }
 
+  /// Returns the value of an int if it fits into an `i64`.
+  ///
+  /// Unlike [`Value::as_int`], this also accepts boxed ints.
+  pub fn as_i64(&self) -> Option<i64> {
+    object::int::to_i64(&self.inner)
+  }
+
+  /// Returns the value of an int if it fits into an `i128`.
+  pub fn as_i128(&self) -> Option<i128> {
+    object::int::to_i128(&self.inner)
+  }
+
   pub fn is_int(&self) -> bool {
-    self.inner.is_int()
+    object::int::is_int(&self.inner)
   }
 
   pub fn as_bool(&self) -> Option<bool> {
@@ -91,6 +108,32 @@ impl<'cx> FromValue<'cx> for i32 {
   }
 }
 
+macro_rules! impl_wide_int_value {
+  ($($T:ty),*) => {
+    $(
+      impl<'cx> IntoValue<'cx> for $T {
+        fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
+          Ok(object::int::from_i128(&global.inner, self as i128).bind(global))
+        }
+      }
+
+      impl<'cx> FromValue<'cx> for $T {
+        fn from_value(value: Value<'cx>, _: Global<'cx>) -> Result<Self> {
+          if !value.is_int() {
+            crate::fail!("value is not an int");
+          }
+          match value.as_i128().and_then(|v| <$T>::try_from(v).ok()) {
+            Some(v) => Ok(v),
+            None => crate::fail!("`{}` does not fit into `{}`", value.inner, stringify!($T)),
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_wide_int_value!(i64, u64, usize, i128);
+
 impl<'cx> IntoValue<'cx> for f64 {
   fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
     Ok(value::Value::float(self).bind(global))
@@ -210,6 +253,150 @@ impl<'cx> IntoValue<'cx> for String {
   }
 }
 
+/// Copies the bytes out of the value.
+///
+/// There is no `FromValue` for `&[u8]`, because the value which owns the bytes
+/// is dropped once it has been converted. To borrow them without copying, take
+/// a [`Bytes`][crate::public::Bytes] and use
+/// [`Bytes::as_bytes`][crate::public::Bytes::as_bytes].
+impl<'cx> FromValue<'cx> for Vec<u8> {
+  fn from_value(value: Value<'cx>, _: Global<'cx>) -> Result<Self> {
+    let Some(bytes) = value.unbind().to_object::<object::Bytes>() else {
+      fail!("value is not bytes")
+    };
+    Ok(bytes.as_slice().to_vec())
+  }
+}
+
+impl<'cx> IntoValue<'cx> for Vec<u8> {
+  fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
+    global.new_bytes(self).into_value(global)
+  }
+}
+
+impl<'cx> IntoValue<'cx> for &[u8] {
+  fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
+    global.new_bytes(self).into_value(global)
+  }
+}
+
+impl<'cx, T, S> IntoValue<'cx> for HashSet<T, S>
+where
+  T: IntoValue<'cx>,
+{
+  fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
+    let set = global.new_set(self.len());
+    for item in self {
+      set.insert(item.into_value(global.clone())?)?;
+    }
+    set.into_value(global)
+  }
+}
+
+impl<'cx, T, S> FromValue<'cx> for HashSet<T, S>
+where
+  T: FromValue<'cx> + Eq + Hash,
+  S: BuildHasher + Default,
+{
+  fn from_value(value: Value<'cx>, global: Global<'cx>) -> Result<Self> {
+    let Some(set) = value.as_object::<Set<'cx>>(global.clone()) else {
+      fail!("value is not a set");
+    };
+    set
+      .iter()
+      .map(|item| T::from_value(item, global.clone()))
+      .collect()
+  }
+}
+
+impl<'cx, T> IntoValue<'cx> for BTreeSet<T>
+where
+  T: IntoValue<'cx>,
+{
+  fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
+    let set = global.new_set(self.len());
+    for item in self {
+      set.insert(item.into_value(global.clone())?)?;
+    }
+    set.into_value(global)
+  }
+}
+
+impl<'cx, T> FromValue<'cx> for BTreeSet<T>
+where
+  T: FromValue<'cx> + Ord,
+{
+  fn from_value(value: Value<'cx>, global: Global<'cx>) -> Result<Self> {
+    let Some(set) = value.as_object::<Set<'cx>>(global.clone()) else {
+      fail!("value is not a set");
+    };
+    set
+      .iter()
+      .map(|item| T::from_value(item, global.clone()))
+      .collect()
+  }
+}
+
+macro_rules! impl_tuple_value {
+  ($($T:ident),*) => {
+    impl<'cx, $($T),*> IntoValue<'cx> for ($($T,)*)
+    where
+      $(
+        $T: IntoValue<'cx>,
+      )*
+    {
+      #[allow(non_snake_case)]
+      fn into_value(self, global: Global<'cx>) -> Result<Value<'cx>> {
+        let ($($T,)*) = self;
+        let items = [$($T.into_value(global.clone())?),*];
+        global.new_tuple(items).into_value(global)
+      }
+    }
+
+    impl<'cx, $($T),*> FromValue<'cx> for ($($T,)*)
+    where
+      $(
+        $T: FromValue<'cx>,
+      )*
+    {
+      #[allow(non_snake_case)]
+      fn from_value(value: Value<'cx>, global: Global<'cx>) -> Result<Self> {
+        let Some(tuple) = value.as_object::<Tuple<'cx>>(global.clone()) else {
+          fail!("value is not a tuple");
+        };
+        let len = tuple.len();
+        let expected_len = __count!($($T)*);
+        if len != expected_len {
+          fail!("expected a tuple of length {expected_len}, got {len}");
+        }
+
+        let mut offset = 0;
+        $(
+          let $T = tuple.get(offset).unwrap();
+          let $T = <$T>::from_value($T, global.clone())?;
+          offset += 1;
+        )*
+        let _ = offset;
+
+        Ok(($($T,)*))
+      }
+    }
+  };
+}
+
+impl_tuple_value!(A);
+impl_tuple_value!(A, B);
+impl_tuple_value!(A, B, C);
+impl_tuple_value!(A, B, C, D);
+impl_tuple_value!(A, B, C, D, E);
+impl_tuple_value!(A, B, C, D, E, F);
+impl_tuple_value!(A, B, C, D, E, F, G);
+impl_tuple_value!(A, B, C, D, E, F, G, H);
+impl_tuple_value!(A, B, C, D, E, F, G, H, I);
+impl_tuple_value!(A, B, C, D, E, F, G, H, I, J);
+impl_tuple_value!(A, B, C, D, E, F, G, H, I, J, K);
+impl_tuple_value!(A, B, C, D, E, F, G, H, I, J, K, L);
+
 pub trait FromValuePack<'cx> {
   type Output: Sized;
   fn from_value_pack(args: &[value::Value], global: Global<'cx>) -> Result<Self::Output>;
@@ -78,6 +78,60 @@ impl Index<Span> for str {
   }
 }
 
+/// Identifies a [`Source`] created by the VM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SourceId(pub(crate) u32);
+
+impl Display for SourceId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+/// A named source string, such as an imported module or a snippet of code
+/// passed to `eval`.
+///
+/// Every piece of code compiled by the VM is wrapped in a source, so that
+/// spans in errors may be traced back to the code they point into.
+#[derive(Debug)]
+pub struct Source {
+  id: SourceId,
+  name: String,
+  text: String,
+}
+
+impl Source {
+  pub(crate) fn new(id: SourceId, name: impl ToString, text: impl ToString) -> Self {
+    Self {
+      id,
+      name: name.to_string(),
+      text: text.to_string(),
+    }
+  }
+
+  pub fn id(&self) -> SourceId {
+    self.id
+  }
+
+  /// The module path, or a placeholder such as `<eval>` for code which
+  /// did not come from a module.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// The 1-based line number of the byte at `offset`.
+  pub fn line_of(&self, offset: usize) -> usize {
+    self.text[..offset.min(self.text.len())]
+      .matches('\n')
+      .count()
+      + 1
+  }
+}
+
 /// Represents a value, and its span in the source string from which it was
 /// parsed. This allows tracing syntax nodes back to their location in the
 /// source string.